@@ -0,0 +1,88 @@
+//! Benchmarks of parsing, formatting, comparison and reciprocals across magnitude classes.
+//!
+//! Run with `--features bench-internals` and `FAST_DECIMAL_BENCH_COUNTERS` set to also print
+//! how often each internal path was hit.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use fast_decimal::Decimal;
+
+/// Representative literals of each magnitude class.
+const CLASSES: [(&str, &str); 5] = [
+    ("zero", "0"),
+    ("small", "1.5"),
+    ("medium", "123456789.123456789"),
+    ("large", "123456789012345678901234567890.123456"),
+    ("tiny", "0.000000000000000000000000000000000000000000000000001234"),
+];
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, s) in CLASSES.iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), s, |b, s| {
+            b.iter(|| black_box(s).parse::<Decimal>().unwrap())
+        });
+    }
+    group.finish();
+    report();
+}
+
+fn format(c: &mut Criterion) {
+    let mut group = c.benchmark_group("format");
+    for (name, s) in CLASSES.iter() {
+        let d = s.parse::<Decimal>().unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &d, |b, d| {
+            b.iter(|| black_box(d).to_string())
+        });
+    }
+    group.finish();
+    report();
+}
+
+fn cmp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cmp");
+    for (name, s) in CLASSES.iter() {
+        let a = s.parse::<Decimal>().unwrap();
+        // Differs from `a` only in the last digit, so the comparison reaches the limbs.
+        let b = a
+            .checked_add(&Decimal::from_coefficient_exponent(1, -a.scale()).unwrap())
+            .unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &(a, b), |bench, (a, b)| {
+            bench.iter(|| black_box(a).cmp(black_box(b)))
+        });
+    }
+    group.finish();
+    report();
+}
+
+fn recip(c: &mut Criterion) {
+    let mut group = c.benchmark_group("recip");
+    for (name, s) in CLASSES.iter() {
+        let d = s.parse::<Decimal>().unwrap();
+        // Zero and tiny values have no reciprocal in range.
+        if d.checked_recip(None).is_none() {
+            continue;
+        }
+        group.bench_with_input(BenchmarkId::new("recip", name), &d, |b, d| {
+            b.iter(|| black_box(d).recip(None))
+        });
+        group.bench_with_input(BenchmarkId::new("div", name), &d, |b, d| {
+            b.iter(|| Decimal::ONE / *black_box(d))
+        });
+    }
+    group.finish();
+    report();
+}
+
+#[cfg(feature = "bench-internals")]
+fn report() {
+    if std::env::var_os("FAST_DECIMAL_BENCH_COUNTERS").is_some() {
+        eprintln!("{:?}", fast_decimal::bench_internals::counters());
+    }
+    fast_decimal::bench_internals::reset();
+}
+
+#[cfg(not(feature = "bench-internals"))]
+fn report() {}
+
+criterion_group!(benches, parse, format, cmp, recip);
+criterion_main!(benches);
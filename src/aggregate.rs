@@ -0,0 +1,415 @@
+//! Aggregations over many decimals.
+
+use crate::decimal::Decimal;
+use crate::error::DecimalParseError;
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+/// Returns the weighted mean `sum(values[i] * weights[i]) / sum(weights)` rounded to `scale`
+/// digits after the decimal point.
+///
+/// Products and sums are accumulated at extended precision, so the result is rounded only once.
+/// NaN in any input gives the first NaN. Returns `None` if the weights sum to zero or the result overflows.
+///
+/// # Panics
+///
+/// Panics if `values` and `weights` have different lengths.
+pub fn weighted_mean(
+    values: &[Decimal],
+    weights: &[Decimal],
+    scale: i32,
+    strategy: RoundingStrategy,
+) -> Option<Decimal> {
+    assert_eq!(values.len(), weights.len(), "values and weights have different lengths");
+
+    let mut sum = Var::ZERO;
+    let mut total_weight = Var::ZERO;
+    for (value, weight) in values.iter().zip(weights) {
+        let weight = Var::from_decimal(weight);
+        sum = sum.add(&Var::from_decimal(value).mul(&weight));
+        total_weight = total_weight.add(&weight);
+    }
+
+    if let Some(nan) = sum.nan_operand(&total_weight) {
+        return nan.into_decimal(strategy).map(|(d, _)| d);
+    }
+    if total_weight.is_zero() {
+        return None;
+    }
+
+    let (mean, _) = sum.div_round(&total_weight, scale, strategy);
+    mean.into_decimal(strategy).map(|(d, _)| d)
+}
+
+/// Returns the mean of the values rounded half up to `scale` digits after the decimal point.
+///
+/// The values are summed in an [`Accumulator`] and divided once, so the sum doesn't overflow
+/// even for billions of values near the maximum precision. NaN in the values gives the first NaN.
+/// Returns `None` if there are no values or the mean overflows.
+pub fn mean_with_scale<I>(iter: I, scale: i32) -> Option<Decimal>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Decimal>,
+{
+    let mut acc = Accumulator::new();
+    for value in iter {
+        acc.add(value.borrow());
+    }
+    acc.mean(scale, RoundingStrategy::HalfUp)
+}
+
+/// Returns the sum of the values, rounded half up only once to fit in a decimal.
+///
+/// The values are summed exactly in an [`Accumulator`], so no low-order digit is lost however
+/// many values there are, while [`Sum`](std::iter::Sum) rounds every partial sum. Partial sums
+/// may exceed the range of a decimal as long as the total fits. NaN in the values gives the
+/// first NaN, and no values give zero.
+///
+/// # Panics
+///
+/// Panics if the sum overflows.
+pub fn sum_exact(values: &[Decimal]) -> Decimal {
+    let mut acc = Accumulator::new();
+    for value in values {
+        acc.add(value);
+    }
+    acc.sum().expect("overflow when summing decimals")
+}
+
+/// An exact sum of decimals with a width far beyond a decimal.
+///
+/// Sums that don't fit in a decimal are kept exactly, so only the final result is rounded.
+#[derive(Clone, Copy)]
+pub struct Accumulator {
+    sum: Var,
+    count: u64,
+}
+
+impl Accumulator {
+    /// Creates an empty accumulator.
+    #[inline]
+    pub const fn new() -> Accumulator {
+        Accumulator {
+            sum: Var::ZERO,
+            count: 0,
+        }
+    }
+
+    /// Adds a value.
+    #[inline]
+    pub fn add(&mut self, value: &Decimal) {
+        self.sum = self.sum.add(&Var::from_decimal(value));
+        self.count += 1;
+    }
+
+    /// Returns the number of values added.
+    #[inline]
+    pub const fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Returns the sum rounded half up to fit in a decimal, or `None` if it overflows.
+    #[inline]
+    pub fn sum(&self) -> Option<Decimal> {
+        self.sum.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d)
+    }
+
+    /// Returns the mean rounded to `scale` digits after the decimal point.
+    ///
+    /// Returns `None` if no value was added or the mean overflows.
+    pub fn mean(&self, scale: i32, strategy: RoundingStrategy) -> Option<Decimal> {
+        if self.count == 0 {
+            return None;
+        }
+        if self.sum.is_nan() {
+            return self.sum.into_decimal(strategy).map(|(d, _)| d);
+        }
+
+        let count = Var::from_decimal(&Decimal::from_u128_exact(self.count as u128));
+        let (mean, _) = self.sum.div_round(&count, scale, strategy);
+        mean.into_decimal(strategy).map(|(d, _)| d)
+    }
+}
+
+impl Default for Accumulator {
+    #[inline]
+    fn default() -> Self {
+        Accumulator::new()
+    }
+}
+
+impl fmt::Debug for Accumulator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Accumulator")
+            .field("sum", &self.sum())
+            .field("count", &self.count)
+            .finish()
+    }
+}
+
+impl Decimal {
+    /// Adds the values in order as [`Sum`](std::iter::Sum), returning `None` instead of
+    /// panicking if a partial sum overflows. An empty iterator gives zero.
+    ///
+    /// Digits that don't fit are rounded as by [`Add`](std::ops::Add), use an [`Accumulator`]
+    /// to round only once.
+    pub fn checked_sum<I>(iter: I) -> Option<Decimal>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Decimal>,
+    {
+        iter.into_iter()
+            .try_fold(Decimal::ZERO, |sum, value| sum.checked_add(value.borrow()))
+    }
+
+    /// Multiplies the values in order as [`Product`](std::iter::Product), returning `None`
+    /// instead of panicking if a partial product overflows. An empty iterator gives one.
+    pub fn checked_product<I>(iter: I) -> Option<Decimal>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<Decimal>,
+    {
+        iter.into_iter()
+            .try_fold(Decimal::ONE, |product, value| product.checked_mul(value.borrow()))
+    }
+
+    /// Parses and sums decimal literals in one pass, without collecting the parsed values.
+    ///
+    /// The sum is accumulated at extended precision and rounded once at the end. Returns the first
+    /// parse error, or [`DecimalParseError::Overflow`] if the sum doesn't fit in a decimal.
+    pub fn sum_strs<'a, I: IntoIterator<Item = &'a str>>(iter: I) -> Result<Decimal, DecimalParseError> {
+        let mut sum = Var::ZERO;
+        for s in iter {
+            let value = s.parse::<Decimal>()?;
+            sum = sum.add(&Var::from_decimal(&value));
+        }
+
+        match sum.into_decimal(RoundingStrategy::HalfUp) {
+            Some((d, _)) => Ok(d),
+            None => Err(DecimalParseError::Overflow),
+        }
+    }
+}
+
+/// Sums decimals per key, with each group accumulated exactly at extended precision.
+///
+/// Accumulators are stored inline in the hash table, so adding values never allocates except
+/// when a new group is inserted.
+#[derive(Clone)]
+pub struct GroupedSum<K> {
+    groups: HashMap<K, Var>,
+}
+
+impl<K: Hash + Eq> GroupedSum<K> {
+    /// Creates an empty aggregation.
+    #[inline]
+    pub fn new() -> Self {
+        GroupedSum { groups: HashMap::new() }
+    }
+
+    /// Creates an empty aggregation with room for `capacity` groups.
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        GroupedSum {
+            groups: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Adds `value` to the group of `key`.
+    #[inline]
+    pub fn add(&mut self, key: K, value: &Decimal) {
+        let sum = self.groups.entry(key).or_insert(Var::ZERO);
+        *sum = sum.add(&Var::from_decimal(value));
+    }
+
+    /// Returns the number of groups.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns `true` if there are no groups.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Returns the sum of the group of `key` rounded half up to fit in a decimal, or `None` if
+    /// there is no such group or the sum overflows, as [`Accumulator::sum`].
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<Decimal>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.groups.get(key).and_then(to_sum)
+    }
+
+    /// Returns an iterator over the groups and their sums in arbitrary order, with `None` for
+    /// sums that overflow.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&K, Option<Decimal>)> + '_ {
+        self.groups.iter().map(|(k, sum)| (k, to_sum(sum)))
+    }
+}
+
+impl<K: Hash + Eq> Default for GroupedSum<K> {
+    #[inline]
+    fn default() -> Self {
+        GroupedSum::new()
+    }
+}
+
+#[inline]
+fn to_sum(sum: &Var) -> Option<Decimal> {
+    sum.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mean_with_scale, sum_exact, weighted_mean, Accumulator, GroupedSum};
+    use crate::{Decimal, DecimalParseError, RoundingStrategy};
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn decs(values: &[&str]) -> Vec<Decimal> {
+        values.iter().map(|s| dec(s)).collect()
+    }
+
+    #[test]
+    fn weighted_means() {
+        let prices = decs(&["10.00", "10.50", "11.25"]);
+        let volumes = decs(&["100", "200", "300"]);
+        let vwap = weighted_mean(&prices, &volumes, 4, RoundingStrategy::HalfEven);
+        assert_eq!(vwap.unwrap().to_string(), "10.7917");
+        let vwap = weighted_mean(&prices, &volumes, 2, RoundingStrategy::Down);
+        assert_eq!(vwap.unwrap().to_string(), "10.79");
+
+        // The weights sum to zero, or a product is beyond `MAX` but the mean fits.
+        assert_eq!(
+            weighted_mean(&decs(&["1", "2"]), &decs(&["1", "-1"]), 2, RoundingStrategy::HalfUp),
+            None
+        );
+        let mean = weighted_mean(&[Decimal::MAX], &decs(&["10"]), 0, RoundingStrategy::HalfUp);
+        assert_eq!(mean, Some(Decimal::MAX));
+        assert_eq!(weighted_mean(&[], &[], 2, RoundingStrategy::HalfUp), None);
+        let nan = weighted_mean(
+            &decs(&["1", "NaN3"]),
+            &decs(&["NaN4", "1"]),
+            2,
+            RoundingStrategy::HalfUp,
+        );
+        assert_eq!(nan.unwrap().to_string(), "NaN4");
+    }
+
+    #[test]
+    #[should_panic(expected = "values and weights have different lengths")]
+    fn weighted_mean_length_mismatch() {
+        weighted_mean(&decs(&["1"]), &[], 2, RoundingStrategy::HalfUp);
+    }
+
+    #[test]
+    fn sum_strs() {
+        assert_eq!(Decimal::sum_strs(["1.5", "2.25", "-0.75"]).unwrap().to_string(), "3.00");
+        assert_eq!(Decimal::sum_strs([]).unwrap(), Decimal::ZERO);
+        assert!(Decimal::sum_strs(["1", "x"]).is_err());
+        let max = Decimal::MAX.to_string();
+        assert_eq!(
+            Decimal::sum_strs([max.as_str(), max.as_str()]),
+            Err(DecimalParseError::Overflow)
+        );
+        // Partial sums beyond `MAX` whose total fits.
+        let min = Decimal::MIN.to_string();
+        assert_eq!(
+            Decimal::sum_strs([max.as_str(), max.as_str(), min.as_str()]),
+            Ok(Decimal::MAX)
+        );
+    }
+
+    #[test]
+    fn accumulators() {
+        let mut acc = Accumulator::new();
+        assert_eq!((acc.count(), acc.sum()), (0, Some(Decimal::ZERO)));
+        assert_eq!(acc.mean(2, RoundingStrategy::HalfUp), None);
+        for _ in 0..3 {
+            acc.add(&Decimal::MAX);
+        }
+        assert_eq!(acc.count(), 3);
+        assert_eq!(acc.sum(), None);
+        assert_eq!(acc.mean(0, RoundingStrategy::HalfUp), Some(Decimal::MAX));
+        acc.add(&Decimal::MIN);
+        acc.add(&Decimal::MIN);
+        assert_eq!(acc.sum(), Some(Decimal::MAX));
+        assert_eq!(acc.mean(0, RoundingStrategy::HalfUp), Some(Decimal::MAX / dec("5")));
+
+        // Low-order digits are kept, unlike the rounding of `Sum`.
+        let values = [dec("1e35"), dec("0.4"), dec("0.4"), dec("-1e35")];
+        assert_eq!(sum_exact(&values).to_string(), "0.8");
+        assert_eq!(values.iter().sum::<Decimal>().to_string(), "0");
+        assert_eq!(sum_exact(&[]), Decimal::ZERO);
+        assert_eq!(sum_exact(&decs(&["1", "NaN2"])).to_string(), "NaN2");
+
+        assert_eq!(
+            mean_with_scale(decs(&["1", "2", "2"]).iter(), 3).unwrap().to_string(),
+            "1.667"
+        );
+        assert_eq!(mean_with_scale([Decimal::MAX, Decimal::MAX], 0), Some(Decimal::MAX));
+        assert_eq!(mean_with_scale(Vec::<Decimal>::new(), 2), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when summing decimals")]
+    fn sum_exact_overflow() {
+        sum_exact(&[Decimal::MAX, Decimal::MAX]);
+    }
+
+    #[test]
+    fn checked_sums() {
+        assert_eq!(Decimal::checked_sum(decs(&["1.5", "2"]).iter()), Some(dec("3.5")));
+        assert_eq!(Decimal::checked_sum(Vec::<Decimal>::new()), Some(Decimal::ZERO));
+        assert_eq!(Decimal::checked_sum([Decimal::MAX, Decimal::MAX, Decimal::MIN]), None);
+        assert_eq!(Decimal::checked_product(decs(&["1.5", "-2"]).iter()), Some(dec("-3.0")));
+        assert_eq!(Decimal::checked_product(Vec::<Decimal>::new()), Some(Decimal::ONE));
+        assert_eq!(Decimal::checked_product([Decimal::MAX, dec("10"), dec("0.1")]), None);
+    }
+
+    #[test]
+    fn grouped_sums() {
+        let mut groups = GroupedSum::with_capacity(2);
+        assert!(groups.is_empty());
+        groups.add("a", &dec("1.5"));
+        groups.add("b", &dec("2"));
+        groups.add("a", &dec("-0.25"));
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups.get("a").unwrap().to_string(), "1.25");
+        assert_eq!(groups.get("b"), Some(dec("2")));
+        assert_eq!(groups.get("c"), None);
+
+        // Partial sums beyond `MAX` whose total fits, and a sum that overflows.
+        groups.add("c", &Decimal::MAX);
+        groups.add("c", &Decimal::MAX);
+        groups.add("c", &Decimal::MIN);
+        groups.add("d", &Decimal::MAX);
+        groups.add("d", &Decimal::MAX);
+        assert_eq!(groups.get("c"), Some(Decimal::MAX));
+        assert_eq!(groups.get("d"), None);
+
+        let mut sums: Vec<_> = groups.iter().map(|(&k, sum)| (k, sum)).collect();
+        sums.sort_by_key(|&(k, _)| k);
+        assert_eq!(
+            sums,
+            [
+                ("a", Some(dec("1.25"))),
+                ("b", Some(dec("2"))),
+                ("c", Some(Decimal::MAX)),
+                ("d", None)
+            ]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+//! Precision tracking for audited calculations.
+
+use crate::decimal::{Decimal, MAX_SCALE};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+use std::cmp;
+use std::fmt;
+
+/// A decimal that records the rounding applied to compute it.
+///
+/// Each value carries the largest rounding error introduced by any single operation in its
+/// history and the number of inexact operations, so a calculation can demonstrate its fidelity.
+/// Operations combine the records of their operands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AuditedDecimal {
+    value: Decimal,
+    max_error: Decimal,
+    inexact_ops: u32,
+}
+
+impl AuditedDecimal {
+    /// Creates an exact value.
+    #[inline]
+    pub const fn new(value: Decimal) -> AuditedDecimal {
+        AuditedDecimal {
+            value,
+            max_error: Decimal::ZERO,
+            inexact_ops: 0,
+        }
+    }
+
+    /// Returns the value.
+    #[inline]
+    pub const fn value(&self) -> Decimal {
+        self.value
+    }
+
+    /// Returns an upper bound of the largest rounding error introduced by a single operation.
+    #[inline]
+    pub const fn max_error(&self) -> Decimal {
+        self.max_error
+    }
+
+    /// Returns the number of inexact operations applied.
+    #[inline]
+    pub const fn inexact_ops(&self) -> u32 {
+        self.inexact_ops
+    }
+
+    /// Returns `true` if no operation was inexact.
+    #[inline]
+    pub const fn is_exact(&self) -> bool {
+        self.inexact_ops == 0
+    }
+
+    /// Adds two values, returning `None` if the sum overflows.
+    #[inline]
+    pub fn add(&self, other: &AuditedDecimal) -> Option<AuditedDecimal> {
+        let exact = Var::from_decimal(&self.value).add(&Var::from_decimal(&other.value));
+        self.record(other, exact)
+    }
+
+    /// Subtracts two values, returning `None` if the difference overflows.
+    #[inline]
+    pub fn sub(&self, other: &AuditedDecimal) -> Option<AuditedDecimal> {
+        let exact = Var::from_decimal(&self.value).sub(&Var::from_decimal(&other.value));
+        self.record(other, exact)
+    }
+
+    /// Multiplies two values, returning `None` if the product overflows.
+    #[inline]
+    pub fn mul(&self, other: &AuditedDecimal) -> Option<AuditedDecimal> {
+        let exact = Var::from_decimal(&self.value).mul(&Var::from_decimal(&other.value));
+        self.record(other, exact)
+    }
+
+    /// Divides two values, rounding the quotient to `scale` digits after the decimal point.
+    ///
+    /// Returns `None` if the divisor is zero or the quotient overflows.
+    pub fn div(&self, other: &AuditedDecimal, scale: i32, strategy: RoundingStrategy) -> Option<AuditedDecimal> {
+        let u = Var::from_decimal(&self.value);
+        let v = Var::from_decimal(&other.value);
+        if let Some(nan) = u.nan_operand(&v) {
+            let (value, _) = nan.into_decimal(strategy)?;
+            return Some(self.combine(other, value, Decimal::ZERO));
+        }
+        if v.is_zero() {
+            return None;
+        }
+
+        let (quotient, _) = u.div_round(&v, scale, strategy);
+        let (value, _) = quotient.into_decimal(strategy)?;
+        // The error is `|u - q * v| / |v|`.
+        let remainder = u.sub(&Var::from_decimal(&value).mul(&v)).abs();
+        let (error, _) = remainder.div_round(&v.abs(), MAX_SCALE, RoundingStrategy::Ceiling);
+        Some(self.combine(other, value, error_bound(error)))
+    }
+
+    /// Rounds to `scale` digits after the decimal point.
+    ///
+    /// Returns `None` if the result overflows.
+    pub fn round(&self, scale: i32, strategy: RoundingStrategy) -> Option<AuditedDecimal> {
+        let exact = Var::from_decimal(&self.value);
+        let mut rounded = exact;
+        rounded.round(scale, strategy, false);
+        let (value, _) = rounded.into_decimal(strategy)?;
+        let error = rounding_error(&exact, &value);
+        Some(self.combine(&AuditedDecimal::new(Decimal::ZERO), value, error))
+    }
+
+    #[inline]
+    fn record(&self, other: &AuditedDecimal, exact: Var) -> Option<AuditedDecimal> {
+        let (value, _) = exact.into_decimal(RoundingStrategy::HalfUp)?;
+        let error = rounding_error(&exact, &value);
+        Some(self.combine(other, value, error))
+    }
+
+    #[inline]
+    fn combine(&self, other: &AuditedDecimal, value: Decimal, error: Decimal) -> AuditedDecimal {
+        let inexact = !error.is_zero() as u32;
+        AuditedDecimal {
+            value,
+            max_error: cmp::max(cmp::max(self.max_error, other.max_error), error),
+            inexact_ops: self
+                .inexact_ops
+                .saturating_add(other.inexact_ops)
+                .saturating_add(inexact),
+        }
+    }
+}
+
+/// Returns `|exact - value|` rounded up to fit in a decimal.
+#[inline]
+fn rounding_error(exact: &Var, value: &Decimal) -> Decimal {
+    if value.is_nan() {
+        return Decimal::ZERO;
+    }
+    error_bound(exact.sub(&Var::from_decimal(value)).abs())
+}
+
+#[inline]
+fn error_bound(error: Var) -> Decimal {
+    // The error is below one unit of the rounded value, so it always fits.
+    let (error, _) = error.into_decimal(RoundingStrategy::Ceiling).unwrap();
+    error.canonicalize()
+}
+
+impl From<Decimal> for AuditedDecimal {
+    #[inline]
+    fn from(value: Decimal) -> Self {
+        AuditedDecimal::new(value)
+    }
+}
+
+impl fmt::Display for AuditedDecimal {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.value, f)
+    }
+}
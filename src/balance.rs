@@ -0,0 +1,187 @@
+//! Running balances for ledgers.
+
+use crate::decimal::{Decimal, MAX_SCALE};
+use crate::error::DecimalConvertError;
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+
+/// A running balance kept at a fixed scale, with a trail of the rounding applied to postings.
+///
+/// Every posting is rounded to the scale of the balance before it is applied, so the balance
+/// only holds amounts that can be booked. The exact difference between the unrounded postings
+/// and the balance is carried separately as the residual, so no rounding is silently lost.
+#[derive(Clone, Debug)]
+pub struct Balance {
+    value: Decimal,
+    scale: i32,
+    strategy: RoundingStrategy,
+    residual: Var,
+    postings: u64,
+    adjustments: Vec<RoundingAdjustment>,
+}
+
+/// A posting that was rounded to the scale of a [`Balance`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RoundingAdjustment {
+    /// Index of the posting, counting from 0.
+    pub posting: u64,
+    /// Signed amount requested, negative for debits.
+    pub requested: Decimal,
+    /// Signed amount applied after rounding.
+    pub applied: Decimal,
+}
+
+impl Balance {
+    /// Creates a zero balance rounding postings to `scale` digits after the decimal point with
+    /// `strategy`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is negative or greater than `MAX_SCALE`.
+    #[inline]
+    pub fn new(scale: i32, strategy: RoundingStrategy) -> Balance {
+        assert!((0..=MAX_SCALE).contains(&scale), "invalid balance scale");
+        Balance {
+            value: Decimal::from_coefficient(false, 0, scale),
+            scale,
+            strategy,
+            residual: Var::ZERO,
+            postings: 0,
+            adjustments: Vec::new(),
+        }
+    }
+
+    /// Adds `amount` rounded to the scale of the balance, returning the rounded amount.
+    ///
+    /// Returns an error and leaves the balance unchanged if `amount` is NaN or the balance
+    /// overflows.
+    #[inline]
+    pub fn credit(&mut self, amount: &Decimal) -> Result<Decimal, DecimalConvertError> {
+        self.post(*amount)
+    }
+
+    /// Subtracts `amount` rounded to the scale of the balance, returning the rounded amount.
+    ///
+    /// Returns an error and leaves the balance unchanged if `amount` is NaN or the balance
+    /// overflows.
+    #[inline]
+    pub fn debit(&mut self, amount: &Decimal) -> Result<Decimal, DecimalConvertError> {
+        self.post(-amount).map(|applied| -applied)
+    }
+
+    fn post(&mut self, requested: Decimal) -> Result<Decimal, DecimalConvertError> {
+        if requested.is_nan() {
+            return Err(DecimalConvertError::NaN);
+        }
+
+        // The balance always has the scale postings are rounded to, so quantizing only fails if
+        // the posting overflows at that scale.
+        let applied = requested
+            .quantize(&self.value, self.strategy)
+            .map_err(|_| DecimalConvertError::Overflow)?;
+        let value = self.value.checked_add(&applied).ok_or(DecimalConvertError::Overflow)?;
+        if value.scale() != self.scale {
+            // The sum needed more than `MAX_PRECISION` digits at the scale of the balance.
+            return Err(DecimalConvertError::Overflow);
+        }
+
+        self.value = value;
+        let error = Var::from_decimal(&requested).sub(&Var::from_decimal(&applied));
+        if !error.is_zero() {
+            self.residual = self.residual.add(&error);
+            self.adjustments.push(RoundingAdjustment {
+                posting: self.postings,
+                requested,
+                applied,
+            });
+        }
+        self.postings += 1;
+        Ok(applied)
+    }
+
+    /// Returns the balance.
+    #[inline]
+    pub const fn value(&self) -> Decimal {
+        self.value
+    }
+
+    /// Returns the scale postings are rounded to.
+    #[inline]
+    pub const fn scale(&self) -> i32 {
+        self.scale
+    }
+
+    /// Returns the number of postings applied.
+    #[inline]
+    pub const fn postings(&self) -> u64 {
+        self.postings
+    }
+
+    /// Returns the postings that were rounded, in order.
+    #[inline]
+    pub fn adjustments(&self) -> &[RoundingAdjustment] {
+        &self.adjustments
+    }
+
+    /// Returns the sum of the requested amounts minus the balance, which is the total rounding
+    /// applied, rounded half up to fit in a decimal.
+    ///
+    /// Returns `None` if it overflows.
+    #[inline]
+    pub fn residual(&self) -> Option<Decimal> {
+        self.residual
+            .into_decimal(RoundingStrategy::HalfUp)
+            .map(|(d, _)| d.canonicalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Balance, RoundingAdjustment};
+    use crate::error::DecimalConvertError;
+    use crate::{Decimal, RoundingStrategy};
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn postings() {
+        let mut balance = Balance::new(2, RoundingStrategy::HalfEven);
+        assert_eq!(balance.credit(&dec("10.005")).unwrap().to_string(), "10.00");
+        assert_eq!(balance.debit(&dec("2.5")).unwrap().to_string(), "2.50");
+        assert_eq!(balance.debit(&dec("0.015")).unwrap().to_string(), "0.02");
+        assert_eq!(balance.value().to_string(), "7.48");
+        assert_eq!(balance.postings(), 3);
+        assert_eq!(
+            balance.adjustments(),
+            [
+                RoundingAdjustment {
+                    posting: 0,
+                    requested: dec("10.005"),
+                    applied: dec("10.00"),
+                },
+                RoundingAdjustment {
+                    posting: 2,
+                    requested: dec("-0.015"),
+                    applied: dec("-0.02"),
+                },
+            ]
+        );
+        assert_eq!(balance.residual().unwrap().to_string(), "0.01");
+    }
+
+    #[test]
+    fn errors_leave_the_balance_unchanged() {
+        let mut balance = Balance::new(2, RoundingStrategy::HalfUp);
+        balance.credit(&dec("1")).unwrap();
+        assert_eq!(balance.credit(&Decimal::NAN), Err(DecimalConvertError::NaN));
+        // Too many digits at the scale of the balance.
+        assert_eq!(balance.debit(&dec("1e35")), Err(DecimalConvertError::Overflow));
+        balance.credit(&dec("1e33")).unwrap();
+        assert_eq!(balance.credit(&dec("9e33")), Err(DecimalConvertError::Overflow));
+        assert_eq!(balance.value().to_string(), "1000000000000000000000000000000001.00");
+        assert_eq!(balance.postings(), 2);
+        assert!(balance.adjustments().is_empty());
+    }
+}
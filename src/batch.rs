@@ -0,0 +1,368 @@
+//! Kernels over columns of decimals with null masks.
+//!
+//! A column is a slice of decimals with a [`Bitmap`] marking which rows are valid, as in Apache
+//! Arrow. The values of null rows are ignored, so they can be anything. The kernels follow SQL
+//! semantics: aggregates skip nulls, and return null (`None`) when no row is valid.
+
+use crate::aggregate::Accumulator;
+use crate::decimal::Decimal;
+
+/// A validity bitmap, where a set bit marks a valid row and a clear bit a null.
+///
+/// Bits are stored least significant first within each byte, the layout of Arrow validity
+/// buffers.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bitmap {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+/// How [`min`] and [`max`] handle null rows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NullHandling {
+    /// Skips nulls as SQL aggregates do.
+    Ignore,
+    /// Returns null if any row is null.
+    Propagate,
+}
+
+impl Bitmap {
+    /// Creates a bitmap of `len` valid rows.
+    #[inline]
+    pub fn new_valid(len: usize) -> Bitmap {
+        let mut bitmap = Bitmap {
+            bytes: vec![0xff; len.div_ceil(8)],
+            len,
+        };
+        bitmap.clear_padding();
+        bitmap
+    }
+
+    /// Creates a bitmap of `len` null rows.
+    #[inline]
+    pub fn new_null(len: usize) -> Bitmap {
+        Bitmap {
+            bytes: vec![0; len.div_ceil(8)],
+            len,
+        }
+    }
+
+    /// Creates a bitmap from an Arrow validity buffer of `len` rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` has fewer than `len` bits.
+    pub fn from_bytes(bytes: &[u8], len: usize) -> Bitmap {
+        let n = len.div_ceil(8);
+        assert!(bytes.len() >= n, "bitmap buffer is too short");
+        let mut bitmap = Bitmap {
+            bytes: bytes[..n].to_vec(),
+            len,
+        };
+        bitmap.clear_padding();
+        bitmap
+    }
+
+    /// Returns the number of rows.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no rows.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if row `i` is valid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    #[inline]
+    pub fn is_valid(&self, i: usize) -> bool {
+        assert!(i < self.len, "row out of bounds");
+        self.bytes[i / 8] >> (i % 8) & 1 == 1
+    }
+
+    /// Marks row `i` as valid or null.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds.
+    #[inline]
+    pub fn set(&mut self, i: usize, valid: bool) {
+        assert!(i < self.len, "row out of bounds");
+        if valid {
+            self.bytes[i / 8] |= 1 << (i % 8);
+        } else {
+            self.bytes[i / 8] &= !(1 << (i % 8));
+        }
+    }
+
+    /// Returns the number of null rows.
+    #[inline]
+    pub fn null_count(&self) -> usize {
+        self.len - self.bytes.iter().map(|b| b.count_ones() as usize).sum::<usize>()
+    }
+
+    /// Returns the Arrow validity buffer.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    #[inline]
+    fn clear_padding(&mut self) {
+        if self.len % 8 != 0 {
+            let last = self.bytes.len() - 1;
+            self.bytes[last] &= (1 << (self.len % 8)) - 1;
+        }
+    }
+}
+
+impl std::iter::FromIterator<bool> for Bitmap {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bytes = Vec::new();
+        let mut len = 0;
+        for valid in iter {
+            if len % 8 == 0 {
+                bytes.push(0);
+            }
+            bytes[len / 8] |= (valid as u8) << (len % 8);
+            len += 1;
+        }
+        Bitmap { bytes, len }
+    }
+}
+
+/// Iterates the valid values of a column.
+///
+/// # Panics
+///
+/// Panics if `values` and `validity` have different lengths.
+#[inline]
+fn valid_values<'a>(values: &'a [Decimal], validity: &'a Bitmap) -> impl Iterator<Item = &'a Decimal> + 'a {
+    assert_eq!(
+        values.len(),
+        validity.len(),
+        "values and validity have different lengths"
+    );
+    values
+        .iter()
+        .enumerate()
+        .filter(move |(i, _)| validity.is_valid(*i))
+        .map(|(_, value)| value)
+}
+
+/// Returns the sum of the valid values, or `None` if there are none.
+///
+/// The sum is accumulated exactly and rounded half up once to fit in a decimal. NaN in the valid
+/// values gives NaN.
+///
+/// # Panics
+///
+/// Panics if `values` and `validity` have different lengths, or if the sum overflows.
+pub fn sum(values: &[Decimal], validity: &Bitmap) -> Option<Decimal> {
+    let mut acc = Accumulator::new();
+    for value in valid_values(values, validity) {
+        acc.add(value);
+    }
+    if acc.count() == 0 {
+        return None;
+    }
+    Some(acc.sum().expect("sum overflowed"))
+}
+
+/// Returns the number of valid values, as SQL's `COUNT(column)`.
+///
+/// # Panics
+///
+/// Panics if `values` and `validity` have different lengths.
+#[inline]
+pub fn count(values: &[Decimal], validity: &Bitmap) -> usize {
+    assert_eq!(
+        values.len(),
+        validity.len(),
+        "values and validity have different lengths"
+    );
+    validity.len() - validity.null_count()
+}
+
+/// Returns the smallest valid value, or `None` if there is none or a null is propagated.
+///
+/// # Panics
+///
+/// Panics if `values` and `validity` have different lengths.
+#[inline]
+pub fn min(values: &[Decimal], validity: &Bitmap, nulls: NullHandling) -> Option<Decimal> {
+    extremum(values, validity, nulls, |a, b| b < a)
+}
+
+/// Returns the largest valid value, or `None` if there is none or a null is propagated.
+///
+/// NaN is greater than any number, as in the order of decimals.
+///
+/// # Panics
+///
+/// Panics if `values` and `validity` have different lengths.
+#[inline]
+pub fn max(values: &[Decimal], validity: &Bitmap, nulls: NullHandling) -> Option<Decimal> {
+    extremum(values, validity, nulls, |a, b| b > a)
+}
+
+fn extremum<F>(values: &[Decimal], validity: &Bitmap, nulls: NullHandling, better: F) -> Option<Decimal>
+where
+    F: Fn(&Decimal, &Decimal) -> bool,
+{
+    if nulls == NullHandling::Propagate && validity.null_count() > 0 {
+        assert_eq!(
+            values.len(),
+            validity.len(),
+            "values and validity have different lengths"
+        );
+        return None;
+    }
+
+    let mut result: Option<&Decimal> = None;
+    for value in valid_values(values, validity) {
+        match result {
+            Some(current) if !better(current, value) => {}
+            _ => result = Some(value),
+        }
+    }
+    result.copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{count, max, min, sum, Bitmap, NullHandling};
+    use crate::Decimal;
+
+    fn decs(values: &[&str]) -> Vec<Decimal> {
+        values.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn bit_layout() {
+        // Row 0 is the least significant bit of the first byte.
+        let bitmap: Bitmap = [true, false, false, true, false, false, false, false, true, true]
+            .into_iter()
+            .collect();
+        assert_eq!(bitmap.as_bytes(), [0b0000_1001, 0b0000_0011]);
+        assert_eq!((bitmap.len(), bitmap.null_count()), (10, 6));
+        assert!(bitmap.is_valid(0) && !bitmap.is_valid(1) && bitmap.is_valid(9));
+
+        let mut bitmap = Bitmap::new_null(10);
+        bitmap.set(3, true);
+        bitmap.set(8, true);
+        bitmap.set(8, false);
+        assert_eq!(bitmap.as_bytes(), [0b0000_1000, 0]);
+        assert_eq!(bitmap.null_count(), 9);
+
+        assert!(Bitmap::new_null(0).is_empty());
+        assert_eq!(Bitmap::new_valid(0).as_bytes(), [0u8; 0]);
+        assert_eq!(Bitmap::new_valid(8).as_bytes(), [0xff]);
+    }
+
+    #[test]
+    fn padding_is_cleared() {
+        let bitmap = Bitmap::new_valid(10);
+        assert_eq!(bitmap.as_bytes(), [0xff, 0b0000_0011]);
+        assert_eq!(bitmap.null_count(), 0);
+
+        // Bits beyond `len` in an Arrow buffer are ignored, as are extra bytes.
+        let bitmap = Bitmap::from_bytes(&[0b1111_0111, 0xff, 0xff], 12);
+        assert_eq!(bitmap.as_bytes(), [0b1111_0111, 0b0000_1111]);
+        assert_eq!(bitmap.null_count(), 1);
+        assert_eq!(
+            bitmap,
+            [0, 1, 2, 4, 5, 6, 7, 8, 9, 10, 11]
+                .iter()
+                .fold(Bitmap::new_null(12), |mut b, &i| {
+                    b.set(i, true);
+                    b
+                })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "bitmap buffer is too short")]
+    fn short_buffer() {
+        Bitmap::from_bytes(&[0xff], 9);
+    }
+
+    #[test]
+    #[should_panic(expected = "row out of bounds")]
+    fn row_out_of_bounds() {
+        Bitmap::new_valid(10).is_valid(10);
+    }
+
+    #[test]
+    fn kernels() {
+        let values = decs(&["1.5", "99", "-2", "NaN", "0.25"]);
+        let validity: Bitmap = [true, false, true, false, true].into_iter().collect();
+        assert_eq!(sum(&values, &validity).unwrap().to_string(), "-0.25");
+        assert_eq!(count(&values, &validity), 3);
+        assert_eq!(min(&values, &validity, NullHandling::Ignore).unwrap().to_string(), "-2");
+        assert_eq!(
+            max(&values, &validity, NullHandling::Ignore).unwrap().to_string(),
+            "1.5"
+        );
+
+        // A null row makes propagated extrema null.
+        assert_eq!(min(&values, &validity, NullHandling::Propagate), None);
+        assert_eq!(max(&values, &validity, NullHandling::Propagate), None);
+        let all = Bitmap::new_valid(values.len());
+        assert!(max(&values, &all, NullHandling::Propagate).unwrap().is_nan());
+        assert_eq!(min(&values, &all, NullHandling::Propagate).unwrap().to_string(), "-2");
+        assert!(sum(&values, &all).unwrap().is_nan());
+
+        // Partial sums beyond `MAX` whose total fits.
+        let values = [Decimal::MAX, Decimal::MAX, Decimal::MIN];
+        assert_eq!(sum(&values, &Bitmap::new_valid(3)), Some(Decimal::MAX));
+    }
+
+    #[test]
+    fn all_null_columns() {
+        let values = decs(&["1", "2", "3"]);
+        let nulls = Bitmap::new_null(3);
+        assert_eq!(sum(&values, &nulls), None);
+        assert_eq!(count(&values, &nulls), 0);
+        for handling in [NullHandling::Ignore, NullHandling::Propagate] {
+            assert_eq!(min(&values, &nulls, handling), None);
+            assert_eq!(max(&values, &nulls, handling), None);
+        }
+        assert_eq!(sum(&[], &Bitmap::new_valid(0)), None);
+        assert_eq!(min(&[], &Bitmap::new_valid(0), NullHandling::Propagate), None);
+    }
+
+    #[test]
+    fn length_mismatches() {
+        let values = decs(&["1", "2", "3"]);
+        let validity = Bitmap::new_valid(2);
+        let nulls = Bitmap::new_null(4);
+        let panics = |f: &dyn Fn()| {
+            let message = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_err();
+            assert!(message
+                .downcast_ref::<String>()
+                .unwrap()
+                .contains("values and validity have different lengths"));
+        };
+        panics(&|| {
+            sum(&values, &validity);
+        });
+        panics(&|| {
+            count(&values, &validity);
+        });
+        for handling in [NullHandling::Ignore, NullHandling::Propagate] {
+            panics(&|| {
+                min(&values, &validity, handling);
+            });
+            panics(&|| {
+                max(&values, &nulls, handling);
+            });
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Counters of the internal paths taken by parsing, formatting and comparison.
+//!
+//! Available with the `bench-internals` feature, to profile which paths a workload hits and
+//! report regressions against a specific path. The counters are global relaxed atomics that
+//! add a small cost to every counted path, so the feature is meant for profiling builds only.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+pub(crate) static PARSE_DIGITS: AtomicU64 = AtomicU64::new(0);
+pub(crate) static FORMAT_DIGITS: AtomicU64 = AtomicU64::new(0);
+pub(crate) static CMP_NAN: AtomicU64 = AtomicU64::new(0);
+pub(crate) static CMP_SIGN: AtomicU64 = AtomicU64::new(0);
+pub(crate) static CMP_WEIGHT: AtomicU64 = AtomicU64::new(0);
+pub(crate) static CMP_LIMBS: AtomicU64 = AtomicU64::new(0);
+
+/// A snapshot of the counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Counters {
+    /// Mantissa digits scanned by parsing.
+    pub parse_digits: u64,
+    /// Digits written by formatting.
+    pub format_digits: u64,
+    /// Comparisons decided by a NaN operand.
+    pub cmp_nan: u64,
+    /// Comparisons decided by the signs.
+    pub cmp_sign: u64,
+    /// Comparisons of magnitudes decided by zero operands or the weights.
+    pub cmp_weight: u64,
+    /// Comparisons of magnitudes that compared limbs.
+    pub cmp_limbs: u64,
+}
+
+/// Returns the current values of the counters.
+pub fn counters() -> Counters {
+    Counters {
+        parse_digits: PARSE_DIGITS.load(Ordering::Relaxed),
+        format_digits: FORMAT_DIGITS.load(Ordering::Relaxed),
+        cmp_nan: CMP_NAN.load(Ordering::Relaxed),
+        cmp_sign: CMP_SIGN.load(Ordering::Relaxed),
+        cmp_weight: CMP_WEIGHT.load(Ordering::Relaxed),
+        cmp_limbs: CMP_LIMBS.load(Ordering::Relaxed),
+    }
+}
+
+/// Resets all counters to zero.
+pub fn reset() {
+    for counter in [
+        &PARSE_DIGITS,
+        &FORMAT_DIGITS,
+        &CMP_NAN,
+        &CMP_SIGN,
+        &CMP_WEIGHT,
+        &CMP_LIMBS,
+    ] {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,824 @@
+//! IEEE 754 decimal32, decimal64 and decimal128 in the binary integer decimal (BID) encoding.
+//!
+//! These are compact storage types. Values are converted to [`Decimal`] for arithmetic: widening
+//! is exact, and narrowing is exact with `TryFrom` or rounded with the `to_decimal*` helpers.
+
+use crate::classify::DecimalCategory;
+use crate::decimal::{Decimal, MAX_SCALE};
+use crate::error::DecimalConvertError;
+use crate::round::RoundingStrategy;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Parameters of an interchange format.
+struct Format {
+    /// Total number of bits.
+    bits: u32,
+    /// Number of bits of the biased exponent.
+    exp_bits: u32,
+    /// Number of coefficient digits.
+    precision: u32,
+    /// Exponent of the smallest unit, `emin - precision + 1`.
+    qmin: i32,
+    /// Exponent of the largest unit, `emax - precision + 1`.
+    qmax: i32,
+}
+
+const DECIMAL32: Format = Format {
+    bits: 32,
+    exp_bits: 8,
+    precision: 7,
+    qmin: -101,
+    qmax: 90,
+};
+
+const DECIMAL64: Format = Format {
+    bits: 64,
+    exp_bits: 10,
+    precision: 16,
+    qmin: -398,
+    qmax: 369,
+};
+
+const DECIMAL128: Format = Format {
+    bits: 128,
+    exp_bits: 14,
+    precision: 34,
+    qmin: -6176,
+    qmax: 6111,
+};
+
+/// A decoded interchange value.
+#[derive(Clone, Copy)]
+enum Unpacked {
+    Finite {
+        negative: bool,
+        coefficient: u128,
+        exponent: i32,
+    },
+    Infinite {
+        negative: bool,
+    },
+    NaN {
+        signaling: bool,
+        payload: u128,
+    },
+}
+
+impl Unpacked {
+    /// Compares in the total order `-Infinity < numbers < Infinity < NaN` of
+    /// [`Decimal::total_cmp`], with `-0 < 0` and equal numbers ordered by exponent as IEEE 754
+    /// `totalOrder`.
+    fn total_cmp(&self, other: &Unpacked) -> Ordering {
+        // Numbers are ranked by sign, so that their order can be reversed for negatives.
+        let rank = |value: &Unpacked| match *value {
+            Unpacked::Infinite { negative: true } => 0,
+            Unpacked::Finite { negative: true, .. } => 1,
+            Unpacked::Finite { negative: false, .. } => 2,
+            Unpacked::Infinite { negative: false } => 3,
+            Unpacked::NaN { .. } => 4,
+        };
+        let ord = rank(self).cmp(&rank(other));
+        if ord != Ordering::Equal {
+            return ord;
+        }
+
+        match (*self, *other) {
+            (
+                Unpacked::Finite {
+                    negative,
+                    coefficient: a,
+                    exponent: ea,
+                },
+                Unpacked::Finite {
+                    coefficient: b,
+                    exponent: eb,
+                    ..
+                },
+            ) => {
+                let ord = cmp_finite(a, ea, b, eb).then(ea.cmp(&eb));
+                if negative {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            }
+            (
+                Unpacked::NaN {
+                    signaling: sa,
+                    payload: pa,
+                },
+                Unpacked::NaN {
+                    signaling: sb,
+                    payload: pb,
+                },
+            ) => sa.cmp(&sb).reverse().then(pa.cmp(&pb)),
+            _ => Ordering::Equal,
+        }
+    }
+}
+
+/// Compares the magnitudes `a * 10^ea` and `b * 10^eb`.
+fn cmp_finite(a: u128, ea: i32, b: u128, eb: i32) -> Ordering {
+    match (a == 0, b == 0) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        (false, false) => {}
+    }
+
+    let (da, db) = (count_digits(a) as i32 + ea, count_digits(b) as i32 + eb);
+    da.cmp(&db).then_with(|| {
+        // With equal adjusted exponents, the exponents differ by less than the precision, so
+        // the aligned coefficients fit.
+        if ea >= eb {
+            (a * 10u128.pow((ea - eb) as u32)).cmp(&b)
+        } else {
+            a.cmp(&(b * 10u128.pow((eb - ea) as u32)))
+        }
+    })
+}
+
+impl Format {
+    #[inline]
+    fn max_coefficient(&self) -> u128 {
+        10u128.pow(self.precision) - 1
+    }
+
+    /// Returns `payload` if it fits the format, or zero.
+    #[inline]
+    fn canonical_payload(&self, payload: u128) -> u128 {
+        if payload < 10u128.pow(self.precision - 1) {
+            payload
+        } else {
+            0
+        }
+    }
+
+    /// Number of trailing coefficient bits in the first encoding form.
+    #[inline]
+    fn coefficient_bits(&self) -> u32 {
+        self.bits - 1 - self.exp_bits
+    }
+
+    fn decode(&self, bits: u128) -> Unpacked {
+        let negative = bits >> (self.bits - 1) & 1 == 1;
+        let top = |n: u32| bits >> (self.bits - 1 - n) & ((1 << n) - 1);
+        let exp_mask = (1 << self.exp_bits) - 1;
+        let coefficient_bits = self.coefficient_bits();
+
+        if top(5) == 0b11111 {
+            // The payload is in the trailing significand field.
+            let payload = bits & ((1 << (coefficient_bits - 3)) - 1);
+            return Unpacked::NaN {
+                signaling: top(6) & 1 == 1,
+                payload: self.canonical_payload(payload),
+            };
+        }
+        if top(5) == 0b11110 {
+            return Unpacked::Infinite { negative };
+        }
+
+        let (biased, coefficient) = if top(2) == 0b11 {
+            let biased = bits >> (coefficient_bits - 2) & exp_mask;
+            let coefficient = 0b100 << (coefficient_bits - 2) | bits & ((1 << (coefficient_bits - 2)) - 1);
+            (biased, coefficient)
+        } else {
+            (
+                bits >> coefficient_bits & exp_mask,
+                bits & ((1 << coefficient_bits) - 1),
+            )
+        };
+        // Non-canonical coefficients are zero.
+        let coefficient = if coefficient > self.max_coefficient() {
+            0
+        } else {
+            coefficient
+        };
+
+        Unpacked::Finite {
+            negative,
+            coefficient,
+            exponent: biased as i32 + self.qmin,
+        }
+    }
+
+    fn encode(&self, value: Unpacked) -> u128 {
+        let sign_bit = |negative: bool| (negative as u128) << (self.bits - 1);
+        let coefficient_bits = self.coefficient_bits();
+        match value {
+            Unpacked::NaN { signaling, payload } => {
+                let tag = if signaling { 0b111111 } else { 0b111110 };
+                tag << (self.bits - 7) | payload
+            }
+            Unpacked::Infinite { negative } => sign_bit(negative) | 0b11110 << (self.bits - 6),
+            Unpacked::Finite {
+                negative,
+                coefficient,
+                exponent,
+            } => {
+                let biased = (exponent - self.qmin) as u128;
+                if coefficient >> coefficient_bits == 0 {
+                    sign_bit(negative) | biased << coefficient_bits | coefficient
+                } else {
+                    let low = coefficient & ((1 << (coefficient_bits - 2)) - 1);
+                    sign_bit(negative) | 0b11 << (self.bits - 3) | biased << (coefficient_bits - 2) | low
+                }
+            }
+        }
+    }
+
+    /// Rounds a finite value to the precision and exponent range of this format.
+    ///
+    /// Returns the value and whether it is inexact, or an infinity on overflow.
+    fn round(&self, negative: bool, coefficient: u128, exponent: i32, strategy: RoundingStrategy) -> (Unpacked, bool) {
+        let mut coefficient = coefficient;
+        let mut exponent = exponent;
+        let mut inexact = false;
+
+        let digits = count_digits(coefficient) as i32;
+        let drop = (digits - self.precision as i32).max(self.qmin - exponent).max(0);
+        if drop > 0 {
+            let (kept, rounded) = drop_digits(negative, coefficient, drop as u32, strategy);
+            coefficient = kept;
+            exponent += drop;
+            inexact = rounded;
+            if coefficient > self.max_coefficient() {
+                // Carried into a new digit, which is a power of ten.
+                coefficient /= 10;
+                exponent += 1;
+            }
+        }
+
+        // Pads with zeros a coefficient whose exponent is too large, if there is room.
+        while exponent > self.qmax && coefficient != 0 && coefficient * 10 <= self.max_coefficient() {
+            coefficient *= 10;
+            exponent -= 1;
+        }
+        if coefficient == 0 {
+            exponent = exponent.clamp(self.qmin, self.qmax);
+        }
+        if exponent > self.qmax {
+            let value = if strategy.round_up(negative, false, Ordering::Greater, true) {
+                Unpacked::Infinite { negative }
+            } else {
+                Unpacked::Finite {
+                    negative,
+                    coefficient: self.max_coefficient(),
+                    exponent: self.qmax,
+                }
+            };
+            return (value, true);
+        }
+
+        let value = Unpacked::Finite {
+            negative,
+            coefficient,
+            exponent,
+        };
+        (value, inexact)
+    }
+
+    /// Converts a decimal, rounding digits that don't fit.
+    fn unpack_decimal(&self, d: &Decimal, strategy: RoundingStrategy) -> (Unpacked, bool) {
+        if d.is_nan() {
+            let value = Unpacked::NaN {
+                signaling: d.is_signaling_nan(),
+                payload: self.canonical_payload(d.nan_payload().unwrap_or(0)),
+            };
+            return (value, false);
+        }
+        // The exponent is minus the scale, unless the trailing zeros of an integer don't fit.
+        let (coefficient, exponent) = match d.coefficient() {
+            Some(coefficient) => (coefficient, -d.scale()),
+            None => d.coefficient_parts(),
+        };
+        self.round(d.is_sign_negative(), coefficient, exponent, strategy)
+    }
+
+    /// Converts a value decoded from another format, rounding digits that don't fit.
+    fn convert(&self, value: Unpacked, strategy: RoundingStrategy) -> (Unpacked, bool) {
+        match value {
+            Unpacked::Finite {
+                negative,
+                coefficient,
+                exponent,
+            } => self.round(negative, coefficient, exponent, strategy),
+            Unpacked::NaN { signaling, payload } => {
+                let payload = self.canonical_payload(payload);
+                (Unpacked::NaN { signaling, payload }, false)
+            }
+            value => (value, false),
+        }
+    }
+}
+
+/// Converts a decoded value to a decimal exactly.
+fn to_decimal(value: Unpacked) -> Result<Decimal, DecimalConvertError> {
+    let (negative, mut coefficient, mut exponent) = match value {
+        Unpacked::NaN { signaling, payload } => return Ok(Decimal::nan_with_payload(payload, signaling).unwrap()),
+        Unpacked::Infinite { .. } => return Err(DecimalConvertError::Overflow),
+        Unpacked::Finite {
+            negative,
+            coefficient,
+            exponent,
+        } => (negative, coefficient, exponent),
+    };
+
+    if coefficient == 0 {
+        return Ok(Decimal::from_coefficient(false, 0, (-exponent).clamp(0, MAX_SCALE)));
+    }
+    while exponent < -MAX_SCALE && coefficient % 10 == 0 {
+        coefficient /= 10;
+        exponent += 1;
+    }
+    let coefficient = coefficient as i128;
+    let coefficient = if negative { -coefficient } else { coefficient };
+    Decimal::from_coefficient_exponent(coefficient, exponent).ok_or(DecimalConvertError::Overflow)
+}
+
+#[inline]
+fn count_digits(value: u128) -> u32 {
+    let mut digits = 1;
+    while digits < 39 && value >= 10u128.pow(digits) {
+        digits += 1;
+    }
+    digits
+}
+
+/// Drops the `n` least significant digits of `coefficient` with `strategy`, returning the kept
+/// digits and whether the dropped ones are non-zero.
+fn drop_digits(negative: bool, coefficient: u128, n: u32, strategy: RoundingStrategy) -> (u128, bool) {
+    if n >= 39 {
+        // All digits are dropped and they are less than half a unit.
+        let inexact = coefficient != 0;
+        let up = strategy.round_up(negative, false, Ordering::Less, inexact);
+        return (up as u128, inexact);
+    }
+
+    let unit = 10u128.pow(n);
+    let (kept, rest) = (coefficient / unit, coefficient % unit);
+    let half = rest.cmp(&(unit / 2));
+    let up = strategy.round_up(negative, kept % 2 == 1, half, rest != 0);
+    (kept + up as u128, rest != 0)
+}
+
+macro_rules! impl_interchange {
+    ($name:ident, $bits:ty, $format:ident, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name($bits);
+
+        impl $name {
+            /// Positive infinity.
+            pub const INFINITY: $name = $name(0b11110 << (<$bits>::BITS - 6));
+
+            /// Negative infinity.
+            pub const NEG_INFINITY: $name = $name(0b111110 << (<$bits>::BITS - 6));
+
+            /// Quiet NaN.
+            pub const NAN: $name = $name(0b111110 << (<$bits>::BITS - 7));
+
+            /// Creates a value from its encoding.
+            #[inline]
+            pub const fn from_bits(bits: $bits) -> $name {
+                $name(bits)
+            }
+
+            /// Returns the encoding.
+            #[inline]
+            pub const fn to_bits(self) -> $bits {
+                self.0
+            }
+
+            /// Returns `true` if this value is a quiet or signaling NaN.
+            #[inline]
+            pub fn is_nan(self) -> bool {
+                matches!($format.decode(self.0 as u128), Unpacked::NaN { .. })
+            }
+
+            /// Returns `true` if this value is positive or negative infinity.
+            #[inline]
+            pub fn is_infinite(self) -> bool {
+                matches!($format.decode(self.0 as u128), Unpacked::Infinite { .. })
+            }
+
+            /// Returns the category of this value, negative zero is [`DecimalCategory::Zero`].
+            #[inline]
+            pub fn classify(self) -> DecimalCategory {
+                match $format.decode(self.0 as u128) {
+                    Unpacked::Finite { coefficient: 0, .. } => DecimalCategory::Zero,
+                    Unpacked::Finite { negative: true, .. } => DecimalCategory::NormalNegative,
+                    Unpacked::Finite { negative: false, .. } => DecimalCategory::NormalPositive,
+                    Unpacked::Infinite { .. } => DecimalCategory::Infinite,
+                    Unpacked::NaN { .. } => DecimalCategory::NaN,
+                }
+            }
+
+            /// Compares in the total order of the crate, `-Infinity < numbers < Infinity < NaN`,
+            /// which is that of [`Decimal::total_cmp`] for values that convert exactly.
+            ///
+            /// Negative zero sorts before zero, and equal numbers are ordered by exponent as
+            /// IEEE 754 `totalOrder`. NaNs sort last whatever their sign, signaling before
+            /// quiet, then by payload.
+            #[inline]
+            pub fn total_cmp(&self, other: &$name) -> Ordering {
+                $format
+                    .decode(self.0 as u128)
+                    .total_cmp(&$format.decode(other.0 as u128))
+            }
+        }
+
+        impl TryFrom<Decimal> for $name {
+            type Error = DecimalConvertError;
+
+            /// Converts exactly, failing if the decimal has too many significant digits.
+            #[inline]
+            fn try_from(d: Decimal) -> Result<Self, Self::Error> {
+                match $format.unpack_decimal(&d, RoundingStrategy::HalfEven) {
+                    (value, false) => Ok($name($format.encode(value) as $bits)),
+                    (_, true) => Err(DecimalConvertError::Overflow),
+                }
+            }
+        }
+
+        impl TryFrom<$name> for Decimal {
+            type Error = DecimalConvertError;
+
+            /// Converts exactly, failing for infinities and values out of the range of a decimal.
+            #[inline]
+            fn try_from(value: $name) -> Result<Self, Self::Error> {
+                to_decimal($format.decode(value.0 as u128))
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let value = $format.decode(self.0 as u128);
+                match value {
+                    Unpacked::Infinite { negative: false } => f.pad("Infinity"),
+                    Unpacked::Infinite { negative: true } => f.pad("-Infinity"),
+                    Unpacked::Finite {
+                        negative,
+                        coefficient,
+                        exponent,
+                    } => match to_decimal(value) {
+                        Ok(d) => fmt::Display::fmt(&d, f),
+                        Err(_) => {
+                            let sign = if negative { "-" } else { "" };
+                            f.pad(&format!("{}{}E{:+}", sign, coefficient, exponent))
+                        }
+                    },
+                    Unpacked::NaN { .. } => fmt::Display::fmt(&to_decimal(value).unwrap(), f),
+                }
+            }
+        }
+
+        impl fmt::Debug for $name {
+            #[inline]
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
+    };
+}
+
+impl_interchange!(
+    Decimal32,
+    u32,
+    DECIMAL32,
+    "An IEEE 754 decimal32 with 7 significant digits, in the BID encoding."
+);
+
+impl_interchange!(
+    Decimal64,
+    u64,
+    DECIMAL64,
+    "An IEEE 754 decimal64 with 16 significant digits, in the BID encoding."
+);
+
+impl_interchange!(
+    Decimal128,
+    u128,
+    DECIMAL128,
+    "An IEEE 754 decimal128 with 34 significant digits, in the BID encoding."
+);
+
+impl From<Decimal32> for Decimal64 {
+    /// Widens exactly.
+    #[inline]
+    fn from(value: Decimal32) -> Self {
+        let value = DECIMAL64.convert(DECIMAL32.decode(value.0 as u128), RoundingStrategy::HalfEven);
+        Decimal64(DECIMAL64.encode(value.0) as u64)
+    }
+}
+
+impl From<Decimal32> for Decimal128 {
+    /// Widens exactly.
+    #[inline]
+    fn from(value: Decimal32) -> Self {
+        let value = DECIMAL128.convert(DECIMAL32.decode(value.0 as u128), RoundingStrategy::HalfEven);
+        Decimal128(DECIMAL128.encode(value.0))
+    }
+}
+
+impl From<Decimal64> for Decimal128 {
+    /// Widens exactly.
+    #[inline]
+    fn from(value: Decimal64) -> Self {
+        let value = DECIMAL128.convert(DECIMAL64.decode(value.0 as u128), RoundingStrategy::HalfEven);
+        Decimal128(DECIMAL128.encode(value.0))
+    }
+}
+
+impl TryFrom<Decimal64> for Decimal32 {
+    type Error = DecimalConvertError;
+
+    /// Narrows exactly, failing if the value has too many significant digits or is out of range.
+    #[inline]
+    fn try_from(value: Decimal64) -> Result<Self, Self::Error> {
+        match DECIMAL32.convert(DECIMAL64.decode(value.0 as u128), RoundingStrategy::HalfEven) {
+            (value, false) => Ok(Decimal32(DECIMAL32.encode(value) as u32)),
+            (_, true) => Err(DecimalConvertError::Overflow),
+        }
+    }
+}
+
+impl TryFrom<Decimal128> for Decimal64 {
+    type Error = DecimalConvertError;
+
+    /// Narrows exactly, failing if the value has too many significant digits or is out of range.
+    #[inline]
+    fn try_from(value: Decimal128) -> Result<Self, Self::Error> {
+        match DECIMAL64.convert(DECIMAL128.decode(value.0), RoundingStrategy::HalfEven) {
+            (value, false) => Ok(Decimal64(DECIMAL64.encode(value) as u64)),
+            (_, true) => Err(DecimalConvertError::Overflow),
+        }
+    }
+}
+
+impl Decimal64 {
+    /// Narrows to a decimal32, rounding digits that don't fit with `strategy`.
+    ///
+    /// Values beyond the range of a decimal32 become an infinity, or the largest finite value
+    /// when `strategy` rounds towards zero.
+    #[inline]
+    pub fn to_decimal32(self, strategy: RoundingStrategy) -> Decimal32 {
+        let value = DECIMAL32.convert(DECIMAL64.decode(self.0 as u128), strategy);
+        Decimal32(DECIMAL32.encode(value.0) as u32)
+    }
+}
+
+impl Decimal128 {
+    /// Narrows to a decimal64, rounding digits that don't fit with `strategy`.
+    ///
+    /// Values beyond the range of a decimal64 become an infinity, or the largest finite value
+    /// when `strategy` rounds towards zero. NaN payloads that don't fit are dropped.
+    #[inline]
+    pub fn to_decimal64(self, strategy: RoundingStrategy) -> Decimal64 {
+        let value = DECIMAL64.convert(DECIMAL128.decode(self.0), strategy);
+        Decimal64(DECIMAL64.encode(value.0) as u64)
+    }
+}
+
+impl Decimal {
+    /// Converts to a decimal128, rounding digits that don't fit with `strategy`.
+    ///
+    /// Every decimal is in the range of a decimal128, so only digits beyond 34 significant
+    /// digits are rounded. NaN payloads and the signaling bit are kept.
+    #[inline]
+    pub fn to_decimal128(&self, strategy: RoundingStrategy) -> Decimal128 {
+        Decimal128(DECIMAL128.encode(DECIMAL128.unpack_decimal(self, strategy).0))
+    }
+
+    /// Narrows to a decimal64, rounding digits that don't fit with `strategy`.
+    ///
+    /// Every decimal is in the range of a decimal64, so only digits beyond 16 significant
+    /// digits are rounded.
+    #[inline]
+    pub fn to_decimal64(&self, strategy: RoundingStrategy) -> Decimal64 {
+        Decimal64(DECIMAL64.encode(DECIMAL64.unpack_decimal(self, strategy).0) as u64)
+    }
+
+    /// Narrows to a decimal32, rounding digits that don't fit with `strategy`.
+    ///
+    /// Every decimal is within the largest decimal32, but values below its smallest unit
+    /// `10^-101` are rounded to a multiple of it.
+    #[inline]
+    pub fn to_decimal32(&self, strategy: RoundingStrategy) -> Decimal32 {
+        Decimal32(DECIMAL32.encode(DECIMAL32.unpack_decimal(self, strategy).0) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Decimal128, Decimal32, Decimal64};
+    use crate::{Decimal, DecimalCategory, RoundingStrategy};
+    use std::convert::TryFrom;
+
+    /// Known answers of the BID encoding, with values that convert exactly to a decimal.
+    const DECIMAL64_VECTORS: [(u64, &str); 8] = [
+        (0x31C0000000000001, "1"),
+        (0xB1C0000000000001, "-1"),
+        (0x31C0000000000000, "0"),
+        (0x31A000000000000A, "1.0"),
+        (0x31C000000000007B, "123"),
+        // Coefficients from 2^53 on use the large form with the combination field `11`.
+        (0x6C7386F26FC0FFFF, "9999999999999999"),
+        (0xEC6386F26FC0FFFF, "-99999999999999.99"),
+        (0x6C6386F26FC0FFFF, "99999999999999.99"),
+    ];
+
+    const DECIMAL32_VECTORS: [(u32, &str); 5] = [
+        (0x32800001, "1"),
+        (0xB2800001, "-1"),
+        (0x3280000A, "10"),
+        (0x6CB8967F, "9999999"),
+        (0x3112D687, "1234.567"),
+    ];
+
+    const DECIMAL128_VECTORS: [(u128, &str); 6] = [
+        (0x30400000000000000000000000000001, "1"),
+        (0xB0400000000000000000000000000001, "-1"),
+        (0x303E000000000000000000000000000A, "1.0"),
+        (0x3041ED09BEAD87C0378D8E63FFFFFFFF, "9999999999999999999999999999999999"),
+        (0xB01800018EE90FF6C373E0EE4E3F0AD2, "-1234567890.12345678901234567890"),
+        (
+            0x2FFDED09BEAD87C0378D8E63FFFFFFFF,
+            "0.9999999999999999999999999999999999",
+        ),
+    ];
+
+    #[test]
+    fn decimal128_known_answers() {
+        for (bits, s) in DECIMAL128_VECTORS {
+            let value = Decimal128::from_bits(bits);
+            assert_eq!(Decimal::try_from(value).unwrap().to_string(), s, "{:#x}", bits);
+            let d: Decimal = s.parse().unwrap();
+            assert_eq!(d.to_decimal128(RoundingStrategy::HalfEven).to_bits(), bits, "{}", s);
+            assert_eq!(Decimal128::try_from(d).unwrap().to_bits(), bits, "{}", s);
+        }
+
+        // A decimal has up to 36 significant digits, which are rounded to 34.
+        let d: Decimal = "123456789012345678901234567890123456".parse().unwrap();
+        assert!(Decimal128::try_from(d).is_err());
+        assert_eq!(
+            d.to_decimal128(RoundingStrategy::HalfEven).to_string(),
+            "123456789012345678901234567890123500"
+        );
+        assert_eq!(
+            Decimal128::from_bits(0x5FFFED09BEAD87C0378D8E63FFFFFFFF).to_string(),
+            "9999999999999999999999999999999999E+6111"
+        );
+        assert_eq!(Decimal128::from_bits(1).to_string(), "1E-6176");
+    }
+
+    #[test]
+    fn decimal128_nan_payloads() {
+        assert_eq!(Decimal128::NAN.to_bits(), 0x7C000000000000000000000000000000);
+        assert_eq!(Decimal128::INFINITY.to_bits(), 0x78000000000000000000000000000000);
+        assert_eq!(Decimal128::NEG_INFINITY.to_bits(), 0xF8000000000000000000000000000000);
+
+        // The payload and the signaling bit survive a round trip through the encoding.
+        for (s, bits) in [
+            ("NaN", 0x7C000000000000000000000000000000),
+            ("NaN123", 0x7C00000000000000000000000000007B),
+            ("sNaN", 0x7E000000000000000000000000000000),
+            ("sNaN123", 0x7E00000000000000000000000000007B),
+            (
+                "sNaN999999999999999999999999999999999",
+                0x7E00314DC6448D9338C15B09FFFFFFFF,
+            ),
+        ] {
+            let d: Decimal = s.parse().unwrap();
+            let value = d.to_decimal128(RoundingStrategy::HalfEven);
+            assert_eq!(value.to_bits(), bits, "{}", s);
+            assert_eq!(value.to_string(), s);
+            assert!(value.is_nan());
+            assert_eq!(value.classify(), DecimalCategory::NaN);
+            let back = Decimal::try_from(value).unwrap();
+            assert_eq!(back.to_string(), s);
+            assert_eq!(back.is_signaling_nan(), s.starts_with('s'));
+        }
+
+        // Payloads of 34 digits or more don't fit and are dropped.
+        let d: Decimal = "NaN1000000000000000000000000000000000".parse().unwrap();
+        assert_eq!(d.to_decimal128(RoundingStrategy::HalfEven).to_string(), "NaN");
+        assert_eq!(
+            Decimal128::from_bits(0x7C00314DC6448D9338C15B0A00000000).to_string(),
+            "NaN"
+        );
+
+        // Widening keeps payloads, and narrowing drops those that don't fit.
+        let snan = Decimal128::from(Decimal64::from_bits(0x7E00000000000007));
+        assert_eq!(snan.to_string(), "sNaN7");
+        assert_eq!(
+            snan.to_decimal64(RoundingStrategy::HalfEven).to_bits(),
+            0x7E00000000000007
+        );
+        let wide: Decimal = "sNaN1000000000000000".parse().unwrap();
+        let wide = wide.to_decimal128(RoundingStrategy::HalfEven);
+        assert_eq!(wide.to_string(), "sNaN1000000000000000");
+        assert_eq!(Decimal64::try_from(wide).unwrap().to_string(), "sNaN");
+    }
+
+    #[test]
+    fn decimal128_conversions() {
+        let one = Decimal128::from_bits(0x30400000000000000000000000000001);
+        assert_eq!(Decimal128::from(Decimal64::from_bits(0x31C0000000000001)), one);
+        assert_eq!(Decimal128::from(Decimal32::from_bits(0x32800001)), one);
+        assert_eq!(Decimal128::from(Decimal64::INFINITY), Decimal128::INFINITY);
+        assert_eq!(
+            Decimal128::from(Decimal64::from_bits(0x77FB86F26FC0FFFF)).to_string(),
+            "9999999999999999E+369"
+        );
+
+        assert_eq!(Decimal64::try_from(one).unwrap().to_bits(), 0x31C0000000000001);
+        let max = Decimal128::from_bits(0x3041ED09BEAD87C0378D8E63FFFFFFFF);
+        assert!(Decimal64::try_from(max).is_err());
+        assert_eq!(
+            max.to_decimal64(RoundingStrategy::HalfEven).to_string(),
+            "10000000000000000000000000000000000"
+        );
+        assert_eq!(
+            max.to_decimal64(RoundingStrategy::Down).to_string(),
+            "9999999999999999000000000000000000"
+        );
+        let huge = Decimal128::from_bits(0x5FFFED09BEAD87C0378D8E63FFFFFFFF);
+        assert_eq!(huge.to_decimal64(RoundingStrategy::HalfEven), Decimal64::INFINITY);
+        assert!(Decimal::try_from(huge).is_err());
+        assert_eq!(huge.total_cmp(&Decimal128::INFINITY), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn decimal64_known_answers() {
+        for (bits, s) in DECIMAL64_VECTORS {
+            let value = Decimal64::from_bits(bits);
+            assert_eq!(Decimal::try_from(value).unwrap().to_string(), s, "{:#x}", bits);
+            let d: Decimal = s.parse().unwrap();
+            assert_eq!(d.to_decimal64(RoundingStrategy::HalfEven).to_bits(), bits, "{}", s);
+            assert_eq!(Decimal64::try_from(d).unwrap().to_bits(), bits, "{}", s);
+        }
+    }
+
+    #[test]
+    fn decimal32_known_answers() {
+        for (bits, s) in DECIMAL32_VECTORS {
+            let value = Decimal32::from_bits(bits);
+            assert_eq!(Decimal::try_from(value).unwrap().to_string(), s, "{:#x}", bits);
+            let d: Decimal = s.parse().unwrap();
+            assert_eq!(d.to_decimal32(RoundingStrategy::HalfEven).to_bits(), bits, "{}", s);
+        }
+    }
+
+    #[test]
+    fn extremes() {
+        // The largest finite values and the smallest subnormal.
+        assert_eq!(
+            Decimal64::from_bits(0x77FB86F26FC0FFFF).to_string(),
+            "9999999999999999E+369"
+        );
+        assert_eq!(Decimal32::from_bits(0x77F8967F).to_string(), "9999999E+90");
+        assert_eq!(Decimal64::from_bits(0x0000000000000001).to_string(), "1E-398");
+        assert!(Decimal::try_from(Decimal64::from_bits(0x77FB86F26FC0FFFF)).is_err());
+    }
+
+    #[test]
+    fn special_values() {
+        assert_eq!(Decimal64::INFINITY.to_bits(), 0x7800000000000000);
+        assert_eq!(Decimal64::NEG_INFINITY.to_bits(), 0xF800000000000000);
+        assert_eq!(Decimal64::NAN.to_bits(), 0x7C00000000000000);
+        assert_eq!(Decimal32::INFINITY.to_bits(), 0x78000000);
+        assert_eq!(Decimal32::NAN.to_bits(), 0x7C000000);
+
+        let snan = Decimal64::from_bits(0x7E00000000000000);
+        assert!(snan.is_nan() && !snan.is_infinite());
+        assert_eq!(snan.classify(), DecimalCategory::NaN);
+        assert!(Decimal::try_from(snan).unwrap().is_signaling_nan());
+        let nan = Decimal64::from_bits(0x7C00000000000007);
+        assert_eq!(nan.classify(), DecimalCategory::NaN);
+        assert_eq!(Decimal::try_from(nan).unwrap().to_string(), "NaN7");
+        assert_eq!(Decimal32::from_bits(0x7E000000).classify(), DecimalCategory::NaN);
+
+        for inf in [Decimal64::INFINITY, Decimal64::NEG_INFINITY] {
+            assert!(inf.is_infinite() && !inf.is_nan());
+            assert_eq!(inf.classify(), DecimalCategory::Infinite);
+            assert!(Decimal::try_from(inf).is_err());
+        }
+        assert_eq!(Decimal32::NEG_INFINITY.classify(), DecimalCategory::Infinite);
+
+        assert_eq!(
+            Decimal64::from_bits(0xB1C0000000000000).classify(),
+            DecimalCategory::Zero
+        );
+        assert_eq!(
+            Decimal64::from_bits(0xB1C0000000000001).classify(),
+            DecimalCategory::NormalNegative
+        );
+        assert_eq!(
+            Decimal32::from_bits(0x32800001).classify(),
+            DecimalCategory::NormalPositive
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Classification of values.
+
+use crate::decimal::Decimal;
+
+/// The category of a value, as returned by [`Decimal::classify`].
+///
+/// Serializers and validators can branch on a single exhaustive `match` instead of a chain of
+/// predicates.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DecimalCategory {
+    /// Zero, whatever its scale or sign.
+    Zero,
+    /// A non-zero positive number.
+    NormalPositive,
+    /// A non-zero negative number.
+    NormalNegative,
+    /// A quiet or signaling NaN.
+    NaN,
+    /// Positive or negative infinity, only found in [`Decimal32`](crate::Decimal32),
+    /// [`Decimal64`](crate::Decimal64) and [`Decimal128`](crate::Decimal128).
+    Infinite,
+}
+
+impl Decimal {
+    /// Returns the category of this decimal, which is never [`DecimalCategory::Infinite`].
+    #[inline]
+    pub const fn classify(&self) -> DecimalCategory {
+        if self.is_nan() {
+            DecimalCategory::NaN
+        } else if self.is_zero() {
+            DecimalCategory::Zero
+        } else if self.is_sign_negative() {
+            DecimalCategory::NormalNegative
+        } else {
+            DecimalCategory::NormalPositive
+        }
+    }
+
+    /// Returns `true` if this decimal is non-zero but smaller in magnitude than `10^-scale`, so
+    /// it has no significant digit within `scale` digits after the decimal point.
+    ///
+    /// Such a value can't be stored in a column with that scale without becoming zero, or
+    /// rounding up to `10^-scale`.
+    #[inline]
+    pub fn is_subnormal_for(&self, scale: i32) -> bool {
+        !self.is_nan() && !self.is_zero() && self.exponent() as i64 <= -(scale as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decimal, DecimalCategory};
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn classify() {
+        assert_eq!(dec("0.00").classify(), DecimalCategory::Zero);
+        assert_eq!(dec("-1.5").classify(), DecimalCategory::NormalNegative);
+        assert_eq!(dec("1e40").classify(), DecimalCategory::NormalPositive);
+        assert_eq!(dec("sNaN3").classify(), DecimalCategory::NaN);
+    }
+
+    #[test]
+    fn is_subnormal_for() {
+        assert!(dec("0.005").is_subnormal_for(2));
+        assert!(!dec("0.01").is_subnormal_for(2));
+        assert!(!dec("-0.015").is_subnormal_for(2));
+        assert!(dec("-0.0099").is_subnormal_for(2));
+        assert!(dec("5").is_subnormal_for(-1));
+        assert!(!dec("10").is_subnormal_for(-1));
+        assert!(!Decimal::ZERO.is_subnormal_for(2));
+        assert!(!Decimal::NAN.is_subnormal_for(2));
+
+        // Extreme scales don't overflow.
+        assert!(Decimal::MAX.is_subnormal_for(i32::MIN));
+        assert!(Decimal::MIN.is_subnormal_for(-45));
+        assert!(!Decimal::MAX.is_subnormal_for(-44));
+        assert!(!dec("0.001").is_subnormal_for(i32::MAX));
+        assert!(!dec("0.001").is_subnormal_for(3));
+    }
+}
@@ -0,0 +1,419 @@
+//! Decimal comparison.
+
+use crate::decimal::{Decimal, DEC_DIGITS, MAX_INT_DIGITS, MAX_SCALE, POWERS_10, SIGN_NEG};
+use crate::error::DecimalParseError;
+use crate::parse::{mantissa_digits, scan, Scanned};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+use std::cmp::Ordering;
+
+impl Decimal {
+    /// Compares absolute values.
+    #[inline]
+    pub(crate) fn cmp_abs(&self, other: &Decimal) -> Ordering {
+        let (a, b) = (self.limbs(), other.limbs());
+        if a.is_empty() || b.is_empty() || self.weight != other.weight {
+            bench_count!(CMP_WEIGHT, 1);
+        }
+        match (a.is_empty(), b.is_empty()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+
+        self.weight.cmp(&other.weight).then_with(|| {
+            bench_count!(CMP_LIMBS, 1);
+            a.cmp(b)
+        })
+    }
+
+    /// Compares this decimal with the number in `s` without constructing a second decimal.
+    ///
+    /// `s` is accepted with the same grammar as [`FromStr`](std::str::FromStr), but its digits
+    /// are compared exactly, so it is not limited to the precision of a decimal.
+    /// NaN is equal to NaN and greater than any number.
+    pub fn cmp_str(&self, s: &str) -> Result<Ordering, DecimalParseError> {
+        let (negative, int, frac, exp) = match scan(s.as_bytes())? {
+            Scanned::NaN { .. } => return Ok(if self.is_nan() { Ordering::Equal } else { Ordering::Less }),
+            Scanned::Number {
+                negative,
+                int,
+                frac,
+                exp,
+            } => (negative, int, frac, exp),
+        };
+
+        if self.is_nan() {
+            return Ok(Ordering::Greater);
+        }
+
+        let leading_zeros = mantissa_digits(int, frac).take_while(|&d| d == 0).count();
+        let len = int.len() + frac.len() - leading_zeros;
+
+        let other_negative = negative && len > 0;
+        if self.is_sign_negative() != other_negative {
+            return Ok(if other_negative {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            });
+        }
+
+        let ord = match (self.is_zero(), len == 0) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => {
+                let other_exponent = len as i64 + exp - frac.len() as i64;
+                (self.exponent() as i64).cmp(&other_exponent).then_with(|| {
+                    let mut a = self.decimal_digits();
+                    let mut b = mantissa_digits(int, frac).skip(leading_zeros);
+                    loop {
+                        match (a.next(), b.next()) {
+                            (Some(x), Some(y)) if x == y => continue,
+                            (Some(x), Some(y)) => break x.cmp(&y),
+                            (Some(x), None) if x == 0 && a.all(|d| d == 0) => break Ordering::Equal,
+                            (Some(_), None) => break Ordering::Greater,
+                            (None, Some(y)) if y == 0 && b.all(|d| d == 0) => break Ordering::Equal,
+                            (None, Some(_)) => break Ordering::Less,
+                            (None, None) => break Ordering::Equal,
+                        }
+                    }
+                })
+            }
+        };
+
+        Ok(if self.is_sign_negative() { ord.reverse() } else { ord })
+    }
+
+    /// Compares with the exact binary value of `f`, without rounding either side.
+    ///
+    /// For example, `0.1` is less than `0.1f64`, whose exact value is
+    /// `0.1000000000000000055511151231257827...`. Infinities compare beyond every decimal, and
+    /// `None` is returned if either value is NaN.
+    pub fn cmp_f64(&self, f: f64) -> Option<Ordering> {
+        if self.is_nan() || f.is_nan() {
+            return None;
+        }
+
+        let f_negative = f < 0.0;
+        let self_negative = self.is_sign_negative();
+        let ord = match (self.is_zero(), f == 0.0) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) if self_negative != f_negative => {
+                return Some(if self_negative {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                });
+            }
+            (false, false) => self.cmp_abs_f64(f.abs()),
+        };
+
+        let negative = if self.is_zero() { f_negative } else { self_negative };
+        Some(if negative { ord.reverse() } else { ord })
+    }
+
+    /// Compares the absolute value of a non-zero decimal with a positive `f`.
+    fn cmp_abs_f64(&self, f: f64) -> Ordering {
+        // Every decimal is less than `10^45`, which is above the `f64` nearest to it, and every
+        // non-zero decimal is at least `10^-130`.
+        if f > 1e45 {
+            return Ordering::Less;
+        }
+        if f < 1e-131 {
+            return Ordering::Greater;
+        }
+
+        // `f = mantissa * 2^exp` exactly.
+        let bits = f.to_bits();
+        let biased = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = bits & ((1 << 52) - 1);
+        let (mut mantissa, mut exp) = if biased == 0 {
+            (fraction, -1074)
+        } else {
+            (fraction | 1 << 52, biased - 1075)
+        };
+        let shift = (mantissa.trailing_zeros() as i32).min(-exp).max(0);
+        mantissa >>= shift;
+        exp += shift;
+
+        if exp >= 0 {
+            // Less than `2^150`, so `2^exp` fits.
+            let power = Decimal::from_u128_exact(1 << exp);
+            let value = Var::from_i64(mantissa as i64).mul(&Var::from_decimal(&power));
+            return Var::from_decimal(self).cmp_abs(&value);
+        }
+
+        // Truncates `f` one digit below the smallest unit of a decimal, so a decimal equal to the
+        // truncated value is less than `f` exactly when digits were discarded.
+        let mut divisor = Var::from_decimal(&Decimal::ONE);
+        let mut k = -exp;
+        while k > 0 {
+            let step = k.min(100);
+            divisor = divisor.mul(&Var::from_decimal(&Decimal::from_u128_exact(1 << step)));
+            k -= step;
+        }
+        let dividend = Var::from_decimal(&Decimal::from_u128_exact(mantissa as u128));
+        let (value, inexact) = dividend.div(&divisor, MAX_SCALE + 1);
+        match Var::from_decimal(self).cmp_abs(&value) {
+            Ordering::Equal if inexact => Ordering::Less,
+            ord => ord,
+        }
+    }
+
+    /// Returns `true` if both decimals are equal after rounding half up to `dp` digits after
+    /// the decimal point.
+    ///
+    /// This is the comparison wanted when checking results against fixtures computed elsewhere.
+    /// NaN is only equal to NaN.
+    pub fn approx_eq_dp(&self, other: &Decimal, dp: i32) -> bool {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => return true,
+            (false, false) => {}
+            _ => return false,
+        }
+
+        // Beyond these scales, rounding keeps every digit or rounds every value to zero, so
+        // clamping doesn't change the result.
+        let dp = dp.clamp(-MAX_INT_DIGITS - 1, MAX_SCALE);
+        let mut a = Var::from_decimal(self);
+        let mut b = Var::from_decimal(other);
+        a.round(dp, RoundingStrategy::HalfUp, false);
+        b.round(dp, RoundingStrategy::HalfUp, false);
+        match (a.is_zero(), b.is_zero()) {
+            (true, true) => true,
+            (false, false) => a.is_negative() == b.is_negative() && a.cmp_abs(&b) == Ordering::Equal,
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if the decimals differ by at most `n` units in the last place, where a
+    /// unit is `10^-scale`, e.g. one cent for `scale = 2`.
+    ///
+    /// The difference is computed exactly, so reconciliation can accept off-by-one-cent
+    /// results and flag anything larger. `scale` may be negative for units of tens, hundreds,
+    /// and so on. NaN is only within any tolerance of NaN.
+    pub fn within_ulps(&self, other: &Decimal, n: u32, scale: i32) -> bool {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => return true,
+            (false, false) => {}
+            _ => return false,
+        }
+
+        let difference = Var::from_decimal(self).sub(&Var::from_decimal(other));
+        // Beyond these scales, the tolerance is below any non-zero difference or above any
+        // difference, so clamping doesn't change the result.
+        let shift = -scale.clamp(-MAX_INT_DIGITS - 1, MAX_SCALE + 10);
+        let unit = Var::from_limb(
+            POWERS_10[shift.rem_euclid(DEC_DIGITS) as usize],
+            shift.div_euclid(DEC_DIGITS),
+        );
+        let tolerance = Var::from_i64(n as i64).mul(&unit);
+        difference.cmp_abs(&tolerance) != Ordering::Greater
+    }
+
+    /// Compares in the total order of the crate, which refines [`Ord`] to tell apart every
+    /// representation: numbers < NaN, as `-Infinity < numbers < Infinity < NaN` with the
+    /// infinities of [`Decimal32`](crate::Decimal32), [`Decimal64`](crate::Decimal64) and
+    /// [`Decimal128`](crate::Decimal128).
+    ///
+    /// Equal numbers are ordered by scale as IEEE 754 `totalOrder` orders by exponent, so
+    /// `1.00 < 1.0 < 1` and `-1 < -1.0 < -1.00`. Signaling NaN sorts before quiet NaN, and NaNs
+    /// of the same kind by payload. [`sort_key_total`](Decimal::sort_key_total) gives keys in
+    /// this order.
+    pub fn total_cmp(&self, other: &Decimal) -> Ordering {
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => self
+                .is_signaling_nan()
+                .cmp(&other.is_signaling_nan())
+                .reverse()
+                .then_with(|| self.nan_payload().cmp(&other.nan_payload())),
+            (true, false) => Ordering::Greater,
+            (false, true) => Ordering::Less,
+            (false, false) => self.cmp(other).then_with(|| {
+                // Equal numbers have the same sign, as zero is always positive.
+                let ord = other.dscale.cmp(&self.dscale);
+                if self.is_sign_negative() {
+                    ord.reverse()
+                } else {
+                    ord
+                }
+            }),
+        }
+    }
+}
+
+impl PartialEq for Decimal {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    /// NaN is equal to NaN and greater than any number, so decimals have a total order.
+    /// Payloads and signaling flags of NaN are ignored.
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.is_nan() || other.is_nan() {
+            bench_count!(CMP_NAN, 1);
+        }
+        match (self.is_nan(), other.is_nan()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            (false, false) => {}
+        }
+
+        if self.is_sign_negative() != other.is_sign_negative() {
+            bench_count!(CMP_SIGN, 1);
+        }
+        match (self.sign, other.sign) {
+            (SIGN_NEG, SIGN_NEG) => other.cmp_abs(self),
+            (SIGN_NEG, _) => Ordering::Less,
+            (_, SIGN_NEG) => Ordering::Greater,
+            _ => self.cmp_abs(other),
+        }
+    }
+}
+
+impl PartialEq<&Decimal> for Decimal {
+    #[inline]
+    fn eq(&self, other: &&Decimal) -> bool {
+        self.eq(*other)
+    }
+}
+
+impl PartialEq<Decimal> for &Decimal {
+    #[inline]
+    fn eq(&self, other: &Decimal) -> bool {
+        (*self).eq(other)
+    }
+}
+
+impl PartialOrd<&Decimal> for Decimal {
+    #[inline]
+    fn partial_cmp(&self, other: &&Decimal) -> Option<Ordering> {
+        Some(self.cmp(*other))
+    }
+}
+
+impl PartialOrd<Decimal> for &Decimal {
+    #[inline]
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some((*self).cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Decimal;
+
+    const VALUES: [&str; 16] = [
+        "0",
+        "-0.00",
+        "1",
+        "1.000",
+        "-1",
+        "0.000000001",
+        "-0.000000001",
+        "999999999",
+        "1000000000",
+        "123456789.123456789",
+        "123456789.12345679",
+        "-999999999999999999999999999999999999",
+        "999999999999999999999999999999999999",
+        "1e-130",
+        "NaN",
+        "sNaN3",
+    ];
+
+    #[test]
+    fn cmp_str_agrees_with_cmp() {
+        for a in VALUES {
+            let x = a.parse::<Decimal>().unwrap();
+            for b in VALUES {
+                let y = b.parse::<Decimal>().unwrap();
+                assert_eq!(x.cmp_str(b).unwrap(), x.cmp(&y), "{} vs {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn cmp_str_beyond_precision() {
+        let x = "1".parse::<Decimal>().unwrap();
+        let long = format!("1.{}1", "0".repeat(200));
+        assert_eq!(x.cmp_str(&long).unwrap(), std::cmp::Ordering::Less);
+        assert!(x.cmp_str("1x").is_err());
+    }
+
+    #[test]
+    fn cmp_f64_exact() {
+        use std::cmp::Ordering::{Equal, Greater, Less};
+        let dec = |s: &str| s.parse::<Decimal>().unwrap();
+
+        // `0.1f64` is `0.1000000000000000055511151231257827021181583404541015625`.
+        assert_eq!(dec("0.1").cmp_f64(0.1), Some(Less));
+        assert_eq!(dec("0.1000000000000000055511151231257827").cmp_f64(0.1), Some(Less));
+        assert_eq!(dec("0.1000000000000000055511151231257828").cmp_f64(0.1), Some(Greater));
+        assert_eq!(dec("-0.1").cmp_f64(-0.1), Some(Greater));
+        assert_eq!(dec("0.50").cmp_f64(0.5), Some(Equal));
+        assert_eq!(
+            dec("1267650600228229401496703205376").cmp_f64(2f64.powi(100)),
+            Some(Equal)
+        );
+        // `1e23f64` is `99999999999999991611392`.
+        assert_eq!(dec("1e23").cmp_f64(1e23), Some(Greater));
+        assert_eq!(dec("99999999999999991611392").cmp_f64(1e23), Some(Equal));
+
+        assert_eq!(dec("0").cmp_f64(-0.0), Some(Equal));
+        assert_eq!(dec("0.00").cmp_f64(0.0), Some(Equal));
+        assert_eq!(dec("0").cmp_f64(5e-324), Some(Less));
+        assert_eq!(dec("0").cmp_f64(-5e-324), Some(Greater));
+        assert_eq!(dec("1e-130").cmp_f64(5e-324), Some(Greater));
+        assert_eq!(dec("-1e-130").cmp_f64(-f64::MIN_POSITIVE / 2.0), Some(Less));
+        // `1e-130f64` is slightly above `10^-130`.
+        assert_eq!(dec("1e-130").cmp_f64(1e-130), Some(Less));
+
+        assert_eq!(Decimal::MAX.cmp_f64(f64::MAX), Some(Less));
+        assert_eq!(Decimal::MIN.cmp_f64(-f64::MAX), Some(Greater));
+        assert_eq!(Decimal::MAX.cmp_f64(f64::INFINITY), Some(Less));
+        assert_eq!(Decimal::MIN.cmp_f64(f64::NEG_INFINITY), Some(Greater));
+        assert_eq!(dec("1").cmp_f64(f64::NAN), None);
+        assert_eq!(Decimal::NAN.cmp_f64(1.0), None);
+        assert_eq!(Decimal::NAN.cmp_f64(f64::INFINITY), None);
+    }
+
+    #[test]
+    fn approx_eq_dp() {
+        let dec = |s: &str| s.parse::<Decimal>().unwrap();
+        assert!(dec("1.004").approx_eq_dp(&dec("1.0"), 2));
+        assert!(!dec("1.005").approx_eq_dp(&dec("1.0"), 2));
+        assert!(dec("-1.005").approx_eq_dp(&dec("-1.01"), 2));
+        assert!(!dec("-1.005").approx_eq_dp(&dec("1.01"), 2));
+        assert!(dec("0.004").approx_eq_dp(&dec("-0.004"), 2));
+        assert!(dec("1249").approx_eq_dp(&dec("1150"), -2));
+        assert!(Decimal::NAN.approx_eq_dp(&dec("sNaN1"), 2));
+        assert!(!Decimal::NAN.approx_eq_dp(&dec("0"), 2));
+
+        // Extreme numbers of digits compare exactly, or every value as zero.
+        let tiny = dec("1e-130");
+        assert!(!tiny.approx_eq_dp(&Decimal::ZERO, i32::MAX));
+        assert!(tiny.approx_eq_dp(&tiny, i32::MAX));
+        assert!(Decimal::MAX.approx_eq_dp(&Decimal::MIN, i32::MIN));
+        assert!(!Decimal::MAX.approx_eq_dp(&Decimal::MIN, -45));
+    }
+}
@@ -0,0 +1,104 @@
+//! Column formatting.
+
+use crate::decimal::Decimal;
+use crate::fmt::Buf;
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+
+/// Formats columns of decimals into a reusable byte buffer, e.g. for CSV output.
+///
+/// Values are written as by [`Display`](std::fmt::Display) and joined by a separator, `\n` by
+/// default. The buffer is kept between calls, so formatting many chunks allocates only when a
+/// chunk is longer than all previous ones.
+#[derive(Clone, Debug)]
+pub struct ColumnWriter {
+    buf: Vec<u8>,
+    separator: u8,
+    scale: Option<i32>,
+    strategy: RoundingStrategy,
+}
+
+impl ColumnWriter {
+    /// Creates a writer separating values with `\n` and keeping their scales.
+    #[inline]
+    pub fn new() -> ColumnWriter {
+        ColumnWriter {
+            buf: Vec::new(),
+            separator: b'\n',
+            scale: None,
+            strategy: RoundingStrategy::HalfUp,
+        }
+    }
+
+    /// Sets the separator written between values.
+    #[inline]
+    pub fn separator(mut self, separator: u8) -> ColumnWriter {
+        self.separator = separator;
+        self
+    }
+
+    /// Sets the scale every value is rounded or padded to with `strategy`, or `None` to keep
+    /// the scales of the values.
+    ///
+    /// A value that would need more than `MAX_PRECISION` digits at `scale` is written with fewer
+    /// digits after the decimal point.
+    #[inline]
+    pub fn scale(mut self, scale: Option<i32>, strategy: RoundingStrategy) -> ColumnWriter {
+        self.scale = scale;
+        self.strategy = strategy;
+        self
+    }
+
+    /// Formats `values` into the buffer, replacing its previous content, and returns it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if rounding a value to the scale overflows.
+    pub fn write(&mut self, values: &[Decimal]) -> &[u8] {
+        self.buf.clear();
+        for (i, value) in values.iter().enumerate() {
+            if i > 0 {
+                self.buf.push(self.separator);
+            }
+            self.write_value(value);
+        }
+        &self.buf
+    }
+
+    /// Returns the content of the buffer.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    fn write_value(&mut self, value: &Decimal) {
+        if value.is_nan() {
+            self.buf.extend_from_slice(value.to_string().as_bytes());
+            return;
+        }
+
+        let value = match self.scale {
+            Some(scale) => {
+                let mut var = Var::from_decimal(value);
+                var.round(scale, self.strategy, false);
+                let (rounded, _) = var.into_decimal(self.strategy).expect("overflow when rounding decimal");
+                rounded
+            }
+            None => *value,
+        };
+
+        if value.is_sign_negative() {
+            self.buf.push(b'-');
+        }
+        let mut buf = Buf::new();
+        value.fmt_abs(&mut buf);
+        self.buf.extend_from_slice(buf.as_str().as_bytes());
+    }
+}
+
+impl Default for ColumnWriter {
+    #[inline]
+    fn default() -> Self {
+        ColumnWriter::new()
+    }
+}
@@ -0,0 +1,3 @@
+//! Shims for migrating from other decimal crates.
+
+pub mod rust_decimal_api;
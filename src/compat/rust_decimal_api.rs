@@ -0,0 +1,108 @@
+//! Methods named and behaving as those of `rust_decimal`, so a project can switch crates by
+//! replacing `use rust_decimal::Decimal` with `use fast_decimal::compat::rust_decimal_api::*`
+//! and compare the performance.
+//!
+//! `trunc_with_scale` is an inherent method of [`Decimal`] with the same semantics, but it
+//! takes the scale as `i32`.
+
+use crate::decimal::MAX_SCALE;
+use crate::error::{DecimalConvertError, DecimalParseError};
+use crate::options::ParseOptions;
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+
+pub use crate::decimal::Decimal;
+
+/// The API of `rust_decimal::Decimal` that differs from that of [`Decimal`].
+///
+/// Scales may go up to `MAX_SCALE` instead of 28. `rust_decimal` has no NaN, so the methods
+/// keep NaN as is where they can.
+pub trait RustDecimalApi: Sized {
+    /// Creates `num * 10^-scale`, e.g. `new(12345, 2)` is `123.45`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `scale` is greater than `MAX_SCALE`.
+    fn new(num: i64, scale: u32) -> Self;
+
+    /// Parses a decimal, failing with [`DecimalParseError::Overflow`] instead of rounding
+    /// digits that don't fit.
+    fn from_str_exact(s: &str) -> Result<Self, DecimalParseError>;
+
+    /// Rounds to `dp` digits after the decimal point with banker's rounding, keeping a decimal
+    /// with at most `dp` digits after the decimal point as is.
+    fn round_dp(&self, dp: u32) -> Self;
+
+    /// Returns the signed coefficient, the value multiplied by `10^scale`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the decimal is NaN or an integer beyond the range of `i128`.
+    fn mantissa(&self) -> i128;
+
+    /// Sets the scale keeping the coefficient, so `1234` with scale 2 becomes `12.34`.
+    ///
+    /// Returns [`DecimalConvertError::Overflow`] if `scale` is greater than `MAX_SCALE` or the
+    /// coefficient has too many digits for it, and [`DecimalConvertError::NaN`] for NaN,
+    /// leaving the decimal unchanged.
+    fn set_scale(&mut self, scale: u32) -> Result<(), DecimalConvertError>;
+}
+
+impl RustDecimalApi for Decimal {
+    #[inline]
+    fn new(num: i64, scale: u32) -> Decimal {
+        assert!(scale <= MAX_SCALE as u32, "scale exceeds MAX_SCALE");
+        Decimal::from_coefficient(num < 0, num.unsigned_abs() as u128, scale as i32)
+    }
+
+    #[inline]
+    fn from_str_exact(s: &str) -> Result<Decimal, DecimalParseError> {
+        Decimal::parse_with(s, &ParseOptions::new().rounding(None))
+    }
+
+    fn round_dp(&self, dp: u32) -> Decimal {
+        if dp as i64 >= self.scale() as i64 {
+            return *self;
+        }
+        let mut var = Var::from_decimal(self);
+        var.round(dp as i32, RoundingStrategy::HalfEven, false);
+        // Dropping digits never needs more precision.
+        let (result, _) = var.into_decimal(RoundingStrategy::HalfEven).unwrap();
+        result
+    }
+
+    #[inline]
+    fn mantissa(&self) -> i128 {
+        assert!(!self.is_nan(), "NaN has no mantissa");
+        let coefficient = self
+            .coefficient()
+            .and_then(|c| i128::try_from(c).ok())
+            .expect("mantissa overflows i128");
+        if self.is_sign_negative() {
+            -coefficient
+        } else {
+            coefficient
+        }
+    }
+
+    fn set_scale(&mut self, scale: u32) -> Result<(), DecimalConvertError> {
+        if self.is_nan() {
+            return Err(DecimalConvertError::NaN);
+        }
+        if scale > MAX_SCALE as u32 {
+            return Err(DecimalConvertError::Overflow);
+        }
+        let coefficient = self
+            .coefficient()
+            .and_then(|c| i128::try_from(c).ok())
+            .ok_or(DecimalConvertError::Overflow)?;
+        let coefficient = if self.is_sign_negative() {
+            -coefficient
+        } else {
+            coefficient
+        };
+        *self =
+            Decimal::from_coefficient_exponent(coefficient, -(scale as i32)).ok_or(DecimalConvertError::Overflow)?;
+        Ok(())
+    }
+}
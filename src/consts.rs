@@ -0,0 +1,59 @@
+//! Mathematical constants, correctly rounded to `MAX_PRECISION` significant digits.
+
+use crate::decimal::Decimal;
+
+/// Archimedes' constant π, `3.14159265358979323846264338327950288`.
+pub const PI: Decimal = Decimal::from_raw_parts(false, &[3, 141_592_653, 589_793_238, 462_643_383, 279_502_880], 0, 35);
+
+/// Euler's number e, `2.71828182845904523536028747135266250`.
+pub const E: Decimal = Decimal::from_raw_parts(false, &[2, 718_281_828, 459_045_235, 360_287_471, 352_662_500], 0, 35);
+
+/// ln(2), `0.693147180559945309417232121458176568`.
+pub const LN_2: Decimal = Decimal::from_raw_parts(false, &[693_147_180, 559_945_309, 417_232_121, 458_176_568], -1, 36);
+
+/// ln(10), `2.30258509299404568401799145468436421`.
+pub const LN_10: Decimal =
+    Decimal::from_raw_parts(false, &[2, 302_585_092, 994_045_684, 17_991_454, 684_364_210], 0, 35);
+
+/// √2, `1.41421356237309504880168872420969808`.
+pub const SQRT_2: Decimal =
+    Decimal::from_raw_parts(false, &[1, 414_213_562, 373_095_048, 801_688_724, 209_698_080], 0, 35);
+
+#[cfg(test)]
+mod tests {
+    use super::{E, LN_10, LN_2, PI, SQRT_2};
+    use crate::Decimal;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn digits() {
+        let constants = [
+            (PI, "3.14159265358979323846264338327950288"),
+            (E, "2.71828182845904523536028747135266250"),
+            (LN_2, "0.693147180559945309417232121458176568"),
+            (LN_10, "2.30258509299404568401799145468436421"),
+            (SQRT_2, "1.41421356237309504880168872420969808"),
+        ];
+        for (constant, s) in constants {
+            assert!(constant.is_internally_consistent(), "{s}");
+            assert_eq!(constant.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn computed() {
+        let half = dec("0.5");
+        let constants = [
+            (LN_2, Decimal::TWO.ln()),
+            (LN_10, Decimal::TEN.ln()),
+            (E, Decimal::ONE.exp()),
+            (SQRT_2, Decimal::TWO.powd(&half)),
+        ];
+        for (constant, computed) in constants {
+            assert_eq!(constant.to_string(), computed.to_string());
+        }
+    }
+}
@@ -0,0 +1,610 @@
+//! Arithmetic at a reduced precision.
+
+use crate::decimal::{Decimal, MAX_INT_DIGITS, MAX_PRECISION, MAX_SCALE};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+use std::cell::Cell;
+use std::ops::{BitOr, BitOrAssign};
+
+thread_local! {
+    /// Context of the current thread, see [`with_context`].
+    static CURRENT: Cell<Context> = const { Cell::new(Context::DEFAULT) };
+}
+
+/// Precision and rounding of arithmetic, as Java's `MathContext` or the context of decNumber.
+///
+/// Results of the `*_with_context` operations of [`Decimal`] are rounded once to at most
+/// `precision` significant digits with `rounding`, so computations at a reduced precision give
+/// the same digits on every platform. A result with fewer significant digits keeps the scale it
+/// would have without a context. Each operation also reports the [`Status`] flags it raised.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Context {
+    precision: u32,
+    rounding: RoundingStrategy,
+}
+
+impl Context {
+    /// Full precision, `MAX_PRECISION` digits rounded half up, as the operators.
+    pub const DEFAULT: Context = Context {
+        precision: MAX_PRECISION,
+        rounding: RoundingStrategy::HalfUp,
+    };
+
+    /// Creates a context with `precision` significant digits rounded with `rounding`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is zero or greater than `MAX_PRECISION`.
+    #[inline]
+    pub fn new(precision: u32, rounding: RoundingStrategy) -> Context {
+        assert!(
+            precision > 0 && precision <= MAX_PRECISION,
+            "precision must be in 1..=MAX_PRECISION"
+        );
+        Context { precision, rounding }
+    }
+
+    /// Returns the number of significant digits.
+    #[inline]
+    pub const fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    /// Returns the rounding strategy.
+    #[inline]
+    pub const fn rounding(&self) -> RoundingStrategy {
+        self.rounding
+    }
+
+    /// Returns the context with `precision` significant digits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is zero or greater than `MAX_PRECISION`.
+    #[inline]
+    pub fn with_precision(self, precision: u32) -> Context {
+        Context::new(precision, self.rounding)
+    }
+
+    /// Returns the context with the rounding strategy `rounding`.
+    #[inline]
+    pub const fn with_rounding(self, rounding: RoundingStrategy) -> Context {
+        Context {
+            precision: self.precision,
+            rounding,
+        }
+    }
+
+    /// Returns the context of the current thread, which is [`Context::DEFAULT`] outside of
+    /// [`with_context`].
+    ///
+    /// The `*_with_current` operations of [`Decimal`] round with it, so the application
+    /// switches precision and rounding in one place.
+    #[inline]
+    pub fn current() -> Context {
+        CURRENT.with(|current| current.get())
+    }
+
+    /// Rounds `value` to the precision of the context.
+    ///
+    /// If rounding up carries it out of the range of a decimal, it is clamped to the largest
+    /// value of the precision, see [`Status::OVERFLOW`].
+    #[inline]
+    pub fn round(&self, value: &Decimal) -> (Decimal, Status) {
+        let status = operand_status(value, &Decimal::ZERO);
+        self.finish(Var::from_decimal(value), false, status)
+    }
+
+    /// Rounds an exact result, or a truncated one with `sticky` telling that digits were
+    /// discarded, to the precision of the context, adding the raised flags to `status`.
+    fn finish(&self, mut var: Var, sticky: bool, mut status: Status) -> (Decimal, Status) {
+        if var.is_zero() {
+            if var.dscale > MAX_SCALE {
+                status |= Status::ROUNDED;
+            }
+        } else if !var.is_nan() {
+            let precision = self.precision as i32;
+            let exact_rscale = precision - var.exponent();
+            let rscale = exact_rscale.min(MAX_SCALE);
+            if rscale < var.dscale || sticky {
+                status |= Status::ROUNDED;
+                if var.round(rscale, self.rounding, sticky) {
+                    status |= Status::INEXACT;
+                    if rscale < exact_rscale {
+                        status |= Status::UNDERFLOW;
+                    }
+                }
+                if !var.is_zero() && var.exponent() > precision - rscale {
+                    // Carried into a new digit, drops the trailing zero exactly.
+                    var.round(rscale - 1, self.rounding, false);
+                }
+            }
+        }
+
+        match var.into_decimal(self.rounding) {
+            Some((result, _)) => (result, status),
+            None => {
+                let result = self.largest(var.is_negative());
+                (result, status | Status::OVERFLOW | Status::INEXACT | Status::ROUNDED)
+            }
+        }
+    }
+
+    /// Returns the value of largest magnitude with `precision` significant digits, which is
+    /// [`Decimal::MAX`] or [`Decimal::MIN`] at full precision.
+    fn largest(&self, negative: bool) -> Decimal {
+        let coefficient = 10i128.pow(self.precision) - 1;
+        let coefficient = if negative { -coefficient } else { coefficient };
+        Decimal::from_coefficient_exponent(coefficient, MAX_INT_DIGITS - self.precision as i32).unwrap()
+    }
+}
+
+impl Default for Context {
+    #[inline]
+    fn default() -> Context {
+        Context::DEFAULT
+    }
+}
+
+/// Runs `f` with `context` as the [current](Context::current) context of this thread, and
+/// restores the previous one afterwards, even if `f` panics.
+///
+/// Scopes nest, and other threads are not affected. The operations that read it are the
+/// `*_with_current` ones, such as [`Decimal::add_with_current`]; the operators and the
+/// `*_with_context` operations don't.
+pub fn with_context<R, F: FnOnce() -> R>(context: Context, f: F) -> R {
+    struct Restore(Context);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.with(|current| current.set(self.0));
+        }
+    }
+
+    let _restore = Restore(CURRENT.with(|current| current.replace(context)));
+    f()
+}
+
+/// Exceptional conditions raised by an operation with a [`Context`], as the status flags of
+/// IEEE 754 decimal arithmetic.
+///
+/// Flags combine with `|`, so a database engine can accumulate them over an expression and
+/// raise the matching SQL warnings or errors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Status(u8);
+
+impl Status {
+    /// Non-zero digits were discarded, the result isn't exact.
+    pub const INEXACT: Status = Status(1);
+    /// Digits were discarded to fit the precision, even if they were all zeros.
+    pub const ROUNDED: Status = Status(1 << 1);
+    /// The result doesn't fit in a decimal and was clamped to the value of largest magnitude with
+    /// the precision of the context, such as `99999e40` at precision 5, or [`Decimal::MAX`] and
+    /// [`Decimal::MIN`] at full precision.
+    pub const OVERFLOW: Status = Status(1 << 2);
+    /// A non-zero result is too small to keep the precision within `MAX_SCALE` digits after the
+    /// decimal point, and is inexact.
+    pub const UNDERFLOW: Status = Status(1 << 3);
+    /// A non-zero number was divided by zero, the result is NaN.
+    pub const DIVISION_BY_ZERO: Status = Status(1 << 4);
+    /// The operation is undefined, such as `0 / 0`, a remainder by zero or a signaling NaN
+    /// operand, the result is NaN.
+    pub const INVALID_OPERATION: Status = Status(1 << 5);
+
+    /// Returns no flags.
+    #[inline]
+    pub const fn empty() -> Status {
+        Status(0)
+    }
+
+    /// Returns `true` if no flag is raised.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if all flags of `other` are raised.
+    #[inline]
+    pub const fn contains(&self, other: Status) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Status {
+    type Output = Status;
+
+    #[inline]
+    fn bitor(self, other: Status) -> Status {
+        Status(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for Status {
+    #[inline]
+    fn bitor_assign(&mut self, other: Status) {
+        self.0 |= other.0;
+    }
+}
+
+impl Decimal {
+    /// Adds, rounding the sum to the precision of `context`.
+    ///
+    /// A sum out of range is clamped to the largest value of the precision with
+    /// [`Status::OVERFLOW`].
+    #[inline]
+    pub fn add_with_context(&self, other: &Decimal, context: &Context) -> (Decimal, Status) {
+        let sum = Var::from_decimal(self).add(&Var::from_decimal(other));
+        context.finish(sum, false, operand_status(self, other))
+    }
+
+    /// Subtracts, rounding the difference to the precision of `context`, as
+    /// [`add_with_context`](Decimal::add_with_context).
+    #[inline]
+    pub fn sub_with_context(&self, other: &Decimal, context: &Context) -> (Decimal, Status) {
+        let difference = Var::from_decimal(self).sub(&Var::from_decimal(other));
+        context.finish(difference, false, operand_status(self, other))
+    }
+
+    /// Multiplies, rounding the product to the precision of `context`, as
+    /// [`add_with_context`](Decimal::add_with_context).
+    #[inline]
+    pub fn mul_with_context(&self, other: &Decimal, context: &Context) -> (Decimal, Status) {
+        let product = Var::from_decimal(self).mul(&Var::from_decimal(other));
+        context.finish(product, false, operand_status(self, other))
+    }
+
+    /// Divides, rounding the quotient to the precision of `context`.
+    ///
+    /// An exact quotient has the smallest scale not less than the scales of the operands that
+    /// fits the precision, as [`Div`](std::ops::Div). Dividing a non-zero number by zero gives
+    /// NaN with [`Status::DIVISION_BY_ZERO`], and `0 / 0` gives NaN with
+    /// [`Status::INVALID_OPERATION`].
+    pub fn div_with_context(&self, other: &Decimal, context: &Context) -> (Decimal, Status) {
+        let status = operand_status(self, other);
+        let u = Var::from_decimal(self);
+        let v = Var::from_decimal(other);
+        if let Some(nan) = u.nan_operand(&v) {
+            return context.finish(nan, false, status);
+        }
+        if v.is_zero() {
+            return if u.is_zero() {
+                (Decimal::NAN, Status::INVALID_OPERATION)
+            } else {
+                (Decimal::NAN, Status::DIVISION_BY_ZERO)
+            };
+        }
+        if u.is_zero() {
+            let zero = Var {
+                dscale: u.dscale.max(v.dscale),
+                ..u
+            };
+            return context.finish(zero, false, status);
+        }
+
+        // The quotient has at least `precision` digits down to this scale, plus a guard digit.
+        let rscale = (context.precision as i32 - u.exponent() + v.exponent()).clamp(-MAX_INT_DIGITS - 1, MAX_SCALE) + 1;
+        let (mut quotient, sticky) = u.div(&v, rscale);
+        if !sticky {
+            let scale = self.scale().max(other.scale()).max(quotient.min_scale());
+            quotient.dscale = quotient.dscale.min(scale);
+        }
+        context.finish(quotient, sticky, status)
+    }
+
+    /// Returns the remainder of the division truncated towards zero, as
+    /// [`Rem`](std::ops::Rem), rounded to the precision of `context`.
+    ///
+    /// A remainder by zero gives NaN with [`Status::INVALID_OPERATION`].
+    pub fn rem_with_context(&self, other: &Decimal, context: &Context) -> (Decimal, Status) {
+        if other.is_zero() && !self.is_nan() {
+            return (Decimal::NAN, Status::INVALID_OPERATION);
+        }
+        let (_, remainder) = self.div_rem_var(other);
+        context.finish(Var::from_decimal(&remainder), false, operand_status(self, other))
+    }
+
+    /// Adds, rounding the sum to the precision of the [current](Context::current) context, as
+    /// [`add_with_context`](Decimal::add_with_context).
+    #[inline]
+    pub fn add_with_current(&self, other: &Decimal) -> (Decimal, Status) {
+        self.add_with_context(other, &Context::current())
+    }
+
+    /// Subtracts, rounding the difference to the precision of the
+    /// [current](Context::current) context, as [`sub_with_context`](Decimal::sub_with_context).
+    #[inline]
+    pub fn sub_with_current(&self, other: &Decimal) -> (Decimal, Status) {
+        self.sub_with_context(other, &Context::current())
+    }
+
+    /// Multiplies, rounding the product to the precision of the [current](Context::current)
+    /// context, as [`mul_with_context`](Decimal::mul_with_context).
+    #[inline]
+    pub fn mul_with_current(&self, other: &Decimal) -> (Decimal, Status) {
+        self.mul_with_context(other, &Context::current())
+    }
+
+    /// Divides, rounding the quotient to the precision of the [current](Context::current)
+    /// context, as [`div_with_context`](Decimal::div_with_context).
+    #[inline]
+    pub fn div_with_current(&self, other: &Decimal) -> (Decimal, Status) {
+        self.div_with_context(other, &Context::current())
+    }
+
+    /// Returns the remainder rounded to the precision of the [current](Context::current)
+    /// context, as [`rem_with_context`](Decimal::rem_with_context).
+    #[inline]
+    pub fn rem_with_current(&self, other: &Decimal) -> (Decimal, Status) {
+        self.rem_with_context(other, &Context::current())
+    }
+
+    /// Rounds to the precision of the [current](Context::current) context, as
+    /// [`Context::round`].
+    #[inline]
+    pub fn round_with_current(&self) -> (Decimal, Status) {
+        Context::current().round(self)
+    }
+}
+
+/// Returns [`Status::INVALID_OPERATION`] if an operand is a signaling NaN.
+#[inline]
+fn operand_status(a: &Decimal, b: &Decimal) -> Status {
+    if a.is_signaling_nan() || b.is_signaling_nan() {
+        Status::INVALID_OPERATION
+    } else {
+        Status::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{with_context, Context, Status};
+    use crate::{Decimal, RoundingStrategy};
+    use std::panic;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn flags(result: (Decimal, Status)) -> (String, Status) {
+        (result.0.to_string(), result.1)
+    }
+
+    #[test]
+    fn status() {
+        let status = Status::INEXACT | Status::ROUNDED;
+        assert!(Status::empty().is_empty() && !status.is_empty());
+        assert!(status.contains(Status::INEXACT) && status.contains(Status::ROUNDED));
+        assert!(!status.contains(Status::INEXACT | Status::OVERFLOW));
+        let mut accumulated = Status::default();
+        accumulated |= Status::UNDERFLOW;
+        accumulated |= status;
+        assert_eq!(accumulated, Status::INEXACT | Status::ROUNDED | Status::UNDERFLOW);
+    }
+
+    #[test]
+    fn new() {
+        let context = Context::new(5, RoundingStrategy::HalfEven);
+        assert_eq!(
+            (context.precision(), context.rounding()),
+            (5, RoundingStrategy::HalfEven)
+        );
+        assert_eq!(context.with_precision(7).precision(), 7);
+        assert_eq!(
+            context.with_rounding(RoundingStrategy::Up).rounding(),
+            RoundingStrategy::Up
+        );
+        assert_eq!(Context::default(), Context::DEFAULT);
+        assert!(panic::catch_unwind(|| Context::new(0, RoundingStrategy::HalfUp)).is_err());
+        assert!(panic::catch_unwind(|| Context::new(37, RoundingStrategy::HalfUp)).is_err());
+    }
+
+    #[test]
+    fn exact_results() {
+        let context = Context::new(5, RoundingStrategy::HalfEven);
+        let (a, b) = (dec("12.5"), dec("0.25"));
+        assert_eq!(
+            flags(a.add_with_context(&b, &context)),
+            ("12.75".into(), Status::empty())
+        );
+        assert_eq!(
+            flags(a.sub_with_context(&b, &context)),
+            ("12.25".into(), Status::empty())
+        );
+        assert_eq!(
+            flags(a.mul_with_context(&b, &context)),
+            ("3.125".into(), Status::empty())
+        );
+        assert_eq!(
+            flags(a.div_with_context(&b, &context)),
+            ("50.00".into(), Status::empty())
+        );
+        assert_eq!(
+            flags(a.rem_with_context(&b, &context)),
+            ("0.00".into(), Status::empty())
+        );
+        assert_eq!(flags(context.round(&a)), ("12.5".into(), Status::empty()));
+        assert_eq!(
+            flags(dec("1").div_with_context(&dec("0.50"), &context)),
+            ("2.00".into(), Status::empty())
+        );
+        assert_eq!(
+            flags(Decimal::NAN.add_with_context(&a, &context)),
+            ("NaN".into(), Status::empty())
+        );
+    }
+
+    #[test]
+    fn rounded() {
+        // A 6-digit exact result at precision 5 drops a zero digit, it is rounded but exact.
+        let context = Context::new(5, RoundingStrategy::HalfEven);
+        let (a, b) = (dec("12345.0"), dec("0.0"));
+        assert_eq!(
+            flags(a.add_with_context(&b, &context)),
+            ("12345".into(), Status::ROUNDED)
+        );
+        assert_eq!(
+            flags(a.sub_with_context(&b, &context)),
+            ("12345".into(), Status::ROUNDED)
+        );
+        assert_eq!(
+            flags(a.mul_with_context(&dec("1"), &context)),
+            ("12345".into(), Status::ROUNDED)
+        );
+        assert_eq!(
+            flags(dec("246900").div_with_context(&dec("2"), &context)),
+            ("123450".into(), Status::ROUNDED)
+        );
+        assert_eq!(
+            flags(a.rem_with_context(&dec("20000"), &context)),
+            ("12345".into(), Status::ROUNDED)
+        );
+        assert_eq!(
+            flags(context.round(&dec("1.00000"))),
+            ("1.0000".into(), Status::ROUNDED)
+        );
+    }
+
+    #[test]
+    fn inexact() {
+        let context = Context::new(5, RoundingStrategy::HalfEven);
+        let inexact = Status::INEXACT | Status::ROUNDED;
+        let (a, b) = (dec("123456"), dec("0.5"));
+        assert_eq!(flags(a.add_with_context(&b, &context)), ("123460".into(), inexact));
+        assert_eq!(flags(a.sub_with_context(&b, &context)), ("123460".into(), inexact));
+        assert_eq!(
+            flags(a.mul_with_context(&b, &context)),
+            ("61728".into(), Status::ROUNDED)
+        );
+        assert_eq!(
+            flags(dec("1").div_with_context(&dec("3"), &context)),
+            ("0.33333".into(), inexact)
+        );
+        assert_eq!(
+            flags(dec("2").div_with_context(&dec("3"), &context)),
+            ("0.66667".into(), inexact)
+        );
+        assert_eq!(
+            flags(a.rem_with_context(&dec("1000000"), &context)),
+            ("123460".into(), inexact)
+        );
+        assert_eq!(flags(context.round(&dec("1.23455"))), ("1.2346".into(), inexact));
+        assert_eq!(flags(context.round(&dec("1.23445"))), ("1.2344".into(), inexact));
+    }
+
+    #[test]
+    fn overflow() {
+        let overflow = Status::OVERFLOW | Status::INEXACT | Status::ROUNDED;
+        let context = Context::DEFAULT;
+        let max = Decimal::MAX;
+        assert_eq!(max.add_with_context(&max, &context), (Decimal::MAX, overflow));
+        assert_eq!(Decimal::MIN.sub_with_context(&max, &context), (Decimal::MIN, overflow));
+        assert_eq!(max.mul_with_context(&dec("-10"), &context), (Decimal::MIN, overflow));
+        assert_eq!(max.div_with_context(&dec("0.1"), &context), (Decimal::MAX, overflow));
+        let difference = max.sub_with_context(&dec("1e9"), &context);
+        assert_eq!(difference, (dec(&format!("{}8e9", "9".repeat(35))), Status::ROUNDED));
+
+        // At a reduced precision, the result is clamped to the largest value with 5 digits.
+        let context = Context::new(5, RoundingStrategy::HalfEven);
+        let largest = dec(&format!("99999{}", "0".repeat(40)));
+        assert_eq!(max.add_with_context(&max, &context), (largest, overflow));
+        assert_eq!(max.mul_with_context(&dec("-10"), &context), (-largest, overflow));
+        assert_eq!(context.round(&max), (largest, overflow));
+        // The implied trailing zeros of a large result are rounded but exact.
+        assert_eq!(context.round(&largest), (largest, Status::ROUNDED));
+    }
+
+    #[test]
+    fn underflow() {
+        // The exact result has a digit at scale 131, beyond MAX_SCALE.
+        let tiny = dec(&format!("0.{}1", "0".repeat(129)));
+        let half = dec("0.5");
+        let underflow = Status::UNDERFLOW | Status::INEXACT | Status::ROUNDED;
+        let context = Context::new(5, RoundingStrategy::HalfEven);
+        // A tie rounds to the even zero.
+        assert_eq!(
+            flags(tiny.mul_with_context(&half, &context)),
+            (format!("0.{}", "0".repeat(130)), underflow)
+        );
+        assert_eq!(flags(tiny.div_with_context(&dec("2"), &context)).1, underflow);
+        let up = context.with_rounding(RoundingStrategy::Up);
+        assert_eq!(flags(tiny.mul_with_context(&half, &up)), (tiny.to_string(), underflow));
+        let three = dec(&format!("0.{}3", "0".repeat(129)));
+        assert_eq!(flags(three.mul_with_context(&dec("0.11"), &context)).1, underflow);
+        assert_eq!(flags(tiny.add_with_context(&tiny, &context)).1, Status::empty());
+    }
+
+    #[test]
+    fn division_by_zero_and_invalid_operation() {
+        let context = Context::new(5, RoundingStrategy::HalfEven);
+        let (zero, one) = (Decimal::ZERO, dec("1"));
+        assert_eq!(
+            flags(one.div_with_context(&zero, &context)),
+            ("NaN".into(), Status::DIVISION_BY_ZERO)
+        );
+        assert_eq!(
+            flags(zero.div_with_context(&zero, &context)),
+            ("NaN".into(), Status::INVALID_OPERATION)
+        );
+        assert_eq!(
+            flags(one.rem_with_context(&zero, &context)),
+            ("NaN".into(), Status::INVALID_OPERATION)
+        );
+
+        let snan = dec("sNaN7");
+        for (result, status) in [
+            snan.add_with_context(&one, &context),
+            one.sub_with_context(&snan, &context),
+            snan.mul_with_context(&one, &context),
+            snan.div_with_context(&one, &context),
+            snan.rem_with_context(&one, &context),
+            context.round(&snan),
+        ] {
+            assert!(result.is_nan());
+            assert_eq!(status, Status::INVALID_OPERATION);
+        }
+    }
+
+    #[test]
+    fn current_context() {
+        let (one, three) = (dec("1"), dec("3"));
+        let (quotient, status) = one.div_with_current(&three);
+        assert_eq!(quotient.to_string(), format!("0.{}", "3".repeat(36)));
+        assert_eq!(status, Status::INEXACT | Status::ROUNDED);
+
+        let context = Context::new(5, RoundingStrategy::Up);
+        let nested = with_context(context, || {
+            assert_eq!(Context::current(), context);
+            assert_eq!(one.div_with_current(&three).0.to_string(), "0.33334");
+            let nested = with_context(context.with_precision(2), || one.div_with_current(&three).0);
+            assert_eq!(Context::current(), context);
+            assert_eq!(dec("2").div_with_current(&three).0.to_string(), "0.66667");
+            assert_eq!(dec("1.23456").round_with_current().0.to_string(), "1.2346");
+            assert_eq!(dec("123456").add_with_current(&one).0.to_string(), "123460");
+            assert_eq!(dec("123456").sub_with_current(&one).0.to_string(), "123460");
+            assert_eq!(dec("1234.5").mul_with_current(&three).0.to_string(), "3703.5");
+            assert_eq!(dec("10").rem_with_current(&three).0.to_string(), "1");
+            nested
+        });
+        assert_eq!(nested.to_string(), "0.34");
+        assert_eq!(Context::current(), Context::DEFAULT);
+    }
+
+    #[test]
+    fn context_restored_after_panic() {
+        let context = Context::new(3, RoundingStrategy::Down);
+        let result = panic::catch_unwind(|| {
+            with_context(context, || {
+                with_context(Context::new(1, RoundingStrategy::Up), || panic!("unwinding"));
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(Context::current(), Context::DEFAULT);
+
+        with_context(context, || {
+            let _ = panic::catch_unwind(|| with_context(Context::new(1, RoundingStrategy::Up), || panic!("unwinding")));
+            assert_eq!(Context::current(), context);
+            assert_eq!(dec("2").div_with_current(&dec("3")).0.to_string(), "0.666");
+        });
+    }
+}
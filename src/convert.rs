@@ -0,0 +1,398 @@
+//! Conversions between decimals and primitive types.
+
+use crate::decimal::{Decimal, MAX_INT_DIGITS, MAX_PRECISION, MAX_SCALE, NBASE, SIGN_NEG};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+
+impl Decimal {
+    /// Returns the absolute value of the integral part, saturated to `u128::MAX`.
+    #[inline]
+    pub(crate) fn int_abs(&self) -> u128 {
+        match self.int_abs_overflowing() {
+            (value, false) => value,
+            (_, true) => u128::MAX,
+        }
+    }
+
+    /// Returns the absolute value of the integral part modulo `2^128`, and whether it
+    /// overflowed `u128`.
+    fn int_abs_overflowing(&self) -> (u128, bool) {
+        let weight = self.weight as i32;
+        if weight < 0 {
+            return (0, false);
+        }
+
+        let mut value = 0u128;
+        let mut overflow = false;
+        for i in 0..=weight as usize {
+            let limb = self.limbs().get(i).copied().unwrap_or(0);
+            let (product, o1) = value.overflowing_mul(NBASE as u128);
+            let (sum, o2) = product.overflowing_add(limb as u128);
+            value = sum;
+            overflow |= o1 | o2;
+        }
+        (value, overflow)
+    }
+
+    /// Returns the absolute value multiplied by `10^dscale`, or `None` if it doesn't fit in
+    /// `u128`, which only happens for integers beyond `10^MAX_PRECISION`.
+    pub(crate) fn coefficient(&self) -> Option<u128> {
+        let (coefficient, exponent) = self.coefficient_parts();
+        let exponent = exponent + self.dscale as i32;
+        coefficient.checked_mul(10u128.checked_pow(exponent as u32)?)
+    }
+
+    /// Returns the absolute coefficient of at most `MAX_PRECISION` digits and its exponent,
+    /// such that the absolute value is `coefficient * 10^exponent`.
+    ///
+    /// The exponent is minus the scale when the scale is positive, otherwise the trailing zeros
+    /// of the integer are moved to the exponent.
+    pub(crate) fn coefficient_parts(&self) -> (u128, i32) {
+        let mut digits = self.decimal_digits();
+        let coefficient =
+            (0..self.coefficient_digits()).fold(0u128, |acc, _| acc * 10 + digits.next().unwrap_or(0) as u128);
+        (coefficient, self.last_exponent())
+    }
+
+    /// Creates the decimal `coefficient * 10^exponent`, returning `None` if it doesn't fit.
+    ///
+    /// A negative exponent is the scale, e.g. `(-150, -2)` is `-1.50`, and the coefficient must
+    /// then have at most `MAX_PRECISION` digits. A positive exponent adds implied trailing zeros,
+    /// e.g. `(123, 40)` is `1.23e42`, which is stored as the significant digits of the
+    /// coefficient only, so trailing zeros of the coefficient don't count towards
+    /// `MAX_PRECISION`, and the value must be below `10^45`.
+    pub fn from_coefficient_exponent(coefficient: i128, exponent: i32) -> Option<Decimal> {
+        let negative = coefficient < 0;
+        let mut magnitude = coefficient.unsigned_abs();
+        if exponent < 0 {
+            if -(exponent as i64) > MAX_SCALE as i64 || magnitude >= 10u128.pow(MAX_PRECISION) {
+                return None;
+            }
+            return Some(Decimal::from_coefficient(negative, magnitude, -exponent));
+        }
+        if magnitude == 0 {
+            return Some(Decimal::ZERO);
+        }
+
+        let mut exponent = exponent as i64;
+        while magnitude % 10 == 0 {
+            magnitude /= 10;
+            exponent += 1;
+        }
+        if magnitude >= 10u128.pow(MAX_PRECISION) || magnitude.ilog10() as i64 + 1 + exponent > MAX_INT_DIGITS as i64 {
+            return None;
+        }
+        Some(Decimal::from_coefficient(negative, magnitude, 0).shift(exponent as i32, 0))
+    }
+
+    /// Returns `(coefficient, exponent)` such that the value is `coefficient * 10^exponent`, or
+    /// `None` for NaN.
+    ///
+    /// The exponent is minus the scale when the scale is positive. Otherwise, the trailing zeros
+    /// of an integer are moved to the exponent, e.g. `12300000000` gives `(123, 8)`, and zero
+    /// gives `(0, 0)`, so the coefficient always has at most `MAX_PRECISION` digits.
+    /// [`from_coefficient_exponent`](Decimal::from_coefficient_exponent) gives the decimal back
+    /// with the same scale.
+    pub fn coefficient_exponent(&self) -> Option<(i128, i32)> {
+        if self.is_nan() {
+            return None;
+        }
+
+        let (coefficient, exponent) = self.coefficient_parts();
+        let coefficient = coefficient as i128;
+        Some((
+            if self.is_sign_negative() {
+                -coefficient
+            } else {
+                coefficient
+            },
+            exponent,
+        ))
+    }
+
+    /// Converts an integer rounded to `keep_digits` significant digits with `strategy`,
+    /// returning the decimal and whether it is inexact.
+    ///
+    /// `keep_digits` is clamped to `1..=MAX_PRECISION`, so integers with more digits than a
+    /// decimal holds are rounded instead of rejected, e.g. `123456` with 3 digits is `123000`,
+    /// and the dropped digits become implied trailing zeros. Every `i128` fits once rounded, so
+    /// `None` is never returned.
+    #[inline]
+    pub fn from_i128_rounded(value: i128, keep_digits: u32, strategy: RoundingStrategy) -> Option<(Decimal, bool)> {
+        int_rounded(value < 0, value.unsigned_abs(), keep_digits, strategy)
+    }
+
+    /// Converts an unsigned integer rounded to `keep_digits` significant digits, as
+    /// [`from_i128_rounded`](Decimal::from_i128_rounded).
+    #[inline]
+    pub fn from_u128_rounded(value: u128, keep_digits: u32, strategy: RoundingStrategy) -> Option<(Decimal, bool)> {
+        int_rounded(false, value, keep_digits, strategy)
+    }
+
+    /// Converts to `i64`, truncating the fractional part and clamping to the range of `i64`.
+    ///
+    /// NaN is converted to 0.
+    #[inline]
+    pub fn to_i64_saturating(&self) -> i64 {
+        let value = self.to_i128_saturating();
+        value.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// Converts to `u64`, truncating the fractional part and clamping to the range of `u64`.
+    ///
+    /// NaN is converted to 0.
+    #[inline]
+    pub fn to_u64_saturating(&self) -> u64 {
+        let value = self.to_i128_saturating();
+        value.clamp(0, u64::MAX as i128) as u64
+    }
+
+    /// Converts to `i128`, truncating the fractional part and clamping to the range of `i128`.
+    ///
+    /// NaN is converted to 0.
+    #[inline]
+    pub fn to_i128_saturating(&self) -> i128 {
+        if self.is_nan() {
+            return 0;
+        }
+
+        let value = self.int_abs();
+        if self.is_sign_negative() {
+            0i128.checked_sub_unsigned(value).unwrap_or(i128::MIN)
+        } else {
+            value.min(i128::MAX as u128) as i128
+        }
+    }
+
+    /// Converts to `i64`, truncating the fractional part and wrapping around the range of
+    /// `i64`, as `as` casts between integers.
+    ///
+    /// NaN is converted to 0.
+    #[inline]
+    pub fn to_i64_wrapping(&self) -> i64 {
+        self.to_i128_wrapping() as i64
+    }
+
+    /// Converts to `u64`, truncating the fractional part and wrapping around the range of
+    /// `u64`, as `as` casts between integers, so `-1` gives `u64::MAX`.
+    ///
+    /// NaN is converted to 0.
+    #[inline]
+    pub fn to_u64_wrapping(&self) -> u64 {
+        self.to_i128_wrapping() as u64
+    }
+
+    /// Converts to `i128`, truncating the fractional part and wrapping around the range of
+    /// `i128`, as `as` casts between integers.
+    ///
+    /// NaN is converted to 0.
+    #[inline]
+    pub fn to_i128_wrapping(&self) -> i128 {
+        if self.is_nan() {
+            return 0;
+        }
+
+        let (value, _) = self.int_abs_overflowing();
+        if self.is_sign_negative() {
+            (value as i128).wrapping_neg()
+        } else {
+            value as i128
+        }
+    }
+}
+
+fn int_rounded(
+    negative: bool,
+    mut magnitude: u128,
+    keep_digits: u32,
+    strategy: RoundingStrategy,
+) -> Option<(Decimal, bool)> {
+    // `u128::MAX` has 39 digits, which fit in 5 limbs.
+    let mut limbs = [0u32; 5];
+    for limb in limbs.iter_mut().rev() {
+        *limb = (magnitude % NBASE as u128) as u32;
+        magnitude /= NBASE as u128;
+    }
+    let mut var = Var::from_limbs(&limbs, 4);
+    if var.is_zero() {
+        return Some((Decimal::ZERO, false));
+    }
+    if negative {
+        var.sign = SIGN_NEG;
+    }
+
+    let keep = keep_digits.clamp(1, MAX_PRECISION) as i32;
+    let inexact = var.round((keep - var.exponent()).min(0), strategy, false);
+    let (result, _) = var.into_decimal(strategy)?;
+    Some((result, inexact))
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Decimal {
+                /// Converts exactly, every value fits in a decimal.
+                #[inline]
+                fn from(value: $t) -> Self {
+                    Decimal::from_coefficient(value < 0, value.unsigned_abs() as u128, 0)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Decimal {
+                /// Converts exactly, every value fits in a decimal.
+                #[inline]
+                fn from(value: $t) -> Self {
+                    Decimal::from_u128_exact(value as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i32, i64);
+impl_from_uint!(u64);
+
+#[cfg(test)]
+mod tests {
+    use crate::Decimal;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn saturating() {
+        assert_eq!(dec("-12.9").to_i64_saturating(), -12);
+        assert_eq!(dec("1e20").to_i64_saturating(), i64::MAX);
+        assert_eq!(dec("-1e20").to_i64_saturating(), i64::MIN);
+        assert_eq!(dec("-1").to_u64_saturating(), 0);
+        assert_eq!(dec("1e20").to_u64_saturating(), u64::MAX);
+        assert_eq!(Decimal::MIN.to_i128_saturating(), i128::MIN);
+        assert_eq!(Decimal::MAX.to_i128_saturating(), i128::MAX);
+        assert_eq!(dec("-1e38").to_i128_saturating(), -(10i128.pow(38)));
+    }
+
+    #[test]
+    fn wrapping() {
+        assert_eq!(dec("-12.9").to_i64_wrapping(), -12);
+        assert_eq!(dec("9223372036854775808").to_i64_wrapping(), i64::MIN);
+        assert_eq!(dec("1e20").to_i64_wrapping(), 10i128.pow(20) as i64);
+        assert_eq!(dec("-1.5").to_u64_wrapping(), u64::MAX);
+        assert_eq!(dec("18446744073709551617").to_u64_wrapping(), 1);
+        let max = (10u128.pow(36) - 1).wrapping_mul(10u128.pow(9)) as i128;
+        assert_eq!(Decimal::MAX.to_i128_wrapping(), max);
+        assert_eq!(Decimal::MIN.to_i128_wrapping(), max.wrapping_neg());
+        assert_eq!(dec("1e38").to_i128_wrapping(), 10i128.pow(38));
+        assert_eq!(dec("-1e38").to_u64_wrapping(), (10i128.pow(38)).wrapping_neg() as u64);
+    }
+
+    #[test]
+    fn coefficient_exponent() {
+        let cases = [
+            ("-1.50", -150, -2),
+            ("12300000000", 123, 8),
+            ("0", 0, 0),
+            ("0.00", 0, -2),
+            ("1.23e42", 123, 40),
+            ("999999999999999999999999999999999999e9", 10i128.pow(36) - 1, 9),
+        ];
+        for (s, coefficient, exponent) in cases {
+            let d = dec(s);
+            assert_eq!(d.coefficient_exponent(), Some((coefficient, exponent)), "{}", s);
+            let back = Decimal::from_coefficient_exponent(coefficient, exponent).unwrap();
+            assert_eq!(back.to_string(), d.to_string());
+        }
+        assert_eq!(Decimal::NAN.coefficient_exponent(), None);
+    }
+
+    #[test]
+    fn from_coefficient_exponent_range() {
+        assert_eq!(
+            Decimal::from_coefficient_exponent(5, 44).unwrap().to_string(),
+            format!("5{}", "0".repeat(44))
+        );
+        assert_eq!(
+            Decimal::from_coefficient_exponent(10i128.pow(38), 0)
+                .unwrap()
+                .to_string(),
+            format!("1{}", "0".repeat(38))
+        );
+        assert_eq!(Decimal::from_coefficient_exponent(-7, 40).unwrap(), dec("-7e40"));
+        assert_eq!(Decimal::from_coefficient_exponent(1, 45), None);
+        assert_eq!(Decimal::from_coefficient_exponent(10i128.pow(36) + 1, 0), None);
+        assert_eq!(Decimal::from_coefficient_exponent(10i128.pow(36), -1), None);
+        assert_eq!(Decimal::from_coefficient_exponent(1, -131), None);
+        assert_eq!(Decimal::from_coefficient_exponent(i128::MAX, 0), None);
+    }
+
+    #[test]
+    fn int_rounded() {
+        use crate::RoundingStrategy::*;
+
+        let cases: [(i128, u32, crate::RoundingStrategy, &str, bool); 8] = [
+            (123456, 3, HalfUp, "123000", true),
+            (123500, 3, HalfEven, "124000", true),
+            (-123500, 3, Down, "-123000", true),
+            (123000, 3, Up, "123000", false),
+            (-5, 0, HalfUp, "-5", false),
+            (
+                10i128.pow(36) + 1,
+                36,
+                Down,
+                "1000000000000000000000000000000000000",
+                true,
+            ),
+            (
+                10i128.pow(36) + 1,
+                36,
+                Up,
+                "1000000000000000000000000000000000010",
+                true,
+            ),
+            (i128::MIN, 5, HalfUp, "-170140000000000000000000000000000000000", true),
+        ];
+        for (value, keep, strategy, expected, inexact) in cases {
+            let (d, rounded) = Decimal::from_i128_rounded(value, keep, strategy).unwrap();
+            assert_eq!(
+                (d.to_string().as_str(), rounded),
+                (expected, inexact),
+                "{} {}",
+                value,
+                keep
+            );
+            assert!(d.is_internally_consistent());
+        }
+
+        let (d, inexact) = Decimal::from_i128_rounded(i128::MAX, 36, HalfUp).unwrap();
+        assert_eq!(d.to_string(), "170141183460469231731687303715884106000");
+        assert!(inexact);
+        let (d, inexact) = Decimal::from_u128_rounded(u128::MAX, 40, Down).unwrap();
+        assert_eq!(d.to_string(), "340282366920938463463374607431768211000");
+        assert!(inexact);
+        let (d, inexact) = Decimal::from_u128_rounded(10u128.pow(38), 1, HalfUp).unwrap();
+        assert_eq!(d, dec("1e38"));
+        assert!(!inexact);
+        let (d, inexact) = Decimal::from_u128_rounded(u128::MAX, 1, Up).unwrap();
+        assert_eq!(d, dec("4e38"));
+        assert!(inexact);
+        assert_eq!(Decimal::from_u128_rounded(0, 3, Up), Some((Decimal::ZERO, false)));
+    }
+
+    #[test]
+    fn nan_is_zero() {
+        for nan in [Decimal::NAN, dec("sNaN12")] {
+            assert_eq!(nan.to_i64_saturating(), 0);
+            assert_eq!(nan.to_u64_saturating(), 0);
+            assert_eq!(nan.to_i128_saturating(), 0);
+            assert_eq!(nan.to_i64_wrapping(), 0);
+            assert_eq!(nan.to_u64_wrapping(), 0);
+            assert_eq!(nan.to_i128_wrapping(), 0);
+        }
+    }
+}
@@ -0,0 +1,657 @@
+//! Decimal representation.
+
+use std::hash::{Hash, Hasher};
+
+/// Number of decimal digits stored in a limb.
+pub(crate) const DEC_DIGITS: i32 = 9;
+/// Base of a limb.
+pub(crate) const NBASE: u32 = 1_000_000_000;
+/// Maximum number of limbs stored in a decimal.
+pub(crate) const MAX_NDIGITS: usize = 5;
+
+pub(crate) const SIGN_POS: u8 = 0;
+pub(crate) const SIGN_NEG: u8 = 1;
+pub(crate) const SIGN_NAN: u8 = 2;
+pub(crate) const SIGN_SNAN: u8 = 3;
+
+/// Powers of 10 that fit in a limb.
+pub(crate) const POWERS_10: [u32; 10] = [
+    1,
+    10,
+    100,
+    1_000,
+    10_000,
+    100_000,
+    1_000_000,
+    10_000_000,
+    100_000_000,
+    1_000_000_000,
+];
+
+/// Maximum number of significant decimal digits.
+pub const MAX_PRECISION: u32 = 36;
+/// Maximum number of digits after the decimal point.
+pub const MAX_SCALE: i32 = 130;
+/// Maximum number of digits of the integral part, those beyond `MAX_PRECISION` being implied
+/// trailing zeros.
+pub(crate) const MAX_INT_DIGITS: i32 = MAX_NDIGITS as i32 * DEC_DIGITS;
+
+/// High precision decimal.
+///
+/// The value is stored as up to `MAX_NDIGITS` limbs in base `10^9`, most significant first,
+/// where the first limb has weight `NBASE^weight`. Leading and trailing zero limbs are
+/// always stripped, so every value has exactly one limb representation, and `dscale`
+/// records how many digits after the decimal point are displayed.
+///
+/// A decimal has at most `MAX_PRECISION` significant digits. Integers of up to 45 digits are
+/// stored as their significant limbs with a positive weight, so a value such as `1.23e40` is a
+/// coefficient `123` with implied trailing zeros, and only the digits from the first non-zero
+/// digit to the last non-zero digit count towards the precision.
+///
+/// NaN is quiet or signaling as in IEEE 754, and its diagnostic payload is stored in the limbs
+/// as a non-negative integer.
+#[derive(Clone, Copy)]
+pub struct Decimal {
+    pub(crate) ndigits: u8,
+    pub(crate) sign: u8,
+    pub(crate) weight: i8,
+    pub(crate) dscale: u8,
+    pub(crate) digits: [u32; MAX_NDIGITS],
+}
+
+impl Decimal {
+    /// Zero.
+    pub const ZERO: Decimal = Decimal {
+        ndigits: 0,
+        sign: SIGN_POS,
+        weight: 0,
+        dscale: 0,
+        digits: [0; MAX_NDIGITS],
+    };
+
+    /// One.
+    pub const ONE: Decimal = Decimal::from_raw_parts(false, &[1], 0, 0);
+
+    /// Two.
+    pub const TWO: Decimal = Decimal::from_raw_parts(false, &[2], 0, 0);
+
+    /// Ten.
+    pub const TEN: Decimal = Decimal::from_raw_parts(false, &[10], 0, 0);
+
+    /// One hundred.
+    pub const ONE_HUNDRED: Decimal = Decimal::from_raw_parts(false, &[100], 0, 0);
+
+    /// The largest decimal, `(10^MAX_PRECISION - 1) * 10^9`, the largest coefficient followed by
+    /// 9 implied zeros.
+    pub const MAX: Decimal = Decimal::from_raw_parts(false, &[NBASE - 1; 4], 4, 0);
+
+    /// The smallest decimal, `-(10^MAX_PRECISION - 1) * 10^9`.
+    pub const MIN: Decimal = Decimal::from_raw_parts(true, &[NBASE - 1; 4], 4, 0);
+
+    /// The smallest increment at full scale, `10^-MAX_SCALE`, the unit of the last digit of a
+    /// decimal with scale `MAX_SCALE`.
+    ///
+    /// Unlike `f64::EPSILON`, it doesn't depend on the magnitude, and it equals
+    /// [`MIN_POSITIVE`](Decimal::MIN_POSITIVE).
+    pub const EPSILON: Decimal = Decimal::from_raw_parts(false, &[100_000], -15, MAX_SCALE);
+
+    /// The smallest positive decimal, `10^-MAX_SCALE`.
+    pub const MIN_POSITIVE: Decimal = Decimal::EPSILON;
+
+    /// Not a number.
+    pub const NAN: Decimal = Decimal {
+        ndigits: 0,
+        sign: SIGN_NAN,
+        weight: 0,
+        dscale: 0,
+        digits: [0; MAX_NDIGITS],
+    };
+
+    /// Returns `true` if this decimal is a quiet or signaling NaN.
+    #[inline]
+    pub const fn is_nan(&self) -> bool {
+        self.sign == SIGN_NAN || self.sign == SIGN_SNAN
+    }
+
+    /// Returns `true` if this decimal is a signaling NaN.
+    #[inline]
+    pub const fn is_signaling_nan(&self) -> bool {
+        self.sign == SIGN_SNAN
+    }
+
+    /// Returns `true` if this decimal is zero.
+    #[inline]
+    pub const fn is_zero(&self) -> bool {
+        self.ndigits == 0 && !self.is_nan()
+    }
+
+    /// Creates a NaN with a diagnostic payload, returning `None` if the payload has more than
+    /// `MAX_PRECISION` digits.
+    #[inline]
+    pub fn nan_with_payload(payload: u128, signaling: bool) -> Option<Decimal> {
+        if payload >= 10u128.pow(MAX_PRECISION) {
+            return None;
+        }
+
+        let mut result = Decimal::from_u128_exact(payload);
+        result.sign = if signaling { SIGN_SNAN } else { SIGN_NAN };
+        Some(result)
+    }
+
+    /// Returns the diagnostic payload if this decimal is NaN, 0 means no payload.
+    #[inline]
+    pub fn nan_payload(&self) -> Option<u128> {
+        if self.is_nan() {
+            Some(self.int_abs())
+        } else {
+            None
+        }
+    }
+
+    /// Returns this NaN as a quiet NaN keeping the payload, other values are returned as is.
+    #[inline]
+    pub(crate) fn quiet(&self) -> Decimal {
+        let mut result = *self;
+        if result.sign == SIGN_SNAN {
+            result.sign = SIGN_NAN;
+        }
+        result
+    }
+
+    /// Builds a non-negative integral decimal, the value must have at most `MAX_PRECISION`
+    /// significant digits.
+    pub(crate) fn from_u128_exact(mut value: u128) -> Decimal {
+        let mut result = Decimal::ZERO;
+        let mut limbs = [0u32; MAX_NDIGITS];
+        let mut n = 0;
+        while value > 0 {
+            limbs[n] = (value % NBASE as u128) as u32;
+            value /= NBASE as u128;
+            n += 1;
+        }
+
+        // Trailing zero limbs are stripped by the weight.
+        let trailing = limbs[..n].iter().take_while(|&&l| l == 0).count();
+        for (i, &limb) in limbs[trailing..n].iter().rev().enumerate() {
+            result.digits[i] = limb;
+        }
+        result.ndigits = (n - trailing) as u8;
+        result.weight = if n == 0 { 0 } else { n as i8 - 1 };
+        result
+    }
+
+    /// Builds a decimal with the value `coefficient * 10^-scale`, the coefficient must have at
+    /// most `MAX_PRECISION` digits and the scale must be in `0..=MAX_SCALE`.
+    pub(crate) fn from_coefficient(negative: bool, coefficient: u128, scale: i32) -> Decimal {
+        if coefficient == 0 {
+            return Decimal {
+                dscale: scale as u8,
+                ..Decimal::ZERO
+            };
+        }
+
+        let mut result = Decimal::from_u128_exact(coefficient).shift(-scale, scale);
+        if negative {
+            result.sign = SIGN_NEG;
+        }
+        result
+    }
+
+    /// Returns `true` if this decimal is negative.
+    #[inline]
+    pub const fn is_sign_negative(&self) -> bool {
+        self.sign == SIGN_NEG
+    }
+
+    /// Returns `true` if this decimal is positive or zero.
+    #[inline]
+    pub const fn is_sign_positive(&self) -> bool {
+        self.sign == SIGN_POS
+    }
+
+    /// Returns the number of digits after the decimal point.
+    #[inline]
+    pub const fn scale(&self) -> i32 {
+        self.dscale as i32
+    }
+
+    /// Returns the limbs of the magnitude with their weight and the scale, as `(limbs, weight,
+    /// scale)`, without copying.
+    ///
+    /// The limbs are base `10^9` digits, most significant first, with leading and trailing zero
+    /// limbs stripped, so zero has no limbs. The first limb has the value `limbs[0] * 10^(9 *
+    /// weight)`, and the scale is the number of digits after the decimal point. The sign is not
+    /// included, and NaN gives the limbs of its payload as an integer with scale 0.
+    ///
+    /// This view is stable: the base, the order of the limbs, the stripping of zero limbs and
+    /// the meaning of the weight and scale won't change in any release of this major version, so
+    /// it can back custom encoders.
+    #[inline]
+    pub fn base_1e9_limbs(&self) -> (&[u32], i32, i32) {
+        (self.limbs(), self.weight as i32, self.dscale as i32)
+    }
+
+    /// Creates a decimal from its sign and the limbs, weight and scale of its magnitude, as
+    /// returned by [`base_1e9_limbs`](Decimal::base_1e9_limbs).
+    ///
+    /// Zero has no limbs and ignores `negative` and `weight`. This is a `const fn`, so constants
+    /// can be built without parsing at runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics, at compile time in a constant, if the parts don't form a consistent decimal: the
+    /// limbs must be below `10^9` without leading or trailing zero limbs, the scale must display
+    /// the value exactly within `MAX_SCALE` and `MAX_PRECISION`, and the value must be below
+    /// `10^45`.
+    pub const fn from_raw_parts(negative: bool, limbs: &[u32], weight: i32, scale: i32) -> Decimal {
+        let ndigits = limbs.len();
+        assert!(ndigits <= MAX_NDIGITS, "too many limbs for a decimal");
+        assert!(scale >= 0 && scale <= MAX_SCALE, "scale is out of range of decimal");
+        if ndigits == 0 {
+            return Decimal {
+                dscale: scale as u8,
+                ..Decimal::ZERO
+            };
+        }
+
+        let mut digits = [0; MAX_NDIGITS];
+        let mut i = 0;
+        while i < ndigits {
+            assert!(limbs[i] < NBASE, "limb is out of range");
+            digits[i] = limbs[i];
+            i += 1;
+        }
+        assert!(
+            limbs[0] != 0 && limbs[ndigits - 1] != 0,
+            "limbs are not stripped of zeros"
+        );
+        assert!(
+            weight >= i8::MIN as i32 && weight <= i8::MAX as i32,
+            "weight is out of range"
+        );
+
+        let mut trailing_zeros = 0;
+        while limbs[ndigits - 1] % POWERS_10[trailing_zeros as usize + 1] == 0 {
+            trailing_zeros += 1;
+        }
+        let last = (weight - ndigits as i32 + 1) * DEC_DIGITS + trailing_zeros;
+        assert!(scale >= -last, "scale is too small for the limbs");
+        let exponent = weight * DEC_DIGITS + digits_of(limbs[0]);
+        assert!(exponent <= MAX_INT_DIGITS, "number is too large to fit in decimal");
+        let last = if scale > 0 { -scale } else { last };
+        assert!(
+            exponent - last <= MAX_PRECISION as i32,
+            "number has too many digits for a decimal"
+        );
+
+        Decimal {
+            ndigits: ndigits as u8,
+            sign: if negative { SIGN_NEG } else { SIGN_POS },
+            weight: weight as i8,
+            dscale: scale as u8,
+            digits,
+        }
+    }
+
+    /// Returns the number of significant digits, from the most significant non-zero digit to
+    /// the last digit after the decimal point.
+    ///
+    /// Integers beyond `10^MAX_PRECISION` count their implied trailing zeros, so they have more
+    /// than `MAX_PRECISION` digits.
+    #[inline]
+    pub fn precision(&self) -> u32 {
+        if self.ndigits == 0 || self.is_nan() {
+            return 0;
+        }
+        (self.exponent() + self.dscale as i32) as u32
+    }
+
+    /// Returns the number of digits of the coefficient, from the most significant non-zero
+    /// digit to the last digit after the decimal point, or to the last non-zero digit of an
+    /// integer, which is at most `MAX_PRECISION`.
+    #[inline]
+    pub(crate) fn coefficient_digits(&self) -> i32 {
+        if self.ndigits == 0 {
+            return 0;
+        }
+        self.exponent() - self.last_exponent()
+    }
+
+    /// Returns the exponent of the last digit of the coefficient, minus the scale if it is
+    /// positive, otherwise the exponent of the last non-zero digit of the integer.
+    #[inline]
+    pub(crate) fn last_exponent(&self) -> i32 {
+        if self.dscale > 0 {
+            return -(self.dscale as i32);
+        }
+        let limbs = self.limbs();
+        let last = match limbs.last() {
+            Some(&limb) => limb,
+            None => return 0,
+        };
+        let mut trailing_zeros = 0;
+        while last % POWERS_10[trailing_zeros as usize + 1] == 0 {
+            trailing_zeros += 1;
+        }
+        (self.weight as i32 - limbs.len() as i32 + 1) * DEC_DIGITS + trailing_zeros
+    }
+
+    /// Returns the canonical representation of this decimal.
+    ///
+    /// Equal values have identical canonical representations: zero is positive with scale 0,
+    /// NaN has no payload, and the scale is reduced to the number of significant fractional
+    /// digits. Hashing builds on this form.
+    #[inline]
+    pub fn canonicalize(&self) -> Decimal {
+        if self.is_nan() {
+            return Decimal::NAN;
+        }
+        if self.is_zero() {
+            return Decimal::ZERO;
+        }
+
+        Decimal {
+            dscale: self.min_scale() as u8,
+            ..*self
+        }
+    }
+
+    /// Returns the canonical representation of this decimal, the same as
+    /// [`canonicalize`](Decimal::canonicalize).
+    ///
+    /// Trailing zeros are trimmed from the scale, so `1.500` becomes `1.5` and `-0.00` becomes
+    /// `0`. Limbs are already stripped of zeros by every constructor.
+    #[inline]
+    pub fn normalize(&self) -> Decimal {
+        self.canonicalize()
+    }
+
+    /// Returns the minimum scale that displays the value exactly.
+    #[inline]
+    pub(crate) fn min_scale(&self) -> i32 {
+        let limbs = self.limbs();
+        let last = match limbs.last() {
+            Some(&limb) => limb,
+            None => return 0,
+        };
+
+        let last_weight = self.weight as i32 - limbs.len() as i32 + 1;
+        if last_weight >= 0 {
+            return 0;
+        }
+
+        let mut trailing_zeros = 0;
+        while last % POWERS_10[trailing_zeros as usize + 1] == 0 {
+            trailing_zeros += 1;
+        }
+        -last_weight * DEC_DIGITS - trailing_zeros
+    }
+
+    /// Creates a decimal from its raw fields without checking any invariant, for fuzz targets
+    /// exploring how operations handle invalid representations.
+    ///
+    /// Only available with `--cfg fuzzing`, as set by `cargo fuzz`. Operations on a decimal
+    /// for which [`is_internally_consistent`](Decimal::is_internally_consistent) is `false`
+    /// may panic or give meaningless results, but are memory safe.
+    #[cfg(fuzzing)]
+    #[inline]
+    pub const fn from_raw_unchecked(
+        ndigits: u8,
+        sign: u8,
+        weight: i8,
+        dscale: u8,
+        digits: [u32; MAX_NDIGITS],
+    ) -> Decimal {
+        Decimal {
+            ndigits,
+            sign,
+            weight,
+            dscale,
+            digits,
+        }
+    }
+
+    /// Returns `true` if the representation satisfies all invariants: limbs are below `10^9`
+    /// and stripped of leading and trailing zeros, unused limbs are zero, the scale displays
+    /// the value exactly within `MAX_SCALE` and `MAX_PRECISION`, the value is below `10^45`,
+    /// zero is positive, and a NaN payload is an integer of at most `MAX_PRECISION` digits.
+    ///
+    /// Every decimal built through the public API is consistent, and debug builds assert it
+    /// for the results of arithmetic.
+    pub fn is_internally_consistent(&self) -> bool {
+        let ndigits = self.ndigits as usize;
+        if ndigits > MAX_NDIGITS || self.digits[ndigits..].iter().any(|&l| l != 0) {
+            return false;
+        }
+
+        match self.sign {
+            SIGN_NAN | SIGN_SNAN => {
+                // The payload is an integer.
+                let limbs = self.limbs();
+                return self.dscale == 0
+                    && match limbs.first() {
+                        None => self.weight == 0,
+                        Some(&first) => {
+                            limbs.iter().all(|&l| l < NBASE)
+                                && first != 0
+                                && limbs[ndigits - 1] != 0
+                                && self.weight as i32 >= ndigits as i32 - 1
+                                && self.exponent() <= MAX_PRECISION as i32
+                        }
+                    };
+            }
+            SIGN_POS | SIGN_NEG => {}
+            _ => return false,
+        }
+
+        if self.dscale as i32 > MAX_SCALE {
+            return false;
+        }
+        if ndigits == 0 {
+            return self.weight == 0 && self.sign == SIGN_POS;
+        }
+
+        let limbs = self.limbs();
+        limbs.iter().all(|&l| l < NBASE)
+            && limbs[0] != 0
+            && limbs[ndigits - 1] != 0
+            && self.min_scale() <= self.dscale as i32
+            && self.exponent() <= MAX_INT_DIGITS
+            && self.coefficient_digits() <= MAX_PRECISION as i32
+    }
+
+    /// Returns the used limbs.
+    #[inline]
+    pub(crate) fn limbs(&self) -> &[u32] {
+        &self.digits[..self.ndigits as usize]
+    }
+
+    /// Returns `e` such that `10^(e-1) <= |self| < 10^e`, the value must be non-zero.
+    #[inline]
+    pub(crate) fn exponent(&self) -> i32 {
+        debug_assert!(self.ndigits > 0);
+        self.weight as i32 * DEC_DIGITS + digits_of(self.digits[0])
+    }
+
+    /// Builds a decimal from significant decimal digits where the last digit has the weight `10^exp`.
+    ///
+    /// `digits` must have no leading zeros and at most `MAX_INT_DIGITS` digits, and the caller
+    /// guarantees that the value fits in the limbs.
+    pub(crate) fn from_decimal_digits(negative: bool, digits: &[u8], exp: i32, dscale: i32) -> Decimal {
+        debug_assert!((0..=MAX_SCALE).contains(&dscale));
+
+        let mut result = Decimal {
+            dscale: dscale as u8,
+            ..Decimal::ZERO
+        };
+        if digits.is_empty() {
+            return result;
+        }
+
+        let top = exp + digits.len() as i32 - 1;
+        let weight = top.div_euclid(DEC_DIGITS);
+        for (i, &d) in digits.iter().enumerate() {
+            if d == 0 {
+                continue;
+            }
+            let pos = top - i as i32;
+            let index = (weight - pos.div_euclid(DEC_DIGITS)) as usize;
+            result.digits[index] += d as u32 * POWERS_10[pos.rem_euclid(DEC_DIGITS) as usize];
+            result.ndigits = result.ndigits.max(index as u8 + 1);
+        }
+
+        result.weight = weight as i8;
+        result.sign = if negative { SIGN_NEG } else { SIGN_POS };
+        result
+    }
+
+    /// Returns an iterator over the significant decimal digits, most significant first.
+    ///
+    /// Trailing zeros of the last limb are included, zero has no digits.
+    #[inline]
+    pub(crate) fn decimal_digits(&self) -> DecimalDigits<'_> {
+        let limbs = self.limbs();
+        let first = match limbs.first() {
+            Some(&limb) => digits_of(limb),
+            None => 0,
+        };
+        DecimalDigits {
+            limbs,
+            index: 0,
+            remaining: first,
+        }
+    }
+}
+
+/// Iterator over the decimal digits of a decimal.
+pub(crate) struct DecimalDigits<'a> {
+    limbs: &'a [u32],
+    index: usize,
+    remaining: i32,
+}
+
+impl Iterator for DecimalDigits<'_> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        if self.remaining == 0 {
+            self.index += 1;
+            if self.index >= self.limbs.len() {
+                return None;
+            }
+            self.remaining = DEC_DIGITS;
+        }
+        self.remaining -= 1;
+        let limb = self.limbs[self.index];
+        Some((limb / POWERS_10[self.remaining as usize] % 10) as u8)
+    }
+}
+
+/// Returns the number of decimal digits of a non-zero limb.
+#[inline]
+pub(crate) const fn digits_of(limb: u32) -> i32 {
+    debug_assert!(limb > 0 && limb < NBASE);
+    let mut n = 1;
+    while n < DEC_DIGITS && limb >= POWERS_10[n as usize] {
+        n += 1;
+    }
+    n
+}
+
+impl Default for Decimal {
+    /// Returns [`Decimal::ZERO`].
+    #[inline]
+    fn default() -> Decimal {
+        Decimal::ZERO
+    }
+}
+
+impl Hash for Decimal {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let canonical = self.canonicalize();
+        canonical.sign.hash(state);
+        canonical.weight.hash(state);
+        canonical.limbs().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constants() {
+        let constants = [
+            (Decimal::ONE, "1"),
+            (Decimal::TWO, "2"),
+            (Decimal::TEN, "10"),
+            (Decimal::ONE_HUNDRED, "100"),
+            (Decimal::MAX, "999999999999999999999999999999999999e9"),
+            (Decimal::MIN, "-999999999999999999999999999999999999e9"),
+            (Decimal::EPSILON, "1e-130"),
+        ];
+        for (constant, s) in constants {
+            assert!(constant.is_internally_consistent(), "{}", s);
+            let parsed = s.parse::<Decimal>().unwrap();
+            assert_eq!(constant.base_1e9_limbs(), parsed.base_1e9_limbs(), "{}", s);
+            assert_eq!(constant.is_sign_negative(), parsed.is_sign_negative(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn from_raw_parts_roundtrip() {
+        for s in [
+            "0",
+            "0.00",
+            "-1.500",
+            "123456789.000000001",
+            "-1e35",
+            "1e44",
+            "-123456789012345678901234567890123456e9",
+            "1e-130",
+            "0.1000000000",
+        ] {
+            let d = s.parse::<Decimal>().unwrap();
+            let (limbs, weight, scale) = d.base_1e9_limbs();
+            let raw = Decimal::from_raw_parts(d.is_sign_negative(), limbs, weight, scale);
+            assert!(raw.is_internally_consistent(), "{}", s);
+            assert_eq!(raw.to_string(), d.to_string(), "{}", s);
+        }
+        assert_eq!(Decimal::from_raw_parts(true, &[], 5, 2).to_string(), "0.00");
+    }
+
+    #[test]
+    #[should_panic(expected = "limbs are not stripped of zeros")]
+    fn from_raw_parts_unstripped() {
+        Decimal::from_raw_parts(false, &[1, 0], 1, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "scale is too small for the limbs")]
+    fn from_raw_parts_short_scale() {
+        Decimal::from_raw_parts(false, &[1, 500_000_000], 0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "number has too many digits for a decimal")]
+    fn from_raw_parts_too_precise() {
+        Decimal::from_raw_parts(false, &[1; 5], 4, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "number is too large to fit in decimal")]
+    fn from_raw_parts_too_large() {
+        Decimal::from_raw_parts(false, &[1], 5, 0);
+    }
+
+    #[test]
+    fn implied_zeros() {
+        let d = Decimal::from_raw_parts(false, &[123], 4, 0);
+        assert!(d.is_internally_consistent());
+        assert_eq!(d.to_string(), format!("123{}", "0".repeat(36)));
+        assert_eq!(d.coefficient_digits(), 3);
+        assert_eq!(d.precision(), 39);
+    }
+}
@@ -0,0 +1,270 @@
+//! Dictionary encoding of decimal columns.
+
+use crate::decimal::Decimal;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// A column of decimals stored as bit-packed codes into a dictionary of distinct values.
+///
+/// Columns dominated by a few distinct values, such as prices and fees, take a few bits per row
+/// instead of the size of a decimal. Codes are assigned in order of first appearance and are as
+/// wide as needed for the number of distinct values, so they are repacked when it doubles.
+///
+/// Values are interned by representation, so `1.5` and `1.50` get different codes and each row
+/// reads back with its original scale.
+#[derive(Clone, Debug, Default)]
+pub struct DecimalDictionary {
+    values: Vec<Decimal>,
+    codes_by_value: HashMap<Repr, u32>,
+    words: Vec<u64>,
+    width: u32,
+    len: usize,
+}
+
+impl DecimalDictionary {
+    /// Creates an empty column.
+    #[inline]
+    pub fn new() -> DecimalDictionary {
+        DecimalDictionary::default()
+    }
+
+    /// Appends a row, returning the code of its value.
+    pub fn push(&mut self, value: Decimal) -> u32 {
+        let code = self.intern(value);
+        let width = bit_width(self.values.len());
+        if width != self.width {
+            self.repack(width);
+        }
+
+        let bit = self.len * self.width as usize;
+        if bit + self.width as usize > self.words.len() * 64 {
+            self.words.push(0);
+        }
+        write_bits(&mut self.words, bit, self.width, code as u64);
+        self.len += 1;
+        code
+    }
+
+    /// Returns the value of row `row`, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, row: usize) -> Option<Decimal> {
+        self.code(row).map(|code| self.values[code as usize])
+    }
+
+    /// Returns the code of row `row`, or `None` if it is out of bounds.
+    #[inline]
+    pub fn code(&self, row: usize) -> Option<u32> {
+        if row >= self.len {
+            return None;
+        }
+        Some(read_bits(&self.words, row * self.width as usize, self.width) as u32)
+    }
+
+    /// Returns the code of `value`, or `None` if no row has it.
+    #[inline]
+    pub fn code_of(&self, value: &Decimal) -> Option<u32> {
+        self.codes_by_value.get(&Repr(*value)).copied()
+    }
+
+    /// Returns the distinct values, indexed by code.
+    #[inline]
+    pub fn values(&self) -> &[Decimal] {
+        &self.values
+    }
+
+    /// Returns the number of bits per row.
+    #[inline]
+    pub fn bits_per_code(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if there are no rows.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns an iterator over the values of the rows.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = Decimal> + '_ {
+        (0..self.len).map(move |row| self.get(row).unwrap())
+    }
+
+    fn intern(&mut self, value: Decimal) -> u32 {
+        let values = &mut self.values;
+        *self.codes_by_value.entry(Repr(value)).or_insert_with(|| {
+            let code = u32::try_from(values.len()).expect("too many distinct values");
+            values.push(value);
+            code
+        })
+    }
+
+    fn repack(&mut self, width: u32) {
+        let mut words = vec![0u64; (self.len * width as usize).div_ceil(64)];
+        for row in 0..self.len {
+            let code = read_bits(&self.words, row * self.width as usize, self.width);
+            write_bits(&mut words, row * width as usize, width, code);
+        }
+        self.words = words;
+        self.width = width;
+    }
+}
+
+impl Extend<Decimal> for DecimalDictionary {
+    #[inline]
+    fn extend<I: IntoIterator<Item = Decimal>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+impl std::iter::FromIterator<Decimal> for DecimalDictionary {
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = Decimal>>(iter: I) -> Self {
+        let mut dictionary = DecimalDictionary::new();
+        dictionary.extend(iter);
+        dictionary
+    }
+}
+
+/// A decimal compared by representation, including the scale and NaN payload.
+#[derive(Clone, Copy, Debug)]
+struct Repr(Decimal);
+
+impl PartialEq for Repr {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (&self.0, &other.0);
+        a.sign == b.sign && a.weight == b.weight && a.dscale == b.dscale && a.limbs() == b.limbs()
+    }
+}
+
+impl Eq for Repr {}
+
+impl Hash for Repr {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.sign.hash(state);
+        self.0.weight.hash(state);
+        self.0.dscale.hash(state);
+        self.0.limbs().hash(state);
+    }
+}
+
+/// Returns the number of bits needed for codes below `n`.
+#[inline]
+fn bit_width(n: usize) -> u32 {
+    usize::BITS - n.saturating_sub(1).leading_zeros()
+}
+
+#[inline]
+fn read_bits(words: &[u64], bit: usize, width: u32) -> u64 {
+    if width == 0 {
+        return 0;
+    }
+
+    let (index, offset) = (bit / 64, (bit % 64) as u32);
+    let mut value = words[index] >> offset;
+    if offset + width > 64 {
+        value |= words[index + 1] << (64 - offset);
+    }
+    value & (u64::MAX >> (64 - width))
+}
+
+#[inline]
+fn write_bits(words: &mut [u64], bit: usize, width: u32, value: u64) {
+    if width == 0 {
+        return;
+    }
+
+    let (index, offset) = (bit / 64, (bit % 64) as u32);
+    words[index] |= value << offset;
+    if offset + width > 64 {
+        words[index + 1] |= value >> (64 - offset);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecimalDictionary;
+    use crate::Decimal;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn same(a: Decimal, b: Decimal) -> bool {
+        a.to_string() == b.to_string()
+    }
+
+    #[test]
+    fn repacks() {
+        let mut dictionary = DecimalDictionary::new();
+        let mut rows = Vec::new();
+        let mut widths = vec![dictionary.bits_per_code()];
+        for i in 0..=(1i64 << 16) {
+            // A new value followed by a repeat, so rows straddle words at every width.
+            for value in [Decimal::from(i), Decimal::from(i / 3)] {
+                let code = dictionary.push(value);
+                assert_eq!(dictionary.values()[code as usize], value);
+                rows.push(value);
+            }
+
+            if dictionary.bits_per_code() != *widths.last().unwrap() {
+                widths.push(dictionary.bits_per_code());
+                assert_eq!(dictionary.len(), rows.len());
+                for (row, value) in rows.iter().enumerate() {
+                    assert_eq!(
+                        dictionary.get(row),
+                        Some(*value),
+                        "row {row} at width {}",
+                        widths.last().unwrap()
+                    );
+                }
+            }
+        }
+        assert_eq!(widths, (0..=17).collect::<Vec<_>>());
+        assert_eq!(dictionary.values().len(), (1 << 16) + 1);
+        assert!(dictionary.iter().eq(rows.iter().copied()));
+        assert_eq!(dictionary.get(rows.len()), None);
+        assert_eq!(dictionary.code(rows.len()), None);
+    }
+
+    #[test]
+    fn interning_by_representation() {
+        let values = [
+            "1.5", "1.50", "1.5", "-0", "0", "0.00", "NaN", "NaN7", "sNaN7", "NaN", "1.50",
+        ]
+        .map(dec);
+        let dictionary: DecimalDictionary = values.iter().copied().collect();
+        let codes: Vec<_> = (0..values.len()).map(|row| dictionary.code(row).unwrap()).collect();
+        // `-0` parses as `0`.
+        assert_eq!(codes, [0, 1, 0, 2, 2, 3, 4, 5, 6, 4, 1]);
+        assert_eq!(dictionary.bits_per_code(), 3);
+        assert_eq!(dictionary.code_of(&dec("1.50")), Some(1));
+        assert_eq!(dictionary.code_of(&dec("1.500")), None);
+        for (row, value) in values.iter().enumerate() {
+            assert!(same(dictionary.get(row).unwrap(), *value));
+        }
+        assert_eq!(dictionary.get(1).unwrap().to_string(), "1.50");
+    }
+
+    #[test]
+    fn single_value() {
+        let mut dictionary = DecimalDictionary::new();
+        assert!(dictionary.is_empty());
+        assert_eq!(dictionary.get(0), None);
+        for _ in 0..100 {
+            assert_eq!(dictionary.push(dec("2.5")), 0);
+        }
+        assert_eq!((dictionary.len(), dictionary.bits_per_code()), (100, 0));
+        assert!(dictionary.iter().all(|value| same(value, dec("2.5"))));
+    }
+}
@@ -0,0 +1,135 @@
+//! Digit-level utilities.
+
+use crate::decimal::{digits_of, Decimal, MAX_PRECISION, POWERS_10};
+
+impl Decimal {
+    /// Returns the most significant non-zero digit, or `None` for zero and NaN.
+    #[inline]
+    pub fn leading_digit(&self) -> Option<u8> {
+        if self.is_nan() {
+            return None;
+        }
+        let limb = *self.limbs().first()?;
+        Some((limb / POWERS_10[digits_of(limb) as usize - 1]) as u8)
+    }
+
+    /// Returns a decimal with each significant digit replaced by `f(digit)`, keeping the sign
+    /// and scale.
+    ///
+    /// Significant digits run from the most significant non-zero digit down to the last digit
+    /// after the decimal point, e.g. `1`, `0`, `5` for `0.105`. The implied trailing zeros of an
+    /// integer beyond `MAX_PRECISION` digits are not significant and stay zeros.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` returns a value greater than 9.
+    pub fn map_digits<F: FnMut(u8) -> u8>(&self, mut f: F) -> Decimal {
+        if self.is_nan() || self.is_zero() {
+            return *self;
+        }
+
+        let len = self.coefficient_digits() as usize;
+        let mut buf = [0u8; MAX_PRECISION as usize];
+        let mut digits = self.decimal_digits();
+        for b in buf[..len].iter_mut() {
+            let d = f(digits.next().unwrap_or(0));
+            assert!(d <= 9, "digit out of range");
+            *b = d;
+        }
+
+        let dscale = self.dscale as i32;
+        match buf[..len].iter().position(|&d| d != 0) {
+            Some(p) => {
+                Decimal::from_decimal_digits(self.is_sign_negative(), &buf[p..len], self.last_exponent(), dscale)
+            }
+            None => Decimal::from_decimal_digits(false, &[], 0, dscale),
+        }
+    }
+
+    /// Masks a decimal by zeroing all significant digits except the `keep_msd` most significant
+    /// and the `keep_lsd` least significant ones, preserving magnitude and scale.
+    ///
+    /// For example, redacting `1234.56` with `keep_msd = 2` and `keep_lsd = 0` gives `1200.00`.
+    #[inline]
+    pub fn redact(&self, keep_msd: usize, keep_lsd: usize) -> Decimal {
+        if self.is_nan() || self.is_zero() {
+            return *self;
+        }
+
+        let len = self.coefficient_digits() as usize;
+        let mut index = 0;
+        self.map_digits(|d| {
+            let keep = index < keep_msd || index >= len.saturating_sub(keep_lsd);
+            index += 1;
+            if keep {
+                d
+            } else {
+                0
+            }
+        })
+    }
+}
+
+/// Counts the leading digits of the values, as used by Benford's law checks.
+///
+/// `histogram[d]` is the number of values whose leading digit is `d`. Zeros and NaN have no
+/// leading digit and are skipped, so `histogram[0]` is always 0.
+pub fn digit_histogram(values: &[Decimal]) -> [u64; 10] {
+    let mut histogram = [0u64; 10];
+    for d in values.iter().filter_map(Decimal::leading_digit) {
+        histogram[d as usize] += 1;
+    }
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::digit_histogram;
+    use crate::Decimal;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn redact() {
+        let d = dec("1234.56");
+        assert_eq!(d.redact(2, 0).to_string(), "1200.00");
+        assert_eq!(d.redact(0, 2).to_string(), "0.56");
+        assert_eq!(d.redact(1, 1).to_string(), "1000.06");
+        assert_eq!(d.redact(0, 0).to_string(), "0.00");
+        assert_eq!(dec("-0.105").redact(1, 0).to_string(), "-0.100");
+        assert_eq!(dec("-0.105").redact(0, 0).to_string(), "0.000");
+
+        // Counts beyond the number of digits keep everything.
+        for (msd, lsd) in [(0, usize::MAX), (usize::MAX, 0), (usize::MAX, usize::MAX), (3, 4)] {
+            assert_eq!(d.redact(msd, lsd), d);
+        }
+        assert_eq!(Decimal::ZERO.redact(1, 1), Decimal::ZERO);
+        assert!(Decimal::NAN.redact(0, usize::MAX).is_nan());
+
+        // The implied trailing zeros of a large integer are not significant digits.
+        let big = dec(&format!("{}{}", "7".repeat(36), "0".repeat(9)));
+        assert_eq!(big.redact(1, 0), dec(&format!("7{}", "0".repeat(44))));
+        assert_eq!(big.redact(0, 1), dec(&format!("7{}", "0".repeat(9))));
+    }
+
+    #[test]
+    fn map_digits() {
+        assert_eq!(dec("0.105").map_digits(|d| 9 - d).to_string(), "0.894");
+        assert_eq!(dec("-120").map_digits(|d| d / 2).to_string(), "-10");
+        assert_eq!(dec("1.5").map_digits(|_| 0).to_string(), "0.0");
+        assert!(std::panic::catch_unwind(|| dec("1").map_digits(|_| 10)).is_err());
+    }
+
+    #[test]
+    fn leading_digits() {
+        assert_eq!(dec("0.00345").leading_digit(), Some(3));
+        assert_eq!(dec("-987654321987").leading_digit(), Some(9));
+        assert_eq!(Decimal::ZERO.leading_digit(), None);
+        assert_eq!(Decimal::NAN.leading_digit(), None);
+
+        let values = ["1", "12", "0.19", "-2", "0", "NaN", "9e40"].map(dec);
+        assert_eq!(digit_histogram(&values), [0, 3, 1, 0, 0, 0, 0, 0, 0, 1]);
+    }
+}
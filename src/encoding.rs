@@ -0,0 +1,107 @@
+//! Binary encoding of decimals.
+
+use crate::decimal::{Decimal, MAX_NDIGITS};
+use crate::error::DecimalDecodeError;
+
+/// Version of the layout written by [`Decimal::to_bytes`].
+pub const ENCODING_VERSION: u8 = 1;
+
+/// Length of the version 1 header: version, sign, weight, scale and number of limbs.
+const V1_HEADER_LEN: usize = 5;
+
+impl Decimal {
+    /// Encodes to bytes, prefixed with the layout version [`ENCODING_VERSION`].
+    ///
+    /// The [canonical form](Decimal::canonicalize) is encoded, so equal values have identical
+    /// encodings whatever their scale or NaN payload, and decode to the canonical form. Version 1
+    /// stores the sign, weight, scale and number of limbs as one byte each, followed by the base
+    /// `10^9` limbs as little endian `u32`s.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let canonical = self.canonicalize();
+        let limbs = canonical.limbs();
+
+        let mut bytes = Vec::with_capacity(V1_HEADER_LEN + limbs.len() * 4);
+        bytes.push(ENCODING_VERSION);
+        bytes.push(canonical.sign);
+        bytes.push(canonical.weight as u8);
+        bytes.push(canonical.dscale);
+        bytes.push(canonical.ndigits);
+        for limb in limbs {
+            bytes.extend_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Decodes bytes written by [`Decimal::to_bytes`] of this or any earlier release.
+    ///
+    /// The layout is selected by the leading version byte, and `bytes` must contain exactly one
+    /// encoded decimal.
+    pub fn from_bytes_versioned(bytes: &[u8]) -> Result<Decimal, DecimalDecodeError> {
+        match bytes.first() {
+            None => Err(DecimalDecodeError::UnexpectedEof),
+            Some(1) => decode_v1(&bytes[1..]),
+            Some(&v) => Err(DecimalDecodeError::UnknownVersion(v)),
+        }
+    }
+}
+
+fn decode_v1(bytes: &[u8]) -> Result<Decimal, DecimalDecodeError> {
+    if bytes.len() < V1_HEADER_LEN - 1 {
+        return Err(DecimalDecodeError::UnexpectedEof);
+    }
+
+    let ndigits = bytes[3] as usize;
+    if ndigits > MAX_NDIGITS {
+        return Err(DecimalDecodeError::Invalid);
+    }
+    let body = &bytes[V1_HEADER_LEN - 1..];
+    if body.len() < ndigits * 4 {
+        return Err(DecimalDecodeError::UnexpectedEof);
+    }
+    if body.len() > ndigits * 4 {
+        return Err(DecimalDecodeError::Invalid);
+    }
+
+    let mut result = Decimal {
+        ndigits: ndigits as u8,
+        sign: bytes[0],
+        weight: bytes[1] as i8,
+        dscale: bytes[2],
+        digits: [0; MAX_NDIGITS],
+    };
+    for (limb, chunk) in result.digits.iter_mut().zip(body.chunks_exact(4)) {
+        *limb = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+
+    if result.is_internally_consistent() {
+        Ok(result)
+    } else {
+        Err(DecimalDecodeError::Invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Decimal;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn to_bytes_is_canonical() {
+        assert_eq!(dec("1.50").to_bytes(), dec("1.5").to_bytes());
+        assert_eq!(dec("-0.00").to_bytes(), Decimal::ZERO.to_bytes());
+        assert_eq!(dec("nan123").to_bytes(), Decimal::NAN.to_bytes());
+        assert_ne!(dec("1.5").to_bytes(), dec("-1.5").to_bytes());
+    }
+
+    #[test]
+    fn from_bytes_roundtrip() {
+        for s in ["0", "1.500", "-123456789.000000001", "1e35", "-1e-130", "NaN"] {
+            let d = dec(s);
+            let decoded = Decimal::from_bytes_versioned(&d.to_bytes()).unwrap();
+            assert_eq!(decoded.to_string(), d.canonicalize().to_string(), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! Reading decimals from configuration.
+
+use crate::decimal::Decimal;
+use crate::error::EnvDecimalError;
+use std::env::{self, VarError};
+
+impl Decimal {
+    /// Reads a decimal from the environment variable `var`, e.g. a rate or limit set at startup.
+    ///
+    /// Surrounding whitespace in the value is ignored. Errors name the variable, and for invalid
+    /// values also carry the value.
+    pub fn from_env(var: &str) -> Result<Decimal, EnvDecimalError> {
+        match env::var(var) {
+            Ok(value) => value.trim().parse().map_err(|error| EnvDecimalError::Parse {
+                var: var.to_string(),
+                value,
+                error,
+            }),
+            Err(VarError::NotPresent) => Err(EnvDecimalError::NotPresent(var.to_string())),
+            Err(VarError::NotUnicode(_)) => Err(EnvDecimalError::NotUnicode(var.to_string())),
+        }
+    }
+
+    /// Parses `s`, or returns `default` if it is not a valid decimal.
+    #[inline]
+    pub fn from_str_or(s: &str, default: Decimal) -> Decimal {
+        s.parse().unwrap_or(default)
+    }
+}
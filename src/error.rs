@@ -0,0 +1,234 @@
+//! Error types.
+
+use std::fmt;
+use std::num::IntErrorKind;
+
+/// An error which can be returned when parsing a decimal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecimalParseError {
+    /// Empty string.
+    Empty,
+    /// Invalid decimal literal.
+    Invalid,
+    /// Number is too large to fit in a decimal.
+    Overflow,
+    /// Input is longer or has more digits than allowed by [`ParseOptions`](crate::ParseOptions).
+    TooLong,
+}
+
+impl DecimalParseError {
+    /// Returns the equivalent kind of integer parse error, so generic code can handle errors of
+    /// decimals and primitive integers alike.
+    ///
+    /// The sign of an overflowing number isn't recorded, so [`Overflow`](DecimalParseError::Overflow)
+    /// and [`TooLong`](DecimalParseError::TooLong) map to [`IntErrorKind::PosOverflow`].
+    #[inline]
+    pub fn kind(&self) -> IntErrorKind {
+        match self {
+            DecimalParseError::Empty => IntErrorKind::Empty,
+            DecimalParseError::Invalid => IntErrorKind::InvalidDigit,
+            DecimalParseError::Overflow | DecimalParseError::TooLong => IntErrorKind::PosOverflow,
+        }
+    }
+}
+
+impl fmt::Display for DecimalParseError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecimalParseError::Empty => f.write_str("cannot parse decimal from empty string"),
+            DecimalParseError::Invalid => f.write_str("invalid decimal literal"),
+            DecimalParseError::Overflow => f.write_str("number is too large to fit in decimal"),
+            DecimalParseError::TooLong => f.write_str("decimal literal is too long"),
+        }
+    }
+}
+
+impl std::error::Error for DecimalParseError {}
+
+/// A parse error with a suggested fix, returned by [`Decimal::parse_suggesting`].
+///
+/// [`Decimal::parse_suggesting`]: crate::Decimal::parse_suggesting
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuggestedParseError {
+    pub(crate) kind: DecimalParseError,
+    pub(crate) suggestion: Option<String>,
+}
+
+impl SuggestedParseError {
+    /// Returns the parse error.
+    #[inline]
+    pub fn kind(&self) -> DecimalParseError {
+        self.kind
+    }
+
+    /// Returns a cleaned literal that parses, if the input has a single common defect.
+    #[inline]
+    pub fn suggestion(&self) -> Option<&str> {
+        self.suggestion.as_deref()
+    }
+}
+
+impl fmt::Display for SuggestedParseError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.suggestion {
+            Some(suggestion) => write!(f, "{}, did you mean `{}`?", self.kind, suggestion),
+            None => fmt::Display::fmt(&self.kind, f),
+        }
+    }
+}
+
+impl std::error::Error for SuggestedParseError {}
+
+impl From<SuggestedParseError> for DecimalParseError {
+    #[inline]
+    fn from(e: SuggestedParseError) -> Self {
+        e.kind
+    }
+}
+
+/// An error which can be returned when decoding a decimal from bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecimalDecodeError {
+    /// The input ended before the encoding was complete.
+    UnexpectedEof,
+    /// The encoding has an unknown version.
+    UnknownVersion(u8),
+    /// The encoding doesn't represent a valid decimal.
+    Invalid,
+}
+
+impl fmt::Display for DecimalDecodeError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecimalDecodeError::UnexpectedEof => f.write_str("unexpected end of decimal encoding"),
+            DecimalDecodeError::UnknownVersion(v) => write!(f, "unknown decimal encoding version {}", v),
+            DecimalDecodeError::Invalid => f.write_str("invalid decimal encoding"),
+        }
+    }
+}
+
+impl std::error::Error for DecimalDecodeError {}
+
+/// An error which can be returned when converting between a decimal and another type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecimalConvertError {
+    /// The decimal is NaN, which the other type can't represent.
+    NaN,
+    /// The value doesn't fit in the target type.
+    Overflow,
+}
+
+impl fmt::Display for DecimalConvertError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecimalConvertError::NaN => f.write_str("cannot convert NaN"),
+            DecimalConvertError::Overflow => f.write_str("value is out of range of the target type"),
+        }
+    }
+}
+
+impl std::error::Error for DecimalConvertError {}
+
+/// An error which can be returned when rescaling a decimal with
+/// [`Decimal::rescale`](crate::Decimal::rescale).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecimalError {
+    /// The scale exceeds `MAX_SCALE`.
+    InvalidScale,
+    /// The result would need more than `MAX_PRECISION` digits.
+    Overflow,
+}
+
+impl fmt::Display for DecimalError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecimalError::InvalidScale => f.write_str("scale is out of range of decimal"),
+            DecimalError::Overflow => f.write_str("number is too large to fit in decimal"),
+        }
+    }
+}
+
+impl std::error::Error for DecimalError {}
+
+/// An error which can be returned when reading a decimal from an environment variable with
+/// [`Decimal::from_env`](crate::Decimal::from_env).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EnvDecimalError {
+    /// The variable is not set.
+    NotPresent(String),
+    /// The variable is set but not valid unicode.
+    NotUnicode(String),
+    /// The value of the variable is not a valid decimal.
+    Parse {
+        /// Name of the variable.
+        var: String,
+        /// Value of the variable.
+        value: String,
+        /// The parse error.
+        error: DecimalParseError,
+    },
+}
+
+impl EnvDecimalError {
+    /// Returns the name of the variable.
+    #[inline]
+    pub fn var(&self) -> &str {
+        match self {
+            EnvDecimalError::NotPresent(var) | EnvDecimalError::NotUnicode(var) => var,
+            EnvDecimalError::Parse { var, .. } => var,
+        }
+    }
+}
+
+impl fmt::Display for EnvDecimalError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvDecimalError::NotPresent(var) => write!(f, "environment variable `{}` is not set", var),
+            EnvDecimalError::NotUnicode(var) => write!(f, "environment variable `{}` is not valid unicode", var),
+            EnvDecimalError::Parse { var, value, error } => {
+                write!(f, "environment variable `{}`: {}: `{}`", var, error, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnvDecimalError {
+    #[inline]
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EnvDecimalError::Parse { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// An error which can be returned when rounding to the minor unit of a currency with
+/// [`Decimal::round_for_currency`](crate::Decimal::round_for_currency).
+#[cfg(feature = "currency")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurrencyError {
+    /// The code isn't an ISO 4217 currency with a minor unit.
+    Unknown,
+    /// The rounded value doesn't fit in a decimal.
+    Overflow,
+}
+
+#[cfg(feature = "currency")]
+impl fmt::Display for CurrencyError {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurrencyError::Unknown => f.write_str("unknown currency code"),
+            CurrencyError::Overflow => f.write_str("overflow when rounding to a currency"),
+        }
+    }
+}
+
+#[cfg(feature = "currency")]
+impl std::error::Error for CurrencyError {}
@@ -0,0 +1,64 @@
+//! Spreadsheet export.
+
+use crate::decimal::Decimal;
+
+/// Maximum number of significant digits kept by Excel numbers.
+const EXCEL_PRECISION: u32 = 15;
+
+/// Maximum number of characters Excel displays in the General format before switching to
+/// scientific notation.
+const EXCEL_GENERAL_WIDTH: usize = 11;
+
+impl Decimal {
+    /// Returns `true` if Excel can store this decimal as a number without losing digits.
+    ///
+    /// Excel numbers keep at most 15 significant digits, so this is `false` for NaN and for
+    /// values with more significant digits, ignoring trailing zeros after the decimal point.
+    #[inline]
+    pub fn fits_in_excel_number(&self) -> bool {
+        !self.is_nan() && self.canonicalize().precision() <= EXCEL_PRECISION
+    }
+
+    /// Formats for a spreadsheet cell so that Excel neither truncates nor reinterprets the value.
+    ///
+    /// Values that [fit in an Excel number](Decimal::fits_in_excel_number) and whose
+    /// [`Display`](std::fmt::Display) form has at most 11 characters are formatted as by
+    /// `Display`. Excel shows longer numbers in scientific notation, such as `1.23457E+11` for
+    /// `123456789012` and `1.23E-10` for `0.000000000123`, so they and other numbers are formatted
+    /// as a text formula like `="1234567890.1234567890"`, and NaN as `NaN`.
+    pub fn to_excel_compatible_string(&self) -> String {
+        if self.is_nan() {
+            return self.to_string();
+        }
+        let s = self.to_string();
+        if self.fits_in_excel_number() && s.len() <= EXCEL_GENERAL_WIDTH {
+            s
+        } else {
+            format!("=\"{}\"", s)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Decimal;
+
+    fn excel(s: &str) -> String {
+        s.parse::<Decimal>().unwrap().to_excel_compatible_string()
+    }
+
+    #[test]
+    fn to_excel_compatible_string() {
+        assert_eq!(excel("12345678901"), "12345678901");
+        assert_eq!(excel("-1234567.12"), "-1234567.12");
+        assert_eq!(excel("-1234567.125"), "=\"-1234567.125\"");
+        assert_eq!(excel("123456789012"), "=\"123456789012\"");
+        assert_eq!(excel("-1234567890"), "-1234567890");
+        assert_eq!(excel("-12345678901"), "=\"-12345678901\"");
+        assert_eq!(excel("0.000000000123"), "=\"0.000000000123\"");
+        assert_eq!(excel("0.123456789"), "0.123456789");
+        assert_eq!(excel("1.5000000000000000000"), "=\"1.5000000000000000000\"");
+        assert_eq!(excel("1.2345678901234567"), "=\"1.2345678901234567\"");
+        assert_eq!(excel("NaN"), "NaN");
+    }
+}
@@ -0,0 +1,63 @@
+//! FIX protocol float fields.
+
+use crate::decimal::{Decimal, MAX_INT_DIGITS, MAX_PRECISION};
+use crate::error::{DecimalConvertError, DecimalParseError};
+use crate::parse::{scan, to_decimal, Scanned};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+
+impl Decimal {
+    /// Formats as a FIX float field, rounded half up to at most `max_precision` significant
+    /// digits.
+    ///
+    /// The field has no exponent and no trailing zeros after the decimal point, e.g. `1.5` and
+    /// `-1200`. Returns an error for NaN, which FIX can't represent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_precision` is zero.
+    pub fn to_fix_str(&self, max_precision: u32) -> Result<String, DecimalConvertError> {
+        assert!(max_precision > 0, "max precision is zero");
+        if self.is_nan() {
+            return Err(DecimalConvertError::NaN);
+        }
+        if self.is_zero() {
+            return Ok("0".to_string());
+        }
+
+        let precision = max_precision.min(MAX_PRECISION) as i32;
+        let scale = self.scale().min(precision - self.exponent());
+        let mut var = Var::from_decimal(self);
+        var.round(scale, RoundingStrategy::HalfUp, false);
+        match var.into_decimal(RoundingStrategy::HalfUp) {
+            Some((d, _)) => Ok(d.canonicalize().to_string()),
+            None => {
+                // Only `10^MAX_INT_DIGITS` can result from rounding a decimal and not fit.
+                let sign = if self.is_sign_negative() { "-" } else { "" };
+                Ok(format!("{}1{:0width$}", sign, 0, width = MAX_INT_DIGITS as usize))
+            }
+        }
+    }
+
+    /// Parses a FIX float field.
+    ///
+    /// The field is an optional `-` followed by digits with an optional decimal point. Unlike
+    /// [`FromStr`](std::str::FromStr), `+`, exponents and NaN are rejected.
+    pub fn from_fix_str(s: &str) -> Result<Decimal, DecimalParseError> {
+        let bytes = s.as_bytes();
+        let unsigned = bytes.strip_prefix(b"-").unwrap_or(bytes);
+        if unsigned.iter().any(|&c| !c.is_ascii_digit() && c != b'.') {
+            return Err(DecimalParseError::Invalid);
+        }
+
+        match scan(bytes)? {
+            Scanned::Number {
+                negative,
+                int,
+                frac,
+                exp,
+            } => to_decimal(negative, int, frac, exp),
+            Scanned::NaN { .. } => Err(DecimalParseError::Invalid),
+        }
+    }
+}
@@ -0,0 +1,311 @@
+//! Decimal formatting.
+
+use crate::decimal::{digits_of, Decimal, DEC_DIGITS, MAX_PRECISION, MAX_SCALE, POWERS_10};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+use std::fmt;
+
+/// Maximum length of a formatted decimal without sign.
+pub(crate) const MAX_STR_LEN: usize = MAX_PRECISION as usize + MAX_SCALE as usize + 2;
+
+/// Fixed size buffer for formatted decimals.
+pub(crate) struct Buf {
+    bytes: [u8; MAX_STR_LEN],
+    len: usize,
+}
+
+impl Buf {
+    #[inline]
+    pub(crate) const fn new() -> Buf {
+        Buf {
+            bytes: [0; MAX_STR_LEN],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, c: u8) {
+        self.bytes[self.len] = c;
+        self.len += 1;
+    }
+
+    /// Pushes the `n` low decimal digits of `limb`, zero padded.
+    #[inline]
+    pub(crate) fn push_limb(&mut self, limb: u32, n: i32) {
+        bench_count!(FORMAT_DIGITS, n);
+        let mut div = POWERS_10[n as usize];
+        for _ in 0..n {
+            div /= 10;
+            self.push(b'0' + (limb / div % 10) as u8);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn as_str(&self) -> &str {
+        // SAFETY: only ASCII digits and punctuation are pushed.
+        unsafe { std::str::from_utf8_unchecked(&self.bytes[..self.len]) }
+    }
+}
+
+impl Decimal {
+    /// Writes the absolute value with `dscale` digits after the decimal point.
+    pub(crate) fn fmt_abs(&self, buf: &mut Buf) {
+        let limbs = self.limbs();
+        let weight = self.weight as i32;
+        let limb_at = |w: i32| -> u32 {
+            let index = weight - w;
+            if index >= 0 && (index as usize) < limbs.len() {
+                limbs[index as usize]
+            } else {
+                0
+            }
+        };
+
+        if limbs.is_empty() || weight < 0 {
+            buf.push(b'0');
+        } else {
+            buf.push_limb(limbs[0], digits_of(limbs[0]));
+            for w in (0..weight).rev() {
+                buf.push_limb(limb_at(w), DEC_DIGITS);
+            }
+        }
+
+        let mut remaining = self.dscale as i32;
+        if remaining > 0 {
+            buf.push(b'.');
+            let mut w = -1;
+            while remaining > 0 {
+                let n = remaining.min(DEC_DIGITS);
+                buf.push_limb(limb_at(w) / POWERS_10[(DEC_DIGITS - n) as usize], n);
+                remaining -= n;
+                w -= 1;
+            }
+        }
+    }
+}
+
+impl Decimal {
+    /// Formats in the machine interchange form.
+    ///
+    /// The form is locale independent, ASCII only and never uses an exponent: an optional `-`,
+    /// the integral digits without leading zeros, and the digits after the decimal point up to
+    /// the scale, e.g. `-1234.50`. NaN is formatted as `NaN` or `sNaN` followed by its payload.
+    /// Parsing the result with [`ParseOptions::STRICT`](crate::ParseOptions::STRICT) gives back
+    /// the same decimal with the same scale.
+    #[inline]
+    pub fn to_machine_string(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns an object that displays at least `n` digits after the decimal point, padding
+    /// with zeros, e.g. `10` as `10.00` for `n = 2`.
+    ///
+    /// Decimals with a larger scale are displayed unchanged, and `n` is capped at `MAX_SCALE`.
+    #[inline]
+    pub fn display_min_scale(&self, n: u32) -> impl fmt::Display {
+        let mut padded = *self;
+        if !self.is_nan() {
+            padded.dscale = padded.dscale.max(n.min(MAX_SCALE as u32) as u8);
+        }
+        padded
+    }
+
+    /// Returns an object that displays the decimal as `f64`'s `Debug` displays a float with
+    /// the same digits, for output that must look the same as it did with floats.
+    ///
+    /// Trailing zeros are dropped as floats have no scale, integers keep one zero after the
+    /// decimal point, and magnitudes below `1e-4` or from `1e16` on switch to scientific
+    /// notation, e.g. `1.50` as `1.5`, `3` as `3.0`, `0.00001` as `1e-5` and `12345e20` as
+    /// `1.2345e24`. Zero is `0.0`, and NaN is `NaN` without payload.
+    #[inline]
+    pub fn display_f64_style(&self) -> impl fmt::Display {
+        F64Style(*self)
+    }
+
+    /// Divides and formats the quotient with up to `digits` digits after the decimal point,
+    /// which may be more than a decimal can store, e.g. `1 / 3` to 100 places.
+    ///
+    /// The quotient is computed exactly by long division and rounded half up at the last digit.
+    /// An exact quotient with fewer digits after the decimal point is displayed without
+    /// trailing zeros, e.g. `1 / 4` as `0.25`. NaN operands give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `divisor` is zero.
+    pub fn divide_to_string(&self, divisor: &Decimal, digits: usize) -> String {
+        let u = Var::from_decimal(self);
+        let v = Var::from_decimal(divisor);
+        if let Some(nan) = u.nan_operand(&v) {
+            return nan.into_decimal(RoundingStrategy::HalfUp).unwrap().0.to_string();
+        }
+        assert!(!divisor.is_zero(), "division by zero");
+
+        // `self / divisor` is `(a / b) * 10^(sb - sa)`, so its digits are those of `a / b` with
+        // the decimal point moved, and they are generated from the remainder, which fits as
+        // `b < 10^MAX_PRECISION`.
+        let ((a, ea), (b, eb)) = (self.coefficient_parts(), divisor.coefficient_parts());
+        let mut quotient: Vec<u8> = (a / b).to_string().bytes().map(|c| c - b'0').collect();
+        let point = quotient.len() as i64 + ea as i64 - eb as i64;
+        let end = point + digits as i64 + 1;
+        let mut remainder = a % b;
+        while (quotient.len() as i64) < end && remainder != 0 {
+            remainder *= 10;
+            quotient.push((remainder / b) as u8);
+            remainder %= b;
+        }
+
+        // Digits from the units digit, or the first digit of `a / b` if higher, through the
+        // first discarded digit, followed by a sticky digit for the rest.
+        let start = (point - 1).min(0);
+        let digit = |i: i64| {
+            if i >= 0 {
+                quotient.get(i as usize).copied().unwrap_or(0)
+            } else {
+                0
+            }
+        };
+        let mut buf: Vec<u8> = (start..end).map(digit).collect();
+        let rest = quotient.get(end.max(0) as usize..).unwrap_or(&[]);
+        buf.push((remainder != 0 || rest.iter().any(|&d| d != 0)) as u8);
+
+        let keep = (end - 1 - start) as usize;
+        let (inexact, carried) = RoundingStrategy::HalfUp.round_digits(&mut buf, keep, false);
+        buf.truncate(keep);
+        let mut int_len = (point - start) as usize;
+        if carried {
+            // The kept digits became 1 followed by zeros, and the quotient gained a digit.
+            buf.push(0);
+            int_len += 1;
+        }
+
+        let (int, mut frac) = buf.split_at(int_len);
+        if !inexact {
+            while let Some((&0, init)) = frac.split_last() {
+                frac = init;
+            }
+        }
+        let leading_zeros = int.iter().take_while(|&&d| d == 0).count().min(int.len() - 1);
+        let int = &int[leading_zeros..];
+
+        let mut result = String::with_capacity(int.len() + frac.len() + 2);
+        let negative = self.is_sign_negative() != divisor.is_sign_negative();
+        if negative && int.iter().chain(frac).any(|&d| d != 0) {
+            result.push('-');
+        }
+        result.extend(int.iter().map(|&d| (b'0' + d) as char));
+        if !frac.is_empty() {
+            result.push('.');
+            result.extend(frac.iter().map(|&d| (b'0' + d) as char));
+        }
+        result
+    }
+}
+
+/// Displays a decimal with the rules of `f64`'s `Debug`, see [`Decimal::display_f64_style`].
+struct F64Style(Decimal);
+
+impl fmt::Display for F64Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let d = self.0.canonicalize();
+        if d.is_nan() {
+            return f.pad("NaN");
+        }
+        if d.is_zero() {
+            return f.pad_integral(true, "", "0.0");
+        }
+
+        let mut buf = Buf::new();
+        let e = d.exponent() - 1;
+        if (-4..16).contains(&e) {
+            Decimal {
+                dscale: d.dscale.max(1),
+                ..d
+            }
+            .fmt_abs(&mut buf);
+        } else {
+            let mut digits = d.decimal_digits();
+            buf.push(b'0' + digits.next().unwrap());
+            let mut rest = Buf::new();
+            for digit in digits {
+                rest.push(b'0' + digit);
+            }
+            let rest = rest.as_str().trim_end_matches('0');
+            if !rest.is_empty() {
+                buf.push(b'.');
+                for &c in rest.as_bytes() {
+                    buf.push(c);
+                }
+            }
+            buf.push(b'e');
+            if e < 0 {
+                buf.push(b'-');
+            }
+            let e = e.unsigned_abs();
+            buf.push_limb(e, digits_of(e));
+        }
+        f.pad_integral(d.is_sign_positive(), "", buf.as_str())
+    }
+}
+
+impl fmt::Display for Decimal {
+    /// Displays every digit up to the scale, including trailing zeros, so a decimal parsed from
+    /// `1.500` displays as `1.500`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_nan() {
+            let prefix = if self.is_signaling_nan() { "sNaN" } else { "NaN" };
+            return match self.nan_payload() {
+                Some(0) | None => f.pad(prefix),
+                Some(payload) => f.pad(&format!("{}{}", prefix, payload)),
+            };
+        }
+
+        let mut buf = Buf::new();
+        self.fmt_abs(&mut buf);
+        f.pad_integral(!self.is_sign_negative(), "", buf.as_str())
+    }
+}
+
+impl fmt::Debug for Decimal {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Decimal;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn display_keeps_parsed_scale() {
+        assert_eq!(dec("1.500").to_string(), "1.500");
+        assert_eq!(dec("-1.500").to_string(), "-1.500");
+        assert_eq!(dec("0.000").to_string(), "0.000");
+        assert_eq!(dec("1.5").to_string(), "1.5");
+    }
+
+    #[test]
+    fn scale_survives_arithmetic() {
+        let x = dec("1.500");
+        assert_eq!((-x).to_string(), "-1.500");
+        assert_eq!((-x).abs().to_string(), "1.500");
+        assert_eq!((x + dec("1")).to_string(), "2.500");
+        assert_eq!((x + dec("0.25")).to_string(), "1.750");
+        assert_eq!((x * dec("2")).to_string(), "3.000");
+        assert_eq!((x * dec("0.10")).to_string(), "0.15000");
+        let sum: Decimal = [x, dec("2"), dec("0.5")].iter().sum();
+        assert_eq!(sum.to_string(), "4.000");
+    }
+
+    #[test]
+    fn display_min_scale_pads() {
+        assert_eq!(dec("10").display_min_scale(2).to_string(), "10.00");
+        assert_eq!(dec("1.5").display_min_scale(2).to_string(), "1.50");
+        assert_eq!(dec("1.500").display_min_scale(2).to_string(), "1.500");
+        assert_eq!(dec("NaN").display_min_scale(2).to_string(), "NaN");
+    }
+}
@@ -0,0 +1,370 @@
+//! JSON numbers.
+//!
+//! [`JsonScanner`] reads number tokens straight from the bytes of a JSON text and
+//! [`write_number`] writes them, so hand-written JSON readers and writers can delegate number
+//! handling to this crate. With the `serde_json` feature, decimals also convert exactly to and
+//! from `serde_json::Number`, enabling the `arbitrary_precision` feature of `serde_json` so
+//! numbers keep their digits instead of going through `f64`.
+
+use crate::decimal::Decimal;
+use crate::error::{DecimalConvertError, DecimalParseError};
+use crate::fmt::Buf;
+#[cfg(feature = "serde_json")]
+use crate::options::ParseOptions;
+use crate::parse::{to_decimal_rounded, MAX_EXPONENT};
+use crate::round::RoundingStrategy;
+use std::ops::Range;
+
+#[cfg(feature = "serde_json")]
+impl Decimal {
+    /// Converts to a JSON number with the same digits as [`Display`](std::fmt::Display), or
+    /// returns `None` for NaN, which JSON can't represent.
+    ///
+    /// The scale is kept on purpose, so `1.50` is written as `1.50`. Convert the
+    /// [canonical form](Decimal::canonicalize) for output that only depends on the value.
+    #[inline]
+    pub fn to_json_number(&self) -> Option<serde_json::Number> {
+        if self.is_nan() {
+            return None;
+        }
+        // The display form is always valid JSON number syntax.
+        Some(serde_json::Number::from_string_unchecked(self.to_string()))
+    }
+
+    /// Converts a JSON number exactly, keeping its scale.
+    ///
+    /// Returns [`DecimalParseError::Overflow`] if the number doesn't fit in a decimal without
+    /// rounding.
+    #[inline]
+    pub fn from_json_number(n: &serde_json::Number) -> Result<Decimal, DecimalParseError> {
+        Decimal::parse_with(n.as_str(), &ParseOptions::new().rounding(None))
+    }
+}
+
+/// Parser of JSON number tokens.
+///
+/// The defaults follow the grammar of RFC 8259, `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][+-]?[0-9]+)?`,
+/// and keep numbers exactly with their scale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct JsonScanner {
+    leading_zeros: bool,
+    rounding: Option<RoundingStrategy>,
+}
+
+impl JsonScanner {
+    /// Creates the default scanner.
+    #[inline]
+    pub const fn new() -> JsonScanner {
+        JsonScanner {
+            leading_zeros: false,
+            rounding: None,
+        }
+    }
+
+    /// Sets whether integral parts with leading zeros such as `007` are accepted, `false` by
+    /// default as the JSON grammar forbids them.
+    #[inline]
+    pub const fn leading_zeros(mut self, allow: bool) -> JsonScanner {
+        self.leading_zeros = allow;
+        self
+    }
+
+    /// Sets the rounding of digits that don't fit in a decimal.
+    ///
+    /// If `None`, the default, such numbers are rejected with [`DecimalParseError::Overflow`].
+    #[inline]
+    pub const fn rounding(mut self, rounding: Option<RoundingStrategy>) -> JsonScanner {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Parses the number token at the start of `input`, returning the decimal and the length of
+    /// the token.
+    ///
+    /// The token ends at the first byte that can't continue it, which the caller checks as a
+    /// delimiter of its grammar. A token followed by a digit, `.`, `e`, `E`, `+` or `-` is
+    /// malformed and rejected with [`DecimalParseError::Invalid`], e.g. `0123` or `1.`.
+    pub fn scan(&self, input: &[u8]) -> Result<(Decimal, usize), DecimalParseError> {
+        if input.is_empty() {
+            return Err(DecimalParseError::Empty);
+        }
+
+        let negative = input[0] == b'-';
+        let mut pos = negative as usize;
+        let int = ascii_digits(&input[pos..]);
+        if int.is_empty() || (!self.leading_zeros && int.len() > 1 && int[0] == b'0') {
+            return Err(DecimalParseError::Invalid);
+        }
+        pos += int.len();
+
+        let mut frac: &[u8] = &[];
+        if input.get(pos) == Some(&b'.') {
+            frac = ascii_digits(&input[pos + 1..]);
+            if frac.is_empty() {
+                return Err(DecimalParseError::Invalid);
+            }
+            pos += 1 + frac.len();
+        }
+
+        let mut exp = 0;
+        if let Some(b'e' | b'E') = input.get(pos) {
+            pos += 1;
+            let negative_exp = input.get(pos) == Some(&b'-');
+            if let Some(b'+' | b'-') = input.get(pos) {
+                pos += 1;
+            }
+            let digits = ascii_digits(&input[pos..]);
+            if digits.is_empty() {
+                return Err(DecimalParseError::Invalid);
+            }
+            pos += digits.len();
+            exp = digits
+                .iter()
+                .fold(0i64, |exp, &c| (exp * 10 + (c - b'0') as i64).min(MAX_EXPONENT));
+            if negative_exp {
+                exp = -exp;
+            }
+        }
+
+        if input.get(pos).is_some_and(|&c| continues_number(c)) {
+            return Err(DecimalParseError::Invalid);
+        }
+        let result = to_decimal_rounded(negative, int, frac, exp, self.rounding)?;
+        Ok((result, pos))
+    }
+
+    /// Returns an iterator over the number tokens of a JSON text, with their byte ranges.
+    ///
+    /// Strings are skipped, so digits in keys and values aren't mistaken for numbers, and the
+    /// text isn't otherwise validated. A malformed token yields an error and the iteration
+    /// resumes after it.
+    #[inline]
+    pub fn numbers<'a>(&self, input: &'a [u8]) -> JsonNumbers<'a> {
+        JsonNumbers {
+            scanner: *self,
+            input,
+            pos: 0,
+        }
+    }
+}
+
+impl Default for JsonScanner {
+    #[inline]
+    fn default() -> JsonScanner {
+        JsonScanner::new()
+    }
+}
+
+/// Iterator over the number tokens of a JSON text, returned by [`JsonScanner::numbers`].
+#[derive(Clone, Debug)]
+pub struct JsonNumbers<'a> {
+    scanner: JsonScanner,
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl JsonNumbers<'_> {
+    /// Skips the string starting at the current position, up to its closing quote.
+    fn skip_string(&mut self) {
+        self.pos += 1;
+        while let Some(&c) = self.input.get(self.pos) {
+            self.pos += if c == b'\\' { 2 } else { 1 };
+            if c == b'"' {
+                return;
+            }
+        }
+    }
+}
+
+impl Iterator for JsonNumbers<'_> {
+    type Item = Result<(Decimal, Range<usize>), DecimalParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&c) = self.input.get(self.pos) {
+            match c {
+                b'"' => self.skip_string(),
+                b'-' | b'0'..=b'9' => {
+                    let start = self.pos;
+                    let rest = &self.input[start..];
+                    return Some(match self.scanner.scan(rest) {
+                        Ok((d, len)) => {
+                            self.pos += len;
+                            Ok((d, start..self.pos))
+                        }
+                        Err(e) => {
+                            self.pos += rest.iter().take_while(|&&c| continues_number(c)).count();
+                            Err(e)
+                        }
+                    });
+                }
+                _ => self.pos += 1,
+            }
+        }
+        None
+    }
+}
+
+/// Appends `value` to `buf` as a JSON number with the same digits as
+/// [`Display`](std::fmt::Display), keeping its scale, so [`JsonScanner::scan`] reads it back
+/// identically. Write the [canonical form](Decimal::canonicalize) for output that only depends
+/// on the value.
+///
+/// Returns [`DecimalConvertError::NaN`] for NaN, which JSON can't represent, without writing
+/// anything.
+pub fn write_number(value: &Decimal, buf: &mut Vec<u8>) -> Result<(), DecimalConvertError> {
+    if value.is_nan() {
+        return Err(DecimalConvertError::NaN);
+    }
+    if value.is_sign_negative() {
+        buf.push(b'-');
+    }
+    let mut digits = Buf::new();
+    value.fmt_abs(&mut digits);
+    buf.extend_from_slice(digits.as_str().as_bytes());
+    Ok(())
+}
+
+/// Returns the leading ASCII digits of `input`.
+#[inline]
+fn ascii_digits(input: &[u8]) -> &[u8] {
+    let len = input.iter().take_while(|c| c.is_ascii_digit()).count();
+    &input[..len]
+}
+
+/// Returns `true` if `c` can appear in a number token.
+#[inline]
+fn continues_number(c: u8) -> bool {
+    c.is_ascii_digit() || matches!(c, b'.' | b'e' | b'E' | b'+' | b'-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_number, JsonScanner};
+    use crate::error::{DecimalConvertError, DecimalParseError};
+    use crate::{Decimal, RoundingStrategy};
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    fn scan(input: &str) -> Result<(String, usize), DecimalParseError> {
+        JsonScanner::new()
+            .scan(input.as_bytes())
+            .map(|(d, len)| (d.to_string(), len))
+    }
+
+    #[test]
+    fn scan_tokens() {
+        assert_eq!(scan("0"), Ok(("0".to_string(), 1)));
+        assert_eq!(scan("-0.50,"), Ok(("-0.50".to_string(), 5)));
+        assert_eq!(scan("12.5e-3]"), Ok(("0.0125".to_string(), 7)));
+        assert_eq!(scan("1E+2 "), Ok(("100".to_string(), 4)));
+        assert_eq!(scan("-7}"), Ok(("-7".to_string(), 2)));
+    }
+
+    #[test]
+    fn malformed_tokens() {
+        for input in [
+            "0123", "00", "-01", "1.", "1.e5", ".5", "-", "-x", "+1", "1e", "1e+", "1E-", "1.5.2", "1e5e", "1-", "0x10",
+        ] {
+            let expected = if input == "0x10" {
+                Ok(("0".to_string(), 1))
+            } else {
+                Err(DecimalParseError::Invalid)
+            };
+            assert_eq!(scan(input), expected, "{input}");
+        }
+        assert_eq!(scan(""), Err(DecimalParseError::Empty));
+    }
+
+    #[test]
+    fn leading_zeros() {
+        let scanner = JsonScanner::new().leading_zeros(true);
+        let scan = |input: &str| scanner.scan(input.as_bytes()).map(|(d, len)| (d.to_string(), len));
+        assert_eq!(scan("0123"), Ok(("123".to_string(), 4)));
+        assert_eq!(scan("-007.50"), Ok(("-7.50".to_string(), 7)));
+        assert_eq!(scan("00"), Ok(("0".to_string(), 2)));
+        assert_eq!(scan("01."), Err(DecimalParseError::Invalid));
+        assert_eq!(scan("-"), Err(DecimalParseError::Invalid));
+    }
+
+    #[test]
+    fn rounding() {
+        let digits = "1.2345678901234567890123456789012345678";
+        assert_eq!(scan(digits), Err(DecimalParseError::Overflow));
+        let scanner = JsonScanner::new().rounding(Some(RoundingStrategy::HalfEven));
+        let (d, len) = scanner.scan(digits.as_bytes()).unwrap();
+        assert_eq!(
+            (d.to_string().as_str(), len),
+            ("1.23456789012345678901234567890123457", digits.len())
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        let text = br#"{"a1": 1.50, "b\"2": [-3, "4\\", 5e1], "c": "x\"6\"y", "7": true, "d": -0.0}"#;
+        let found: Vec<_> = JsonScanner::new()
+            .numbers(text)
+            .map(|r| r.map(|(d, range)| (d.to_string(), std::str::from_utf8(&text[range]).unwrap().to_string())))
+            .collect();
+        let expected = [("1.50", "1.50"), ("-3", "-3"), ("50", "5e1"), ("0.0", "-0.0")];
+        assert_eq!(found, expected.map(|(d, token)| Ok((d.to_string(), token.to_string()))));
+    }
+
+    #[test]
+    fn numbers_resume_after_bad_tokens() {
+        let text = b"[1, 0123, 2, 1., 3, -, 4, 1e, 5, 1.5.2, 6]";
+        let found: Vec<_> = JsonScanner::new()
+            .numbers(text)
+            .map(|r| r.map(|(d, range)| (d.to_string(), range)))
+            .collect();
+        let invalid = Err(DecimalParseError::Invalid);
+        assert_eq!(
+            found,
+            [
+                Ok(("1".to_string(), 1..2)),
+                invalid.clone(),
+                Ok(("2".to_string(), 10..11)),
+                invalid.clone(),
+                Ok(("3".to_string(), 17..18)),
+                invalid.clone(),
+                Ok(("4".to_string(), 23..24)),
+                invalid.clone(),
+                Ok(("5".to_string(), 30..31)),
+                invalid,
+                Ok(("6".to_string(), 40..41)),
+            ]
+        );
+    }
+
+    #[test]
+    fn write_numbers() {
+        let mut buf = Vec::new();
+        for s in [
+            "1.50",
+            "-0.001",
+            "0",
+            "123e40",
+            "-99999999999999999999999999999999.9999",
+        ] {
+            buf.clear();
+            write_number(&dec(s), &mut buf).unwrap();
+            let (d, len) = JsonScanner::new().scan(&buf).unwrap();
+            assert_eq!((d.to_string(), len), (dec(s).to_string(), buf.len()));
+        }
+        buf.clear();
+        assert_eq!(write_number(&Decimal::NAN, &mut buf), Err(DecimalConvertError::NaN));
+        assert!(buf.is_empty());
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json_numbers() {
+        let n = dec("-12.500").to_json_number().unwrap();
+        assert_eq!(n.as_str(), "-12.500");
+        assert_eq!(Decimal::from_json_number(&n).unwrap().to_string(), "-12.500");
+        assert_eq!(Decimal::NAN.to_json_number(), None);
+        let n: serde_json::Number = serde_json::from_str("1.23456789012345678901234567890123456789").unwrap();
+        assert_eq!(Decimal::from_json_number(&n), Err(DecimalParseError::Overflow));
+    }
+}
@@ -0,0 +1,520 @@
+//! Keys for hash tables and ordered collections.
+
+use crate::decimal::{
+    Decimal, DEC_DIGITS, MAX_INT_DIGITS, MAX_NDIGITS, MAX_PRECISION, MAX_SCALE, NBASE, SIGN_NEG, SIGN_POS,
+};
+use crate::error::DecimalDecodeError;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// A decimal in canonical form, for use as a key.
+///
+/// Equality and hashing compare the canonical representation directly, without normalizing on
+/// every call. They agree with those of [`Decimal`], so a table keyed by `OrdDecimal` can be
+/// queried with a `&Decimal`, e.g. `map.get(&d)`, and `1.50` finds the key `1.5`.
+#[derive(Clone, Copy)]
+pub struct OrdDecimal(Decimal);
+
+impl OrdDecimal {
+    /// Creates a key from a decimal.
+    #[inline]
+    pub fn new(d: Decimal) -> OrdDecimal {
+        OrdDecimal(d.canonicalize())
+    }
+
+    /// Returns the canonical decimal.
+    #[inline]
+    pub const fn get(&self) -> Decimal {
+        self.0
+    }
+}
+
+impl From<Decimal> for OrdDecimal {
+    #[inline]
+    fn from(d: Decimal) -> Self {
+        OrdDecimal::new(d)
+    }
+}
+
+impl From<OrdDecimal> for Decimal {
+    #[inline]
+    fn from(key: OrdDecimal) -> Self {
+        key.0
+    }
+}
+
+impl Borrow<Decimal> for OrdDecimal {
+    #[inline]
+    fn borrow(&self) -> &Decimal {
+        &self.0
+    }
+}
+
+impl PartialEq for OrdDecimal {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (&self.0, &other.0);
+        a.sign == b.sign && a.weight == b.weight && a.limbs() == b.limbs()
+    }
+}
+
+impl Eq for OrdDecimal {}
+
+impl Hash for OrdDecimal {
+    /// Hashes the same as the equal [`Decimal`].
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.sign.hash(state);
+        self.0.weight.hash(state);
+        self.0.limbs().hash(state);
+    }
+}
+
+impl PartialOrd for OrdDecimal {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrdDecimal {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl fmt::Display for OrdDecimal {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for OrdDecimal {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+/// Allows querying tables keyed by `Decimal` with a `&OrdDecimal`, in `hashbrown` and
+/// `indexmap`. The other direction is covered by [`Borrow`].
+#[cfg(feature = "equivalent")]
+impl equivalent::Equivalent<Decimal> for OrdDecimal {
+    #[inline]
+    fn equivalent(&self, key: &Decimal) -> bool {
+        self.0 == *key
+    }
+}
+
+/// Length of a [`DecimalKey`] in bytes.
+const KEY_LEN: usize = 20;
+/// Bits stored per limb in a key, enough for values below `NBASE`.
+const LIMB_BITS: u32 = 30;
+
+const KEY_ZERO: u8 = 0x80;
+const KEY_NAN: u8 = 0xff;
+/// Header of a positive key with weight 0, the header of other weights is offset by the weight.
+const KEY_POSITIVE: u8 = 0xc0;
+
+/// Maximum length of a compact key in bytes: the header and 19 pairs of digits.
+pub const MAX_COMPACT_KEY_LEN: usize = 1 + (MAX_PRECISION as usize + 2) / 2;
+/// Header of a positive compact key whose first pair of digits has the weight `100^-1`, the
+/// header of other weights is offset by the exponent in base 100.
+const COMPACT_POSITIVE: u8 = 0xc1;
+
+/// A compact key with the order and equality of decimals, for index structures.
+///
+/// The key is 20 bytes that compare as the decimal they come from: byte-wise comparison, and so
+/// the derived `Ord`, `Eq` and `Hash`, agree with [`Decimal`]. The scale and NaN payload are not
+/// kept, so [`Decimal::from_key`] gives the canonical decimal, and
+/// [`Decimal::sort_key_total`] extends the key to tell them apart.
+///
+/// The first byte holds the sign and weight, and the limbs follow packed in 30 bits each, with
+/// every byte inverted for negative numbers so larger magnitudes sort first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DecimalKey([u8; KEY_LEN]);
+
+impl DecimalKey {
+    /// Returns the bytes of the key.
+    #[inline]
+    pub const fn to_bytes(self) -> [u8; KEY_LEN] {
+        self.0
+    }
+
+    /// Creates a key from bytes returned by [`to_bytes`](DecimalKey::to_bytes).
+    ///
+    /// Returns [`DecimalDecodeError::Invalid`] if the bytes are not the key of a decimal.
+    pub fn from_bytes(bytes: [u8; KEY_LEN]) -> Result<DecimalKey, DecimalDecodeError> {
+        let key = DecimalKey(bytes);
+        let d = key.decode().ok_or(DecimalDecodeError::Invalid)?;
+        if !d.is_internally_consistent() || d.to_key() != key {
+            return Err(DecimalDecodeError::Invalid);
+        }
+        Ok(key)
+    }
+
+    /// Decodes the key, returning `None` for malformed limbs.
+    fn decode(&self) -> Option<Decimal> {
+        let mut bytes = self.0;
+        match bytes[0] {
+            KEY_NAN => return Some(Decimal::NAN),
+            KEY_ZERO => return Some(Decimal::ZERO),
+            _ => {}
+        }
+
+        let negative = bytes[0] < KEY_ZERO;
+        if negative {
+            for b in bytes.iter_mut() {
+                *b = !*b;
+            }
+        }
+
+        let mut result = Decimal {
+            sign: if negative { SIGN_NEG } else { SIGN_POS },
+            weight: bytes[0].wrapping_sub(KEY_POSITIVE) as i8,
+            ..Decimal::ZERO
+        };
+        let mut acc = 0u64;
+        let mut nbits = 0;
+        let mut bytes = bytes[1..].iter();
+        for limb in result.digits.iter_mut() {
+            while nbits < LIMB_BITS {
+                acc = acc << 8 | *bytes.next()? as u64;
+                nbits += 8;
+            }
+            nbits -= LIMB_BITS;
+            *limb = (acc >> nbits) as u32 & ((1 << LIMB_BITS) - 1);
+            if *limb >= NBASE {
+                return None;
+            }
+        }
+
+        result.ndigits = MAX_NDIGITS as u8;
+        while result.ndigits > 0 && result.digits[result.ndigits as usize - 1] == 0 {
+            result.ndigits -= 1;
+        }
+        result.dscale = result.min_scale().max(0) as u8;
+        Some(result)
+    }
+}
+
+impl Decimal {
+    /// Returns a compact key with the same order and equality as this decimal.
+    pub fn to_key(&self) -> DecimalKey {
+        let mut bytes = [0; KEY_LEN];
+        if self.is_nan() {
+            bytes[0] = KEY_NAN;
+            return DecimalKey(bytes);
+        }
+        if self.is_zero() {
+            bytes[0] = KEY_ZERO;
+            return DecimalKey(bytes);
+        }
+
+        bytes[0] = KEY_POSITIVE.wrapping_add(self.weight as u8);
+        let mut acc = 0u64;
+        let mut nbits = 0;
+        let mut i = 1;
+        for &limb in self.digits.iter() {
+            acc = acc << LIMB_BITS | limb as u64;
+            nbits += LIMB_BITS;
+            while nbits >= 8 {
+                nbits -= 8;
+                bytes[i] = (acc >> nbits) as u8;
+                i += 1;
+            }
+        }
+        if nbits > 0 {
+            bytes[i] = (acc << (8 - nbits)) as u8;
+        }
+
+        if self.is_sign_negative() {
+            for b in bytes.iter_mut() {
+                *b = !*b;
+            }
+        }
+        DecimalKey(bytes)
+    }
+
+    /// Returns bytes whose byte-wise order is [`total_cmp`](Decimal::total_cmp), to sort
+    /// decimals of any scale and NaNs with a plain byte comparison.
+    ///
+    /// The first 20 bytes are those of [`to_key`](Decimal::to_key) for numbers, and the last
+    /// byte orders equal numbers by scale. NaNs sort last, signaling before quiet, then by
+    /// payload.
+    pub fn sort_key_total(&self) -> [u8; KEY_LEN + 1] {
+        let mut bytes = [0; KEY_LEN + 1];
+        if self.is_nan() {
+            bytes[0] = KEY_NAN;
+            bytes[1] = !self.is_signaling_nan() as u8;
+            bytes[2..18].copy_from_slice(&self.int_abs().to_be_bytes());
+            return bytes;
+        }
+
+        bytes[..KEY_LEN].copy_from_slice(&self.to_key().to_bytes());
+        // Larger scales sort first, and negative keys are inverted as a whole.
+        bytes[KEY_LEN] = if self.is_sign_negative() {
+            self.dscale
+        } else {
+            !self.dscale
+        };
+        bytes
+    }
+
+    /// Returns the canonical decimal of a key.
+    #[inline]
+    pub fn from_key(key: &DecimalKey) -> Decimal {
+        // Keys can only be created from valid decimals.
+        key.decode().unwrap()
+    }
+
+    /// Returns a variable-length key with the same order and equality as this decimal, as
+    /// [`to_key`](Decimal::to_key) but shorter for values with few digits.
+    ///
+    /// Zero and NaN take 1 byte, other values a header byte and one byte per pair of digits, so
+    /// integers below 100 take 2 bytes and the longest keys [`MAX_COMPACT_KEY_LEN`]. The key
+    /// ends itself, so it can be followed by other columns in a composite key and still compare
+    /// byte-wise as the decimal. The scale is not kept.
+    pub fn to_compact_key(&self) -> Vec<u8> {
+        let mut key = Vec::with_capacity(MAX_COMPACT_KEY_LEN);
+        self.write_compact_key(&mut key);
+        key
+    }
+
+    /// Appends the key of [`to_compact_key`](Decimal::to_compact_key) to `buf`.
+    pub fn write_compact_key(&self, buf: &mut Vec<u8>) {
+        if self.is_nan() {
+            buf.push(KEY_NAN);
+            return;
+        }
+        if self.is_zero() {
+            buf.push(KEY_ZERO);
+            return;
+        }
+
+        // Pairs of digits in base 100, aligned so the first pair has an even decimal exponent. The
+        // trailing zeros of the last limb are dropped below.
+        let mut digits = [0u8; MAX_NDIGITS * DEC_DIGITS as usize + 2];
+        let mut len = 0;
+        let mut exponent = self.exponent();
+        if exponent % 2 != 0 {
+            len = 1;
+            exponent += 1;
+        }
+        for d in self.decimal_digits() {
+            digits[len] = d;
+            len += 1;
+        }
+        while digits[len - 1] == 0 {
+            len -= 1;
+        }
+
+        let mask = if self.is_sign_negative() { 0xff } else { 0 };
+        let start = buf.len();
+        buf.push(COMPACT_POSITIVE.wrapping_add((exponent / 2) as u8) ^ mask);
+        for pair in digits[..len + len % 2].chunks_exact(2) {
+            // The low bit is set on every pair but the last.
+            buf.push((pair[0] * 10 + pair[1]) << 1 | 1);
+        }
+        *buf.last_mut().unwrap() &= !1;
+        for b in buf[start + 1..].iter_mut() {
+            *b ^= mask;
+        }
+    }
+
+    /// Decodes a key written by [`to_compact_key`](Decimal::to_compact_key) at the start of
+    /// `bytes`, returning the canonical decimal and the length of the key.
+    ///
+    /// Returns [`DecimalDecodeError::UnexpectedEof`] if `bytes` ends within the key, and
+    /// [`DecimalDecodeError::Invalid`] if it isn't the key of a decimal.
+    pub fn from_compact_key(bytes: &[u8]) -> Result<(Decimal, usize), DecimalDecodeError> {
+        let header = *bytes.first().ok_or(DecimalDecodeError::UnexpectedEof)?;
+        match header {
+            KEY_NAN => return Ok((Decimal::NAN, 1)),
+            KEY_ZERO => return Ok((Decimal::ZERO, 1)),
+            _ => {}
+        }
+
+        let negative = header < KEY_ZERO;
+        let mask = if negative { 0xff } else { 0 };
+        let mut digits = [0u8; MAX_PRECISION as usize + 2];
+        let mut len = 0;
+        loop {
+            let b = *bytes.get(len / 2 + 1).ok_or(DecimalDecodeError::UnexpectedEof)? ^ mask;
+            let pair = b >> 1;
+            if pair >= 100 || len == digits.len() {
+                return Err(DecimalDecodeError::Invalid);
+            }
+            digits[len] = pair / 10;
+            digits[len + 1] = pair % 10;
+            len += 2;
+            if b & 1 == 0 {
+                break;
+            }
+        }
+        let key_len = len / 2 + 1;
+
+        // The first digit has the weight `10^(2 * exponent - 1)`.
+        let exponent = (header ^ mask).wrapping_sub(COMPACT_POSITIVE) as i8 as i32;
+        let mut exp = 2 * exponent - len as i32;
+        let leading = digits[..len].iter().take_while(|&&d| d == 0).count();
+        let mut digits = &digits[leading..len];
+        while let Some((&0, rest)) = digits.split_last() {
+            digits = rest;
+            exp += 1;
+        }
+        if digits.is_empty()
+            || digits.len() > MAX_PRECISION as usize
+            || exp + digits.len() as i32 > MAX_INT_DIGITS
+            || exp < -MAX_SCALE
+        {
+            return Err(DecimalDecodeError::Invalid);
+        }
+
+        let result = Decimal::from_decimal_digits(negative, digits, exp, (-exp).max(0));
+        let mut key = Vec::with_capacity(MAX_COMPACT_KEY_LEN);
+        result.write_compact_key(&mut key);
+        if key[..] != bytes[..key_len] {
+            return Err(DecimalDecodeError::Invalid);
+        }
+        Ok((result, key_len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decimal, DecimalDecodeError, MAX_COMPACT_KEY_LEN};
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn sort_key_total_matches_total_cmp() {
+        let values: Vec<Decimal> = [
+            "NaN",
+            "sNaN",
+            "NaN2",
+            "sNaN7",
+            "NaN10",
+            "1",
+            "1.0",
+            "1.00",
+            "-1",
+            "-1.0",
+            "-1.00",
+            "0",
+            "0.000",
+            "-0.5",
+            "0.5",
+            "0.50",
+            "1e-130",
+            "-1e-130",
+            "999999999999999999999999999999999999",
+            "-999999999999999999999999999999999999",
+            "123456789.123456789",
+            "123456789.1234567890",
+            "1000000000",
+            "999999999",
+        ]
+        .iter()
+        .map(|s| dec(s))
+        .collect();
+
+        let mut by_cmp = values.clone();
+        by_cmp.sort_by(Decimal::total_cmp);
+        let mut by_key = values;
+        by_key.sort_by_key(Decimal::sort_key_total);
+
+        // Displayed strings keep the scale and NaN payload that equality ignores.
+        let by_cmp: Vec<String> = by_cmp.iter().map(Decimal::to_string).collect();
+        let by_key: Vec<String> = by_key.iter().map(Decimal::to_string).collect();
+        assert_eq!(by_cmp, by_key);
+
+        let position = |s: &str| by_key.iter().position(|d| d == s).unwrap();
+        assert!(position("1.00") < position("1.0") && position("1.0") < position("1"));
+        assert!(position("-1") < position("-1.0") && position("-1.0") < position("-1.00"));
+        assert!(position("999999999999999999999999999999999999") < position("sNaN"));
+        assert!(position("sNaN") < position("sNaN7") && position("sNaN7") < position("NaN"));
+        assert!(position("NaN") < position("NaN2") && position("NaN2") < position("NaN10"));
+    }
+
+    const COMPACT: [&str; 22] = [
+        "0",
+        "0.00",
+        "1",
+        "1.000",
+        "-1",
+        "99",
+        "100",
+        "-100",
+        "0.5",
+        "-0.5",
+        "0.05",
+        "-0.05",
+        "123.456",
+        "-123.456",
+        "1e-130",
+        "-1e-130",
+        "123456789012345678901234567890123456",
+        "12345678901234567890123456789012345.6",
+        "-12345678901234567890123456789012345.6",
+        "999999999999999999999999999999999999",
+        "-999999999999999999999999999999999999",
+        "NaN",
+    ];
+
+    #[test]
+    fn compact_key_roundtrip() {
+        for s in COMPACT {
+            let d = dec(s);
+            let key = d.to_compact_key();
+            assert!(key.len() <= MAX_COMPACT_KEY_LEN, "{}", s);
+            let (decoded, len) = Decimal::from_compact_key(&key).unwrap();
+            assert_eq!(len, key.len());
+            assert_eq!(decoded.to_string(), d.normalize().to_string(), "{}", s);
+
+            // A key ends itself, so trailing columns are left alone.
+            let mut composite = key.clone();
+            composite.extend_from_slice(&[0x00, 0xff]);
+            assert_eq!(Decimal::from_compact_key(&composite).unwrap().1, key.len());
+        }
+        assert_eq!(
+            dec("12345678901234567890123456789012345.6").to_compact_key().len(),
+            MAX_COMPACT_KEY_LEN
+        );
+        assert_eq!(
+            dec("-12345678901234567890123456789012345.6").to_compact_key().len(),
+            MAX_COMPACT_KEY_LEN
+        );
+    }
+
+    #[test]
+    fn compact_key_order() {
+        for a in COMPACT {
+            for b in COMPACT {
+                let (x, y) = (dec(a), dec(b));
+                assert_eq!(x.to_compact_key().cmp(&y.to_compact_key()), x.cmp(&y), "{} vs {}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn compact_key_truncated() {
+        for s in COMPACT {
+            let key = dec(s).to_compact_key();
+            for len in 0..key.len() {
+                assert_eq!(
+                    Decimal::from_compact_key(&key[..len]),
+                    Err(DecimalDecodeError::UnexpectedEof),
+                    "{}",
+                    s
+                );
+            }
+        }
+    }
+}
@@ -1 +1,108 @@
 //! Fast high precision decimal.
+
+/// Adds `n` to a counter of [`bench_internals`], or does nothing without the feature.
+#[cfg(feature = "bench-internals")]
+macro_rules! bench_count {
+    ($counter:ident, $n:expr) => {
+        crate::bench_internals::$counter.fetch_add($n as u64, std::sync::atomic::Ordering::Relaxed);
+    };
+}
+
+#[cfg(not(feature = "bench-internals"))]
+macro_rules! bench_count {
+    ($counter:ident, $n:expr) => {};
+}
+
+/// Calls a recording function of [`precision_metrics`] for an operation, or does nothing
+/// without the feature.
+#[cfg(feature = "precision-metrics")]
+macro_rules! record_loss {
+    ($f:ident($operation:ident $(, $arg:expr)*)) => {
+        crate::precision_metrics::$f(crate::precision_metrics::Operation::$operation $(, $arg)*)
+    };
+}
+
+#[cfg(not(feature = "precision-metrics"))]
+macro_rules! record_loss {
+    ($f:ident($operation:ident $(, $arg:expr)*)) => {};
+}
+
+mod aggregate;
+mod audit;
+mod balance;
+pub mod batch;
+#[cfg(feature = "bench-internals")]
+pub mod bench_internals;
+mod bid;
+mod classify;
+mod cmp;
+mod column;
+pub mod compat;
+pub mod consts;
+mod context;
+mod convert;
+#[cfg(feature = "currency")]
+mod currency;
+mod decimal;
+mod dictionary;
+mod digits;
+mod encoding;
+mod env;
+mod error;
+mod excel;
+mod fix;
+mod fmt;
+pub mod json;
+mod key;
+mod math;
+mod ops;
+mod options;
+mod parse;
+mod percent;
+#[cfg(feature = "precision-metrics")]
+pub mod precision_metrics;
+mod round;
+#[cfg(feature = "test_support")]
+pub mod roundtrip;
+mod scaled;
+mod schema;
+mod scratch;
+mod sign;
+pub mod stats;
+mod suggest;
+#[cfg(feature = "test_support")]
+pub mod test_support;
+mod tick;
+mod trig;
+mod validate;
+mod var;
+
+pub use crate::aggregate::{mean_with_scale, sum_exact, weighted_mean, Accumulator, GroupedSum};
+pub use crate::audit::AuditedDecimal;
+pub use crate::balance::{Balance, RoundingAdjustment};
+pub use crate::bid::{Decimal128, Decimal32, Decimal64};
+pub use crate::classify::DecimalCategory;
+pub use crate::column::ColumnWriter;
+pub use crate::context::{with_context, Context, Status};
+#[cfg(feature = "currency")]
+pub use crate::currency::currency_scale;
+pub use crate::decimal::{Decimal, MAX_PRECISION, MAX_SCALE};
+pub use crate::dictionary::DecimalDictionary;
+pub use crate::digits::digit_histogram;
+pub use crate::encoding::ENCODING_VERSION;
+#[cfg(feature = "currency")]
+pub use crate::error::CurrencyError;
+pub use crate::error::{
+    DecimalConvertError, DecimalDecodeError, DecimalError, DecimalParseError, EnvDecimalError, SuggestedParseError,
+};
+pub use crate::key::{DecimalKey, OrdDecimal, MAX_COMPACT_KEY_LEN};
+pub use crate::options::{FormatOptions, ParseOptions, SignDisplay};
+pub use crate::parse::ParsedLayout;
+pub use crate::percent::Percent;
+pub use crate::round::RoundingStrategy;
+pub use crate::scaled::ScaledI128;
+pub use crate::schema::DecimalSchema;
+#[cfg(feature = "scratch")]
+pub use crate::scratch::with_scratch;
+pub use crate::sign::Sign;
+pub use crate::validate::{validate_slice, ValidationError, ValidationErrorKind, ValidationOptions, ValidationReport};
@@ -0,0 +1,870 @@
+//! Powers and other mathematical functions.
+//!
+//! Intermediate results are kept in working variables rounded to `WORK_DIGITS` significant
+//! digits, well beyond `MAX_PRECISION`, and rounded once more to fit in a decimal.
+
+use crate::decimal::{Decimal, DEC_DIGITS, MAX_INT_DIGITS, MAX_PRECISION, MAX_SCALE, POWERS_10};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+
+/// Significant digits kept in intermediate results.
+pub(crate) const WORK_DIGITS: i32 = 72;
+
+/// Intermediate results beyond `10^±EXPONENT_LIMIT` can only overflow or round to zero.
+const EXPONENT_LIMIT: i32 = 4 * MAX_SCALE;
+
+/// Limbs of `ln(2)` to 99 digits after the decimal point, with weight -1.
+const LN_2: [u32; 11] = [
+    693_147_180,
+    559_945_309,
+    417_232_121,
+    458_176_568,
+    75_500_134,
+    360_255_254,
+    120_680_009,
+    493_393_621,
+    969_694_715,
+    605_863_326,
+    996_418_687,
+];
+
+/// Limbs of `ln(10)` to 99 digits after the decimal point, with weight 0.
+const LN_10: [u32; 12] = [
+    2,
+    302_585_092,
+    994_045_684,
+    17_991_454,
+    684_364_207,
+    601_101_488,
+    628_772_976,
+    33_327_900,
+    967_572_609,
+    677_352_480,
+    235_997_205,
+    89_598_298,
+];
+
+/// Digits after the decimal point kept in terms of series that converge to values near 1.
+const SERIES_SCALE: i32 = WORK_DIGITS + 12;
+
+/// Arguments of `exp` with a magnitude of `10^EXP_LIMIT` or more overflow or round to zero.
+const EXP_LIMIT: i32 = 3;
+
+/// Significant digits of a root approximated in `WORK_DIGITS` digits that are certainly correct.
+const ROOT_CHECK_DIGITS: i32 = WORK_DIGITS - 12;
+
+/// Roots with a larger index are exact only for 1: the `n`th power of any other root has more
+/// than `MAX_SCALE` digits after the decimal point or is at least `2^n > 10^MAX_INT_DIGITS`.
+const ROOT_INDEX_LIMIT: u32 = 4 * MAX_INT_DIGITS as u32;
+
+/// Rounds to `WORK_DIGITS` significant digits keeping the display scale, returns whether the
+/// result is inexact.
+#[inline]
+pub(crate) fn round_to_work(var: &mut Var) -> bool {
+    if var.is_zero() {
+        return false;
+    }
+    let dscale = var.dscale;
+    let inexact = var.round(WORK_DIGITS - var.exponent(), RoundingStrategy::HalfEven, false);
+    var.dscale = dscale;
+    inexact
+}
+
+/// Multiplies, rounding to `WORK_DIGITS` significant digits.
+#[inline]
+pub(crate) fn mul_work(a: &Var, b: &Var) -> Var {
+    let mut product = a.mul(b);
+    round_to_work(&mut product);
+    product
+}
+
+/// Divides, truncating to at least `WORK_DIGITS` significant digits. `b` must be non-zero.
+#[inline]
+pub(crate) fn div_work(a: &Var, b: &Var) -> Var {
+    if a.is_zero() {
+        return Var::ZERO;
+    }
+    let (quotient, _) = a.div(b, WORK_DIGITS + 1 - a.exponent() + b.exponent());
+    quotient
+}
+
+/// Computes `e^x` for `|x| < 10^EXP_LIMIT`.
+///
+/// `x` is reduced to `r = x - k * ln(2)`, `e^(r / 256)` is summed as a Taylor series and squared
+/// 8 times, and the result is scaled by `2^k`.
+pub(crate) fn exp_var(x: &Var) -> Var {
+    debug_assert!(x.is_zero() || x.exponent() <= EXP_LIMIT);
+    let one = Var::from_limb(1, 0);
+    if x.is_zero() {
+        return one;
+    }
+
+    let ln_2 = Var::from_limbs(&LN_2, -1);
+    let (k, _) = x.div_round(&ln_2, 0, RoundingStrategy::HalfEven);
+    let r = x.sub(&k.mul(&ln_2));
+    let (r, _) = r.div(&Var::from_limb(256, 0), SERIES_SCALE);
+
+    let mut sum = one;
+    let mut term = one;
+    for n in 1.. {
+        term = term.mul(&r).div(&Var::from_limb(n, 0), SERIES_SCALE).0;
+        if term.is_zero() {
+            break;
+        }
+        sum = sum.add(&term);
+    }
+    for _ in 0..8 {
+        sum = mul_work(&sum, &sum);
+    }
+
+    let k = k.into_i128().unwrap() as i64;
+    if k == 0 {
+        return sum;
+    }
+    let power = match pow_var(&Var::from_limb(2, 0), k.unsigned_abs()) {
+        Power::Finite(power, _) => power,
+        _ => unreachable!(),
+    };
+    if k > 0 {
+        mul_work(&sum, &power)
+    } else {
+        div_work(&sum, &power)
+    }
+}
+
+/// Computes `ln(x)` for a positive `x`.
+///
+/// `x` is reduced to `m = x / (10^e * 2^-j)` in `[0.5, 2]`, and `ln(m)` is summed as the series
+/// of `2 * atanh((m - 1) / (m + 1))`. `x` already in `[0.5, 2]` is not reduced, so that results
+/// near 0 keep their relative precision.
+pub(crate) fn ln_var(x: &Var) -> Var {
+    debug_assert!(!x.is_zero() && !x.is_negative());
+    let one = Var::from_limb(1, 0);
+    let two = Var::from_limb(2, 0);
+    let half = Var::from_limb(500_000_000, -1);
+
+    let mut m = *x;
+    let mut e = 0;
+    let mut j = 0;
+    if m.cmp_abs(&half).is_lt() || m.cmp_abs(&two).is_gt() {
+        e = x.exponent();
+        let shift = -e;
+        m = m.mul(&Var::from_limb(
+            POWERS_10[shift.rem_euclid(DEC_DIGITS) as usize],
+            shift.div_euclid(DEC_DIGITS),
+        ));
+        while m.cmp_abs(&half).is_lt() {
+            m = m.mul(&two);
+            j += 1;
+        }
+    }
+
+    let z = div_work(&m.sub(&one), &m.add(&one));
+    let mut sum = Var::ZERO;
+    if !z.is_zero() {
+        let z2 = mul_work(&z, &z);
+        let limit = z.exponent() - WORK_DIGITS - 2;
+        let mut power = z;
+        sum = z;
+        for n in (3..).step_by(2) {
+            power = mul_work(&power, &z2);
+            let term = div_work(&power, &Var::from_limb(n, 0));
+            if term.is_zero() || term.exponent() < limit {
+                break;
+            }
+            sum = sum.add(&term);
+        }
+        sum = sum.mul(&two);
+    }
+
+    if e != 0 {
+        sum = sum.add(&Var::from_i64(e as i64).mul(&Var::from_limbs(&LN_10, 0)));
+    }
+    if j != 0 {
+        sum = sum.sub(&Var::from_i64(j).mul(&Var::from_limbs(&LN_2, -1)));
+    }
+    round_to_work(&mut sum);
+    sum
+}
+
+/// Computes the square root of a positive `x`.
+#[inline]
+pub(crate) fn sqrt_var(x: &Var) -> Var {
+    exp_var(&ln_var(x).mul(&Var::from_limb(500_000_000, -1)))
+}
+
+/// Converts a result of a function with as many digits after the decimal point as fit,
+/// returning `None` if it overflows.
+#[inline]
+pub(crate) fn into_full_decimal(mut var: Var) -> Option<Decimal> {
+    var.dscale = MAX_SCALE;
+    var.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d)
+}
+
+/// Result of raising a variable to an integer power.
+enum Power {
+    /// The power and whether it is inexact.
+    Finite(Var, bool),
+    /// The magnitude is beyond `10^EXPONENT_LIMIT`.
+    Huge,
+    /// The magnitude is below `10^-EXPONENT_LIMIT`.
+    Tiny,
+}
+
+/// Raises a non-zero `base` to the power `exp` by left-to-right binary exponentiation.
+fn pow_var(base: &Var, exp: u64) -> Power {
+    debug_assert!(!base.is_zero() && exp > 0);
+    let mut result = *base;
+    let mut inexact = false;
+    for i in (0..63 - exp.leading_zeros()).rev() {
+        result = result.mul(&result);
+        inexact |= round_to_work(&mut result);
+        if exp >> i & 1 == 1 {
+            result = result.mul(base);
+            inexact |= round_to_work(&mut result);
+        }
+        // The magnitude only moves further from 1 in the remaining steps.
+        if result.exponent() > EXPONENT_LIMIT {
+            return Power::Huge;
+        }
+        if result.exponent() < -EXPONENT_LIMIT {
+            return Power::Tiny;
+        }
+    }
+    Power::Finite(result, inexact)
+}
+
+/// Returns lower and upper bounds of `base^exp` for a positive `base`, with the full capacity
+/// of a variable, so they are within about `exp * 10^-270` of each other relatively.
+fn pow_bounds(base: &Var, exp: u64) -> (Var, Var) {
+    debug_assert!(!base.is_zero() && !base.is_negative() && exp > 0);
+    // The display scale is irrelevant to the bounds and would grow with the exponent.
+    let mut lower = Var { dscale: 0, ..*base };
+    let mut upper = lower;
+    for i in (0..63 - exp.leading_zeros()).rev() {
+        lower = lower.mul(&lower);
+        upper = upper.mul_up(&upper);
+        if exp >> i & 1 == 1 {
+            lower = lower.mul(base);
+            upper = upper.mul_up(base);
+        }
+    }
+    (lower, upper)
+}
+
+/// Rounds the `n`th root of a positive `x` correctly, given its approximation `root` and
+/// `rounded`, the approximation rounded half up to as many digits as fit.
+///
+/// `rounded` is wrong only if the root and its approximation are on either side of the midpoint
+/// between `rounded` and its neighbor. If the approximation is within `10^-ROOT_CHECK_DIGITS`
+/// of that midpoint relatively, the midpoint is raised to the `n`th power and compared with
+/// `x`, which decides every root not within about `10^-260` of the midpoint.
+fn round_root(x: &Var, n: u32, root: &Var, rounded: Decimal) -> Decimal {
+    let q = Var::from_decimal(&rounded);
+    // A neighbor is `rounded` moved by less than a unit of the last place and rounded away.
+    let tiny = Var::from_limb(1, -(MAX_SCALE / DEC_DIGITS) - 2);
+    let above = root.cmp_abs(&q).is_ge();
+    let (mut neighbor, strategy) = if above {
+        (q.add(&tiny), RoundingStrategy::Up)
+    } else {
+        (q.sub(&tiny), RoundingStrategy::Down)
+    };
+    neighbor.dscale = MAX_SCALE;
+    let (neighbor, _) = neighbor.into_decimal(strategy).unwrap();
+
+    let midpoint = q
+        .add(&Var::from_decimal(&neighbor))
+        .mul(&Var::from_limb(500_000_000, -1));
+    let distance = root.sub(&midpoint);
+    if !distance.is_zero() && distance.exponent() > midpoint.exponent() - ROOT_CHECK_DIGITS {
+        return rounded;
+    }
+
+    // Halfway rounds up, and the root is at least the midpoint if `x` is its `n`th power.
+    let (lower, upper) = pow_bounds(&midpoint, n as u64);
+    let at_least_midpoint = if x.cmp_abs(&upper).is_ge() {
+        true
+    } else if x.cmp_abs(&lower).is_lt() {
+        false
+    } else {
+        !distance.is_negative()
+    };
+    if above == at_least_midpoint {
+        neighbor
+    } else {
+        rounded
+    }
+}
+
+impl Decimal {
+    /// Raises to an integer power, returning `None` instead of panicking on overflow or if
+    /// zero is raised to a negative power.
+    ///
+    /// See [`powi`](Decimal::powi) for the result scale and rounding.
+    pub fn checked_powi(&self, exp: i64) -> Option<Decimal> {
+        let base = Var::from_decimal(self);
+        if base.is_nan() {
+            return base.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d);
+        }
+        if exp == 0 {
+            return Some(Decimal::from(1));
+        }
+        if base.is_zero() {
+            if exp < 0 {
+                return None;
+            }
+            let scale = (self.scale() as i64).saturating_mul(exp).min(MAX_SCALE as i64);
+            return Some(Decimal::from_coefficient(false, 0, scale as i32));
+        }
+
+        if exp > 0 {
+            let mut power = match pow_var(&base, exp as u64) {
+                Power::Finite(power, _) => power,
+                Power::Huge => return None,
+                Power::Tiny => Var {
+                    sign: base.sign,
+                    ..Var::ZERO
+                },
+            };
+            power.dscale = (self.scale() as i64).saturating_mul(exp).min(MAX_SCALE as i64) as i32;
+            return power.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d);
+        }
+
+        let (power, inexact) = match pow_var(&base, exp.unsigned_abs()) {
+            Power::Finite(power, inexact) => (power, inexact),
+            Power::Huge => return Some(Decimal::from_coefficient(false, 0, MAX_SCALE)),
+            Power::Tiny => return None,
+        };
+        let rscale = (WORK_DIGITS + power.exponent()).min(MAX_SCALE + 1);
+        let (mut reciprocal, sticky) = Var::from_limb(1, 0).div(&power, rscale);
+        let scale = if reciprocal.is_zero() {
+            MAX_SCALE
+        } else {
+            MAX_SCALE.min(MAX_PRECISION as i32 - reciprocal.exponent())
+        };
+        let rounded = reciprocal.round(scale, RoundingStrategy::HalfUp, sticky);
+        let (mut result, _) = reciprocal.into_decimal(RoundingStrategy::HalfUp)?;
+        if !inexact && !rounded && !result.is_zero() {
+            // An exact reciprocal keeps only the digits it needs, as division does.
+            result.dscale = result.min_scale() as u8;
+        }
+        Some(result)
+    }
+
+    /// Raises to an integer power by exponentiation by squaring.
+    ///
+    /// A positive power has the scale `self.scale() * exp`, as repeated multiplication, and a
+    /// negative power is the reciprocal of the positive power with as many digits as fit, or
+    /// just the digits it needs if it is exact. Intermediate results keep 72 significant
+    /// digits, and the result is rounded half up to `MAX_PRECISION` digits. Any decimal raised
+    /// to 0 is 1, and NaN gives NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows or zero is raised to a negative power.
+    #[inline]
+    pub fn powi(&self, exp: i64) -> Decimal {
+        assert!(self.is_nan() || exp >= 0 || !self.is_zero(), "division by zero");
+        self.checked_powi(exp)
+            .expect("overflow when raising a decimal to a power")
+    }
+
+    /// Raises to a decimal power, returning `None` instead of panicking on overflow or if zero
+    /// is raised to a negative power.
+    ///
+    /// See [`powd`](Decimal::powd) for the result scale and rounding.
+    pub fn checked_powd(&self, exp: &Decimal) -> Option<Decimal> {
+        let base = Var::from_decimal(self);
+        let y = Var::from_decimal(exp);
+        if let Some(nan) = base.nan_operand(&y) {
+            return nan.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d);
+        }
+
+        if exp.fract().is_zero() {
+            let n = match Var::from_decimal(&exp.trunc()).into_i128() {
+                Some(n) if n >= i64::MIN as i128 && n <= i64::MAX as i128 => n as i64,
+                // Only the parity of an out-of-range exponent matters.
+                _ => {
+                    let odd = !exp.trunc().checked_rem(&Decimal::from(2)).unwrap().is_zero();
+                    match (exp.is_sign_negative(), odd) {
+                        (false, true) => i64::MAX,
+                        (false, false) => i64::MAX - 1,
+                        (true, true) => i64::MIN + 1,
+                        (true, false) => i64::MIN,
+                    }
+                }
+            };
+            return self.checked_powi(n);
+        }
+
+        if base.is_zero() {
+            if exp.is_sign_negative() {
+                return None;
+            }
+            return Some(Decimal::ZERO);
+        }
+        if base.is_negative() {
+            // The power of a negative number to a fractional exponent is not real.
+            return Some(Decimal::NAN);
+        }
+
+        let t = mul_work(&y, &ln_var(&base));
+        if !t.is_zero() && t.exponent() > EXP_LIMIT {
+            return if t.is_negative() {
+                Some(Decimal::from_coefficient(false, 0, MAX_SCALE))
+            } else {
+                None
+            };
+        }
+        into_full_decimal(exp_var(&t))
+    }
+
+    /// Raises to a decimal power, e.g. `1.05.powd(0.25)` for a quarterly growth rate.
+    ///
+    /// Integral exponents are computed as [`powi`](Decimal::powi). Other powers are computed as
+    /// `e^(exp * ln(self))` with 72 significant digits and rounded half up to `MAX_PRECISION`
+    /// digits, with as many digits after the decimal point as fit. A negative number raised to
+    /// a fractional exponent gives NaN, as do NaN operands.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows or zero is raised to a negative power.
+    #[inline]
+    pub fn powd(&self, exp: &Decimal) -> Decimal {
+        assert!(
+            self.is_nan() || exp.is_nan() || !exp.is_sign_negative() || !self.is_zero(),
+            "division by zero"
+        );
+        self.checked_powd(exp)
+            .expect("overflow when raising a decimal to a power")
+    }
+
+    /// Raises to a power given as `f64`, returning `None` instead of panicking on overflow or
+    /// if zero is raised to a negative power.
+    ///
+    /// See [`powf`](Decimal::powf) for how the exponent is converted.
+    pub fn checked_powf(&self, exp: f64) -> Option<Decimal> {
+        if !exp.is_finite() {
+            return Some(Decimal::NAN);
+        }
+        match format!("{:e}", exp).parse::<Decimal>() {
+            Ok(exp) => self.checked_powd(&exp),
+            // An exponent beyond the range of decimals overflows unless the base is 1.
+            Err(_) if self.abs() == Decimal::from(1) => Some(Decimal::from(1)),
+            Err(_) => None,
+        }
+    }
+
+    /// Raises to a power given as `f64`, as [`powd`](Decimal::powd).
+    ///
+    /// The exponent is converted to the shortest decimal that converts back to it, so `0.1f64`
+    /// is `0.1`, and exponents too small for a decimal are taken as 0. A non-finite exponent
+    /// gives NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows or zero is raised to a negative power.
+    #[inline]
+    pub fn powf(&self, exp: f64) -> Decimal {
+        assert!(
+            self.is_nan() || !exp.is_sign_negative() || !self.is_zero(),
+            "division by zero"
+        );
+        self.checked_powf(exp)
+            .expect("overflow when raising a decimal to a power")
+    }
+
+    /// Returns `e^self`, or `None` instead of panicking if it overflows.
+    ///
+    /// See [`exp`](Decimal::exp) for the result scale and rounding.
+    pub fn checked_exp(&self) -> Option<Decimal> {
+        let x = Var::from_decimal(self);
+        if x.is_nan() {
+            return Some(Decimal::NAN);
+        }
+        if x.is_zero() {
+            return Some(Decimal::from(1));
+        }
+        if x.exponent() > EXP_LIMIT {
+            return if x.is_negative() {
+                Some(Decimal::from_coefficient(false, 0, MAX_SCALE))
+            } else {
+                None
+            };
+        }
+        into_full_decimal(exp_var(&x))
+    }
+
+    /// Returns `e^self`, the natural exponential.
+    ///
+    /// The result is computed with 72 significant digits and rounded half up to
+    /// `MAX_PRECISION` digits, with as many digits after the decimal point as fit. `e^0` is
+    /// exactly 1, and results below `10^-MAX_SCALE` round to zero. NaN gives NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows, which happens for `self` above about `82.89`.
+    #[inline]
+    pub fn exp(&self) -> Decimal {
+        self.checked_exp().expect("overflow when computing an exponential")
+    }
+
+    /// Returns the natural logarithm, or `None` instead of panicking if `self` is zero.
+    ///
+    /// See [`ln`](Decimal::ln) for the result scale and rounding.
+    pub fn checked_ln(&self) -> Option<Decimal> {
+        let x = Var::from_decimal(self);
+        if x.is_nan() || x.is_negative() {
+            return Some(Decimal::NAN);
+        }
+        if x.is_zero() {
+            return None;
+        }
+        if *self == Decimal::from(1) {
+            return Some(Decimal::ZERO);
+        }
+        into_full_decimal(ln_var(&x))
+    }
+
+    /// Returns the natural logarithm.
+    ///
+    /// The result is computed with 72 significant digits and rounded half up to
+    /// `MAX_PRECISION` digits, with as many digits after the decimal point as fit. `ln(1)` is
+    /// exactly 0. Negative numbers and NaN give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero, whose logarithm is negative infinity.
+    #[inline]
+    pub fn ln(&self) -> Decimal {
+        self.checked_ln().expect("logarithm of zero")
+    }
+
+    /// Returns the logarithm to `base`, or `None` instead of panicking if `self` is zero.
+    ///
+    /// See [`log`](Decimal::log) for the result scale and rounding.
+    pub fn checked_log(&self, base: &Decimal) -> Option<Decimal> {
+        let x = Var::from_decimal(self);
+        let b = Var::from_decimal(base);
+        if x.nan_operand(&b).is_some() || x.is_negative() || b.is_negative() || b.is_zero() {
+            return Some(Decimal::NAN);
+        }
+        if *base == Decimal::from(1) {
+            return Some(Decimal::NAN);
+        }
+        if x.is_zero() {
+            return None;
+        }
+        if *self == Decimal::from(1) {
+            return Some(Decimal::ZERO);
+        }
+
+        let result = into_full_decimal(div_work(&ln_var(&x), &ln_var(&b)))?;
+        // An integral logarithm is exact when the base raised to it gives `self` back.
+        let n = result.with_scale(0).ok()?;
+        if let Some(n) = Var::from_decimal(&n).into_i128() {
+            if n.abs() <= EXPONENT_LIMIT as i128 && base.checked_powi(n as i64) == Some(*self) {
+                return Some(Decimal::from(n as i64));
+            }
+        }
+        Some(result)
+    }
+
+    /// Returns the logarithm to `base`, e.g. `8.log(2)` is 3.
+    ///
+    /// The result is computed as `ln(self) / ln(base)` with 72 significant digits and rounded
+    /// half up to `MAX_PRECISION` digits, with as many digits after the decimal point as fit.
+    /// An integral logarithm, where `base` raised to it gives `self`, is exact with scale 0.
+    /// Negative numbers, a base that is not positive or is 1, and NaN give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero, whose logarithm is infinite.
+    #[inline]
+    pub fn log(&self, base: &Decimal) -> Decimal {
+        self.checked_log(base).expect("logarithm of zero")
+    }
+
+    /// Returns the base 10 logarithm, as [`log`](Decimal::log).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero.
+    #[inline]
+    pub fn log10(&self) -> Decimal {
+        self.log(&Decimal::from(10))
+    }
+
+    /// Returns the base 2 logarithm, as [`log`](Decimal::log).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `self` is zero.
+    #[inline]
+    pub fn log2(&self) -> Decimal {
+        self.log(&Decimal::from(2))
+    }
+
+    /// Returns the `n`th root.
+    ///
+    /// The root is correctly rounded half up to `MAX_PRECISION` digits, with as many digits
+    /// after the decimal point as fit. It is approximated as `e^(ln(|self|) / n)` with 72
+    /// significant digits, and if that is too close to halfway between two results, the
+    /// halfway point is raised to the `n`th power with 280 digits to tell on which side the
+    /// root is, which only leaves roots within about `10^-260` of halfway undecided. An exact
+    /// root, whose `n`th power gives `self` back, has only the digits it needs but at least
+    /// `self.scale() / n` digits after the decimal point rounded up, e.g. the cube root of
+    /// `8.000` is `2.0`. Negative numbers have a negative root when `n` is odd and NaN when it
+    /// is even. `n = 0` and NaN give NaN.
+    pub fn nth_root(&self, n: u32) -> Decimal {
+        let x = Var::from_decimal(self);
+        if x.is_nan() {
+            return x.into_decimal(RoundingStrategy::HalfUp).unwrap().0;
+        }
+        if n == 0 || (x.is_negative() && n % 2 == 0) {
+            return Decimal::NAN;
+        }
+        if n == 1 {
+            return *self;
+        }
+        let min_scale = ((self.scale() as i64 + n as i64 - 1) / n as i64) as i32;
+        if x.is_zero() {
+            return Decimal::from_coefficient(false, 0, min_scale);
+        }
+
+        // Roots are computed for the magnitude, as rounding half up is symmetric.
+        let magnitude = x.abs();
+        if n > ROOT_INDEX_LIMIT && magnitude.cmp_abs(&Var::from_i64(1)).is_eq() {
+            let one = Decimal::ONE.with_scale(min_scale).unwrap();
+            return if x.is_negative() { -one } else { one };
+        }
+        let root = exp_var(&div_work(&ln_var(&magnitude), &Var::from_i64(n as i64)));
+        // The root has a smaller magnitude than `self` beyond 1, so it can't overflow.
+        let rounded = into_full_decimal(root).unwrap();
+
+        let candidate = rounded.canonicalize();
+        let exact = n <= ROOT_INDEX_LIMIT
+            && matches!(
+                pow_var(&Var::from_decimal(&candidate), n as u64),
+                Power::Finite(power, false) if power.sub(&magnitude).is_zero()
+            );
+        let result = if exact {
+            candidate
+                .with_scale(candidate.scale().max(min_scale))
+                .unwrap_or(candidate)
+        } else {
+            round_root(&magnitude, n, &root, rounded)
+        };
+        if x.is_negative() {
+            -result
+        } else {
+            result
+        }
+    }
+
+    /// Returns the cube root, as [`nth_root`](Decimal::nth_root) with `n = 3`.
+    #[inline]
+    pub fn cbrt(&self) -> Decimal {
+        self.nth_root(3)
+    }
+
+    /// Returns `sqrt(self^2 + other^2)`, or `None` instead of panicking if it overflows.
+    ///
+    /// See [`hypot`](Decimal::hypot) for the result scale and rounding.
+    pub fn checked_hypot(&self, other: &Decimal) -> Option<Decimal> {
+        let a = Var::from_decimal(self);
+        let b = Var::from_decimal(other);
+        if let Some(nan) = a.nan_operand(&b) {
+            return nan.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d);
+        }
+        let scale = self.scale().max(other.scale());
+        // The squares are exact, so operands near `MAX` don't overflow.
+        let sum = a.mul(&a).add(&b.mul(&b));
+        if sum.is_zero() {
+            return Some(Decimal::from_coefficient(false, 0, scale));
+        }
+
+        let result = into_full_decimal(sqrt_var(&sum))?;
+        let candidate = result.canonicalize();
+        let root = Var::from_decimal(&candidate);
+        if root.mul(&root).sub(&sum).is_zero() {
+            // An exact result, e.g. `hypot(3, 4) = 5`, keeps the larger scale of the operands.
+            return Some(candidate.with_scale(candidate.scale().max(scale)).unwrap_or(candidate));
+        }
+        Some(result)
+    }
+
+    /// Returns the length of the hypotenuse of a right triangle with legs `self` and `other`,
+    /// `sqrt(self^2 + other^2)`, as `f64::hypot`.
+    ///
+    /// The squares are summed exactly, so there is no intermediate overflow, and the root is
+    /// computed with 72 significant digits and rounded half up to `MAX_PRECISION` digits, with
+    /// as many digits after the decimal point as fit. An exact result has only the digits it
+    /// needs but at least the larger scale of the operands. NaN operands give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows, which only happens for legs near `MAX`.
+    #[inline]
+    pub fn hypot(&self, other: &Decimal) -> Decimal {
+        self.checked_hypot(other).expect("overflow when computing a hypotenuse")
+    }
+
+    /// Returns the reciprocal `1 / self`, or `None` instead of panicking if `self` is zero or
+    /// the reciprocal overflows.
+    ///
+    /// See [`recip`](Decimal::recip) for the result scale and rounding.
+    pub fn checked_recip(&self, scale: Option<i32>) -> Option<Decimal> {
+        if self.is_zero() {
+            return None;
+        }
+        match scale {
+            Some(scale) => Decimal::ONE
+                .div_rounded(self, scale, RoundingStrategy::HalfUp)
+                .map(|(d, _)| d),
+            None => Decimal::ONE.div_full(self),
+        }
+    }
+
+    /// Returns the reciprocal `1 / self`, rounded half up to `scale` digits after the decimal
+    /// point, or with `scale = None` to as many digits as fit as [`Div`](std::ops::Div) does.
+    ///
+    /// The result is that of [`div_with_scale`](Decimal::div_with_scale) or
+    /// [`Div`](std::ops::Div) with a dividend of 1. A scale that would need more than
+    /// `MAX_PRECISION` digits is reduced, and an exact reciprocal with `scale = None` has only
+    /// the digits it needs but at least the scale of `self`. NaN and zero give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reciprocal overflows.
+    #[inline]
+    pub fn recip(&self, scale: Option<i32>) -> Decimal {
+        match scale {
+            Some(scale) => Decimal::ONE.div_with_scale(self, scale, RoundingStrategy::HalfUp),
+            None => Decimal::ONE / *self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::round_root;
+    use crate::var::Var;
+    use crate::{Decimal, RoundingStrategy};
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn recip_matches_division() {
+        let values = [
+            "1",
+            "3",
+            "-7",
+            "0.5",
+            "1.500",
+            "12345.6789",
+            "-0.000000000000000000000123",
+            "1e-10",
+            "999999999999999999999999999999999999",
+            "3.14159265358979323846",
+            "0.333333333333333333333333333333333333",
+            "7e20",
+            "-81.25",
+        ];
+        for s in values {
+            let x = dec(s);
+            let quotient = Decimal::ONE / x;
+            let recip = x.recip(None);
+            assert_eq!(recip.to_string(), quotient.to_string(), "1 / {}", s);
+            for scale in [-3, 0, 2, 18, 40, 130] {
+                let quotient = Decimal::ONE.div_with_scale(&x, scale, RoundingStrategy::HalfUp);
+                let recip = x.recip(Some(scale));
+                assert_eq!(recip.to_string(), quotient.to_string(), "1 / {} at scale {}", s, scale);
+            }
+        }
+        assert!(Decimal::NAN.recip(None).is_nan());
+    }
+
+    #[test]
+    fn exact_roots() {
+        assert_eq!(dec("27").cbrt().to_string(), "3");
+        assert_eq!(dec("8.000").cbrt().to_string(), "2.0");
+        assert_eq!(dec("-8").nth_root(3).to_string(), "-2");
+        assert_eq!(dec("0.0625").nth_root(4).to_string(), "0.5");
+        assert_eq!(dec("0.00").nth_root(2).to_string(), "0.0");
+        assert_eq!(dec("7.5").nth_root(1).to_string(), "7.5");
+    }
+
+    #[test]
+    fn nan_roots() {
+        assert!(dec("-4").nth_root(2).is_nan());
+        assert!(dec("-16").nth_root(4).is_nan());
+        assert!(dec("4").nth_root(0).is_nan());
+        assert!(Decimal::NAN.nth_root(3).is_nan());
+    }
+
+    #[test]
+    fn inexact_roots() {
+        // Correctly rounded reference values, with digits after the 36th closest to halfway among
+        // the integers below 6000.
+        for (x, n, root) in [
+            ("2", 2, "1.41421356237309504880168872420969808"),
+            ("3747", 2, "61.2127437712115629684551560101834341"),
+            ("5781", 3, "17.9473771717802957553550265163761110"),
+            ("2666", 5, "4.84364191790502058001269648556121364"),
+            ("2", 100, "1.00695555005671880883269821411323979"),
+            ("1234.56789", 7, "2.76468080169512386446165010379216620"),
+            ("-5781", 3, "-17.9473771717802957553550265163761110"),
+            // `10^17 + 5 * 10^-19 - 1.25 * 10^-54`, just below halfway.
+            (
+                "10000000000000000000000000000000000.1",
+                2,
+                "100000000000000000.000000000000000000",
+            ),
+        ] {
+            assert_eq!(dec(x).nth_root(n).to_string(), root, "{} {}", x, n);
+        }
+    }
+
+    #[test]
+    fn large_root_indices() {
+        // Correctly rounded reference values, with indices beyond the check of exact roots.
+        for (x, n, root) in [
+            ("2", u32::MAX, "1.00000000016138590424723534516955429"),
+            ("0.5", i32::MAX as u32, "0.999999999677228191508514371983406114"),
+            ("1e40", 1 << 30, "1.00000008577798276206837891397907640"),
+            ("1e-130", u32::MAX, "0.999999930305394407392938185048365387"),
+            ("1.000", u32::MAX, "1.0"),
+            ("-1", u32::MAX, "-1"),
+            ("0.0", u32::MAX, "0.0"),
+        ] {
+            assert_eq!(dec(x).nth_root(n).to_string(), root, "{} {}", x, n);
+        }
+        assert!(dec("-2").nth_root(u32::MAX - 1).is_nan());
+    }
+
+    #[test]
+    fn root_beside_halfway() {
+        let var = |s: &str| Var::from_decimal(&dec(s));
+
+        // The root of `10^34 + 0.1` is `10^-54` below the midpoint `10^17 + 5 * 10^-19`, so an
+        // approximation on the midpoint, rounded up, is corrected down.
+        let x = var("10000000000000000000000000000000000.1");
+        let approximation = var("100000000000000000").add(&var("0.0000000000000000005"));
+        let rounded = dec("100000000000000000.000000000000000001");
+        assert_eq!(
+            round_root(&x, 2, &approximation, rounded).to_string(),
+            "100000000000000000.000000000000000000"
+        );
+
+        // The root of `9 * 10^32 - 0.003` is also just below the midpoint `3 * 10^16 - 5 * 10^-20`,
+        // and an approximation just below it is kept.
+        let x = var("899999999999999999999999999999999.997");
+        let approximation = var("30000000000000000").sub(&var("0.00000000000000000005000000001"));
+        let rounded = dec("29999999999999999.9999999999999999999");
+        assert_eq!(round_root(&x, 2, &approximation, rounded), rounded);
+        assert_eq!(dec("899999999999999999999999999999999.997").nth_root(2), rounded);
+    }
+}
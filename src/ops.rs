@@ -0,0 +1,955 @@
+//! Decimal arithmetic.
+
+use crate::decimal::{Decimal, DEC_DIGITS, MAX_INT_DIGITS, MAX_NDIGITS, MAX_PRECISION, MAX_SCALE, SIGN_NEG, SIGN_POS};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign};
+
+impl Decimal {
+    /// Multiplies by `10^exp`, returning `None` if the result doesn't fit in a decimal.
+    ///
+    /// The digits are shifted exactly, so the scale shrinks or grows by `exp`. `None` is returned
+    /// instead of rounding when the scale would exceed `MAX_SCALE` or the shifted value would
+    /// need more than `MAX_PRECISION` digits.
+    pub fn checked_mul_pow10(&self, exp: i32) -> Option<Decimal> {
+        if self.is_nan() {
+            return Some(*self);
+        }
+
+        let dscale = (self.dscale as i64 - exp as i64).max(0);
+        if dscale > MAX_SCALE as i64 {
+            return None;
+        }
+        let dscale = dscale as i32;
+
+        if self.is_zero() {
+            return Some(Decimal {
+                dscale: dscale as u8,
+                ..Decimal::ZERO
+            });
+        }
+
+        let exponent = self.exponent() as i64 + exp as i64;
+        if exponent > MAX_INT_DIGITS as i64 {
+            return None;
+        }
+
+        let result = self.shift(exp, dscale);
+        if result.coefficient_digits() > MAX_PRECISION as i32 {
+            return None;
+        }
+        Some(result)
+    }
+
+    /// Negates in place by flipping the sign, the digits are untouched.
+    ///
+    /// Zero and NaN are unchanged.
+    #[inline]
+    pub fn negate(&mut self) {
+        if self.is_zero() {
+            return;
+        }
+        self.sign = match self.sign {
+            SIGN_POS => SIGN_NEG,
+            SIGN_NEG => SIGN_POS,
+            sign => sign,
+        };
+    }
+
+    /// Returns the absolute value, NaN is unchanged.
+    #[inline]
+    pub const fn abs(&self) -> Decimal {
+        match self.sign {
+            SIGN_NEG => Decimal {
+                sign: SIGN_POS,
+                ..*self
+            },
+            _ => *self,
+        }
+    }
+
+    /// Returns `-1`, `0` or `1` with scale 0 according to the sign, NaN is unchanged.
+    #[inline]
+    pub const fn signum(&self) -> Decimal {
+        if self.is_nan() {
+            return *self;
+        }
+        if self.is_zero() {
+            return Decimal::ZERO;
+        }
+
+        let mut digits = [0; MAX_NDIGITS];
+        digits[0] = 1;
+        Decimal {
+            ndigits: 1,
+            sign: self.sign,
+            weight: 0,
+            dscale: 0,
+            digits,
+        }
+    }
+
+    /// Returns the magnitude of `self` with the sign of `other`.
+    ///
+    /// Zero stays zero, a NaN `self` is unchanged, and a NaN `other` counts as positive.
+    #[inline]
+    pub const fn copysign(&self, other: &Decimal) -> Decimal {
+        if self.is_nan() || self.is_zero() {
+            return *self;
+        }
+
+        let sign = if other.is_sign_negative() { SIGN_NEG } else { SIGN_POS };
+        Decimal { sign, ..*self }
+    }
+
+    /// Adds, rounding half up the digits that don't fit, or returns `None` on overflow.
+    ///
+    /// The result has the larger scale of the operands, reduced if it needs more than
+    /// `MAX_PRECISION` digits.
+    #[inline]
+    pub(crate) fn add_rounded(&self, other: &Decimal) -> Option<Decimal> {
+        let sum = Var::from_decimal(self).add(&Var::from_decimal(other));
+        let result = sum.into_decimal(RoundingStrategy::HalfUp);
+        record_loss!(rounded(Add, &sum, result.as_ref()));
+        result.map(|(d, _)| d)
+    }
+
+    /// Subtracts like [`add_rounded`](Decimal::add_rounded).
+    #[inline]
+    pub(crate) fn sub_rounded(&self, other: &Decimal) -> Option<Decimal> {
+        let difference = Var::from_decimal(self).sub(&Var::from_decimal(other));
+        let result = difference.into_decimal(RoundingStrategy::HalfUp);
+        record_loss!(rounded(Sub, &difference, result.as_ref()));
+        result.map(|(d, _)| d)
+    }
+
+    /// Multiplies like [`add_rounded`](Decimal::add_rounded), the result has the sum of the
+    /// scales of the operands.
+    #[inline]
+    pub(crate) fn mul_rounded(&self, other: &Decimal) -> Option<Decimal> {
+        let product = Var::from_decimal(self).mul(&Var::from_decimal(other));
+        let result = product.into_decimal(RoundingStrategy::HalfUp);
+        record_loss!(rounded(Mul, &product, result.as_ref()));
+        result.map(|(d, _)| d)
+    }
+
+    /// Computes `self * a + b` with a single rounding of the exact result.
+    ///
+    /// The result has the larger of the scale of the product and the scale of `b`. If it needs
+    /// more than `MAX_PRECISION` digits or `MAX_SCALE` digits after the decimal point, the
+    /// digits that don't fit are rounded half up. NaN operands give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the integral part of the result overflows.
+    #[inline]
+    pub fn mul_add(&self, a: &Decimal, b: &Decimal) -> Decimal {
+        let product = Var::from_decimal(self).mul(&Var::from_decimal(a));
+        let exact = product.add(&Var::from_decimal(b));
+        let result = exact.into_decimal(RoundingStrategy::HalfUp);
+        record_loss!(rounded(MulAdd, &exact, result.as_ref()));
+        match result {
+            Some((result, _)) => result,
+            None => panic!("overflow when computing a fused multiply-add"),
+        }
+    }
+
+    /// Adds, returning `None` instead of panicking if the sum overflows.
+    ///
+    /// See [`Add`] for the result scale and rounding.
+    #[inline]
+    pub fn checked_add(&self, other: &Decimal) -> Option<Decimal> {
+        self.add_rounded(other)
+    }
+
+    /// Subtracts, returning `None` instead of panicking if the difference overflows.
+    ///
+    /// See [`Sub`] for the result scale and rounding.
+    #[inline]
+    pub fn checked_sub(&self, other: &Decimal) -> Option<Decimal> {
+        self.sub_rounded(other)
+    }
+
+    /// Multiplies, returning `None` instead of panicking if the product overflows.
+    ///
+    /// See [`Mul`] for the result scale and rounding.
+    #[inline]
+    pub fn checked_mul(&self, other: &Decimal) -> Option<Decimal> {
+        self.mul_rounded(other)
+    }
+
+    /// Divides, returning `None` instead of panicking if `other` is zero or the quotient
+    /// overflows.
+    ///
+    /// See [`Div`] for the result scale and rounding.
+    #[inline]
+    pub fn checked_div(&self, other: &Decimal) -> Option<Decimal> {
+        if other.is_zero() && !self.is_nan() {
+            return None;
+        }
+        self.div_full(other)
+    }
+
+    /// Divides, returning the quotient only if it is exact, with at most `MAX_PRECISION`
+    /// significant digits and `MAX_SCALE` digits after the decimal point.
+    ///
+    /// The quotient is the same as [`Div`] gives, e.g. `1 / 8` is `0.125` but `1 / 3` gives
+    /// `None`. Returns `None` as well if `other` is zero, the quotient overflows or an operand
+    /// is NaN.
+    #[inline]
+    pub fn try_div_exact(&self, other: &Decimal) -> Option<Decimal> {
+        if self.is_nan() || other.is_nan() || other.is_zero() {
+            return None;
+        }
+        match self.div_full_lossy(other)? {
+            (result, 0) => Some(result),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if dividing by `divisor` gives an exact quotient, as
+    /// [`try_div_exact`](Decimal::try_div_exact), so a split into `divisor` parts is loss-free.
+    #[inline]
+    pub fn divides_exactly(&self, divisor: &Decimal) -> bool {
+        self.try_div_exact(divisor).is_some()
+    }
+
+    /// Returns the remainder, or `None` instead of panicking if `other` is zero.
+    ///
+    /// See [`Rem`] for the sign and scale of the remainder.
+    #[inline]
+    pub fn checked_rem(&self, other: &Decimal) -> Option<Decimal> {
+        if other.is_zero() && !self.is_nan() {
+            return None;
+        }
+        Some(self.div_rem_var(other).1)
+    }
+
+    /// Adds, returning the sum and whether digits were rounded away to fit in a decimal.
+    ///
+    /// See [`Add`] for the result scale and rounding. If the integral part overflows, the sum
+    /// is clamped to [`Decimal::MAX`] or [`Decimal::MIN`] and the flag is `true`.
+    #[inline]
+    pub fn overflowing_add(&self, other: &Decimal) -> (Decimal, bool) {
+        let exact = Var::from_decimal(self).add(&Var::from_decimal(other));
+        let result = exact.into_decimal(RoundingStrategy::HalfUp);
+        record_loss!(overflowed(Add, &exact, result.as_ref()));
+        overflowing(&exact, result)
+    }
+
+    /// Subtracts, returning the difference and whether digits were rounded away to fit in a
+    /// decimal.
+    ///
+    /// See [`Sub`] for the result scale and rounding. If the integral part overflows, the
+    /// difference is clamped to [`Decimal::MAX`] or [`Decimal::MIN`] and the flag is `true`.
+    #[inline]
+    pub fn overflowing_sub(&self, other: &Decimal) -> (Decimal, bool) {
+        let exact = Var::from_decimal(self).sub(&Var::from_decimal(other));
+        let result = exact.into_decimal(RoundingStrategy::HalfUp);
+        record_loss!(overflowed(Sub, &exact, result.as_ref()));
+        overflowing(&exact, result)
+    }
+
+    /// Multiplies, returning the product and whether digits were rounded away to fit in a
+    /// decimal.
+    ///
+    /// See [`Mul`] for the result scale and rounding. If the integral part overflows, the
+    /// product is clamped to [`Decimal::MAX`] or [`Decimal::MIN`] and the flag is `true`.
+    #[inline]
+    pub fn overflowing_mul(&self, other: &Decimal) -> (Decimal, bool) {
+        let exact = Var::from_decimal(self).mul(&Var::from_decimal(other));
+        let result = exact.into_decimal(RoundingStrategy::HalfUp);
+        record_loss!(overflowed(Mul, &exact, result.as_ref()));
+        overflowing(&exact, result)
+    }
+
+    /// Divides, rounding the quotient once to `scale` digits after the decimal point, or to
+    /// fewer if it needs more than `MAX_PRECISION` digits.
+    ///
+    /// The divisor must be non-zero unless an operand is NaN. Returns `None` on overflow,
+    /// otherwise the quotient and the number of digits rounded away, as counted by
+    /// `precision_metrics`, 0 if it is exact.
+    pub(crate) fn div_rounded(
+        &self,
+        other: &Decimal,
+        scale: i32,
+        strategy: RoundingStrategy,
+    ) -> Option<(Decimal, u32)> {
+        let u = Var::from_decimal(self);
+        let v = Var::from_decimal(other);
+        if let Some(nan) = u.nan_operand(&v) {
+            return nan.into_decimal(strategy).map(|(d, _)| (d, 0));
+        }
+        debug_assert!(!v.is_zero());
+
+        // Quotients beyond these scales round to zero or overflow anyway.
+        let scale = scale.clamp(-MAX_INT_DIGITS - 1, MAX_SCALE);
+        let (mut quotient, sticky) = u.div(&v, scale + 1);
+        let scale = if quotient.is_zero() {
+            scale
+        } else {
+            scale.min(MAX_PRECISION as i32 - quotient.exponent())
+        };
+        let discarded = quotient.min_scale() - scale;
+        let inexact = quotient.round(scale, strategy, sticky);
+        let (result, rounded) = quotient.into_decimal(strategy)?;
+        let lost = if inexact || rounded { discarded.max(1) as u32 } else { 0 };
+        Some((result, lost))
+    }
+
+    /// Divides with as many digits as fit for an inexact quotient, or the smallest scale not
+    /// less than the scales of the operands for an exact one.
+    #[inline]
+    pub(crate) fn div_full(&self, other: &Decimal) -> Option<Decimal> {
+        let quotient = self.div_full_lossy(other);
+        record_loss!(divided(Div, quotient.as_ref()));
+        quotient.map(|(result, _)| result)
+    }
+
+    /// Divides as [`div_full`](Decimal::div_full), also returning the number of digits rounded
+    /// away as [`div_rounded`](Decimal::div_rounded).
+    fn div_full_lossy(&self, other: &Decimal) -> Option<(Decimal, u32)> {
+        let (mut result, lost) = self.div_rounded(other, MAX_SCALE, RoundingStrategy::HalfUp)?;
+        if lost == 0 && !result.is_nan() {
+            let scale = result.min_scale().max(self.scale()).max(other.scale());
+            result.dscale = result.dscale.min(scale as u8);
+        }
+        Some((result, lost))
+    }
+
+    /// Divides, rounding the quotient to `scale` digits after the decimal point with `strategy`.
+    ///
+    /// `scale` may be negative to round to tens, hundreds, and so on. If the quotient would
+    /// need more than `MAX_PRECISION` digits, it is rounded to fewer digits after the decimal
+    /// point. NaN operands give NaN, and so does division by zero, as
+    /// [`div_with_context`](Decimal::div_with_context) which also reports it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the quotient overflows.
+    #[inline]
+    pub fn div_with_scale(&self, other: &Decimal, scale: i32, strategy: RoundingStrategy) -> Decimal {
+        if other.is_zero() && !self.is_nan() {
+            return Decimal::NAN;
+        }
+        let quotient = self.div_rounded(other, scale, strategy);
+        record_loss!(divided(Div, quotient.as_ref()));
+        let (result, _) = quotient.expect("overflow when dividing decimals");
+        result
+    }
+
+    /// Computes `self / denominator` rounded directly to `scale` digits after the decimal point,
+    /// for percentages and unit prices.
+    ///
+    /// The quotient is rounded exactly once with `strategy`, as by
+    /// [`div_with_scale`](Decimal::div_with_scale). Returns `None` if `denominator` is zero or
+    /// the quotient overflows.
+    #[inline]
+    pub fn ratio(&self, denominator: &Decimal, scale: i32, strategy: RoundingStrategy) -> Option<Decimal> {
+        if denominator.is_zero() && !self.is_nan() {
+            return None;
+        }
+        let quotient = self.div_rounded(denominator, scale, strategy);
+        record_loss!(divided(Div, quotient.as_ref()));
+        quotient.map(|(d, _)| d)
+    }
+
+    /// Returns the quotient truncated towards zero and the remainder, which has the sign of
+    /// `self` and the larger scale of the operands.
+    ///
+    /// The divisor must be non-zero unless an operand is NaN. The remainder always fits in a
+    /// decimal, but the quotient may not.
+    pub(crate) fn div_rem_var(&self, other: &Decimal) -> (Var, Decimal) {
+        let u = Var::from_decimal(self);
+        let v = Var::from_decimal(other);
+        if let Some(nan) = u.nan_operand(&v) {
+            let (nan, _) = nan.into_decimal(RoundingStrategy::HalfUp).unwrap();
+            return (Var::from_decimal(&nan), nan);
+        }
+        debug_assert!(!v.is_zero());
+
+        let (quotient, _) = u.div(&v, 0);
+        let (remainder, _) = u.sub(&quotient.mul(&v)).into_decimal(RoundingStrategy::HalfUp).unwrap();
+        (quotient, remainder)
+    }
+
+    /// Returns the quotient truncated towards zero and the remainder in one pass.
+    ///
+    /// The remainder is `self - quotient * other`, which has the sign of `self` and the larger
+    /// scale of the operands as SQL's `%`. NaN operands give NaN for both.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero or the quotient overflows.
+    #[inline]
+    pub fn div_rem(&self, other: &Decimal) -> (Decimal, Decimal) {
+        assert!(self.is_nan() || other.is_nan() || !other.is_zero(), "division by zero");
+        let (quotient, remainder) = self.div_rem_var(other);
+        let (quotient, _) = quotient
+            .into_decimal(RoundingStrategy::HalfUp)
+            .expect("overflow when dividing decimals");
+        (quotient, remainder)
+    }
+
+    /// Returns the quotient of the Euclidean division, such that the remainder
+    /// `self - quotient * other` is never negative.
+    ///
+    /// The quotient is rounded towards negative infinity when `other` is positive and towards
+    /// positive infinity otherwise, e.g. `-7.5.div_euclid(2) = -4`. NaN operands give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero or the quotient overflows.
+    #[inline]
+    pub fn div_euclid(&self, other: &Decimal) -> Decimal {
+        let (quotient, _) = self.div_rem_euclid_var(other);
+        let (quotient, _) = quotient
+            .into_decimal(RoundingStrategy::HalfUp)
+            .expect("overflow when dividing decimals");
+        quotient
+    }
+
+    /// Returns the non-negative remainder of the Euclidean division, less than `|other|`.
+    ///
+    /// The remainder has the larger scale of the operands, e.g. `-7.5.rem_euclid(2) = 0.5`. If
+    /// it needs more than `MAX_PRECISION` digits, the digits that don't fit are
+    /// rounded half up, which may round a tiny negative remainder up to `|other|`. NaN operands
+    /// give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    #[inline]
+    pub fn rem_euclid(&self, other: &Decimal) -> Decimal {
+        self.div_rem_euclid_var(other).1
+    }
+
+    fn div_rem_euclid_var(&self, other: &Decimal) -> (Var, Decimal) {
+        assert!(self.is_nan() || other.is_nan() || !other.is_zero(), "division by zero");
+        let (quotient, remainder) = self.div_rem_var(other);
+        if !remainder.is_sign_negative() {
+            return (quotient, remainder);
+        }
+
+        let v = Var::from_decimal(other);
+        let one = Var::from_decimal(&Decimal::ONE);
+        let quotient = if v.is_negative() {
+            quotient.add(&one)
+        } else {
+            quotient.sub(&one)
+        };
+        let (remainder, _) = Var::from_decimal(&remainder)
+            .add(&v.abs())
+            .into_decimal(RoundingStrategy::HalfUp)
+            .unwrap();
+        (quotient, remainder)
+    }
+
+    /// Multiplies by `10^exp` with the result display scale `dscale`, the result must fit.
+    pub(crate) fn shift(&self, exp: i32, dscale: i32) -> Decimal {
+        if exp.rem_euclid(DEC_DIGITS) == 0 {
+            return Decimal {
+                weight: (self.weight as i32 + exp / DEC_DIGITS) as i8,
+                dscale: dscale as u8,
+                ..*self
+            };
+        }
+
+        let mut buf = [0u8; MAX_NDIGITS * DEC_DIGITS as usize];
+        let mut len = 0;
+        for d in self.decimal_digits() {
+            buf[len] = d;
+            len += 1;
+        }
+        let last = (self.weight as i32 - self.ndigits as i32 + 1) * DEC_DIGITS;
+        Decimal::from_decimal_digits(self.is_sign_negative(), &buf[..len], last + exp, dscale)
+    }
+}
+
+/// Returns the conversion of an exact result, clamping it if the integral part overflows.
+#[inline]
+fn overflowing(var: &Var, result: Option<(Decimal, bool)>) -> (Decimal, bool) {
+    match result {
+        Some(result) => result,
+        None if var.is_negative() => (Decimal::MIN, true),
+        None => (Decimal::MAX, true),
+    }
+}
+
+impl Add for Decimal {
+    type Output = Decimal;
+
+    /// Adds two decimals, the result has the larger scale of the operands.
+    ///
+    /// If the exact sum needs more than `MAX_PRECISION` digits, the digits that don't
+    /// fit are rounded half up. NaN operands give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the integral part of the sum overflows.
+    #[inline]
+    fn add(self, other: Decimal) -> Decimal {
+        self.add_rounded(&other).expect("overflow when adding decimals")
+    }
+}
+
+impl Sub for Decimal {
+    type Output = Decimal;
+
+    /// Subtracts two decimals, the result has the larger scale of the operands.
+    ///
+    /// If the exact difference needs more than `MAX_PRECISION` digits, the digits that
+    /// don't fit are rounded half up. NaN operands give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the integral part of the difference overflows.
+    #[inline]
+    fn sub(self, other: Decimal) -> Decimal {
+        self.sub_rounded(&other).expect("overflow when subtracting decimals")
+    }
+}
+
+impl Mul for Decimal {
+    type Output = Decimal;
+
+    /// Multiplies two decimals, the result has the sum of the scales of the operands.
+    ///
+    /// If the exact product needs more than `MAX_PRECISION` digits or `MAX_SCALE` digits after
+    /// the decimal point, the digits that don't fit are rounded half up. NaN operands
+    /// give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the integral part of the product overflows.
+    #[inline]
+    fn mul(self, other: Decimal) -> Decimal {
+        self.mul_rounded(&other).expect("overflow when multiplying decimals")
+    }
+}
+
+impl Div for Decimal {
+    type Output = Decimal;
+
+    /// Divides two decimals.
+    ///
+    /// An exact quotient has the smallest scale not less than the scales of the operands, e.g.
+    /// `1.0 / 4 = 0.25` and `6 / 2 = 3`. An inexact quotient is rounded half up to
+    /// `MAX_PRECISION` significant digits, or to `MAX_SCALE` digits after the decimal point.
+    /// NaN operands give NaN, and so does division by zero, as
+    /// [`div_with_context`](Decimal::div_with_context) which also reports it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the quotient overflows.
+    #[inline]
+    fn div(self, other: Decimal) -> Decimal {
+        if other.is_zero() && !self.is_nan() {
+            return Decimal::NAN;
+        }
+        self.div_full(&other).expect("overflow when dividing decimals")
+    }
+}
+
+impl Rem for Decimal {
+    type Output = Decimal;
+
+    /// Returns the remainder of the division truncated towards zero.
+    ///
+    /// The remainder has the sign of `self` and the larger scale of the operands as SQL's `%`,
+    /// e.g. `-7.5 % 2 = -1.5`. NaN operands give NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `other` is zero.
+    #[inline]
+    fn rem(self, other: Decimal) -> Decimal {
+        assert!(self.is_nan() || other.is_nan() || !other.is_zero(), "division by zero");
+        self.div_rem_var(&other).1
+    }
+}
+
+impl Neg for Decimal {
+    type Output = Decimal;
+
+    /// Negates the decimal, zero and NaN are unchanged.
+    #[inline]
+    fn neg(mut self) -> Decimal {
+        self.negate();
+        self
+    }
+}
+
+impl Neg for &Decimal {
+    type Output = Decimal;
+
+    /// Negates the decimal, zero and NaN are unchanged.
+    #[inline]
+    fn neg(self) -> Decimal {
+        -*self
+    }
+}
+
+macro_rules! forward_ref_binop {
+    ($trait:ident, $method:ident) => {
+        impl $trait<&Decimal> for Decimal {
+            type Output = Decimal;
+
+            #[inline]
+            fn $method(self, other: &Decimal) -> Decimal {
+                self.$method(*other)
+            }
+        }
+
+        impl $trait<Decimal> for &Decimal {
+            type Output = Decimal;
+
+            #[inline]
+            fn $method(self, other: Decimal) -> Decimal {
+                (*self).$method(other)
+            }
+        }
+
+        impl $trait<&Decimal> for &Decimal {
+            type Output = Decimal;
+
+            #[inline]
+            fn $method(self, other: &Decimal) -> Decimal {
+                (*self).$method(*other)
+            }
+        }
+    };
+}
+
+forward_ref_binop!(Add, add);
+forward_ref_binop!(Sub, sub);
+forward_ref_binop!(Mul, mul);
+forward_ref_binop!(Div, div);
+forward_ref_binop!(Rem, rem);
+
+macro_rules! impl_int_binop {
+    ($trait:ident, $method:ident, $($t:ty),*) => {
+        $(
+            impl $trait<$t> for Decimal {
+                type Output = Decimal;
+
+                #[inline]
+                fn $method(self, other: $t) -> Decimal {
+                    self.$method(Decimal::from(other))
+                }
+            }
+
+            impl $trait<Decimal> for $t {
+                type Output = Decimal;
+
+                #[inline]
+                fn $method(self, other: Decimal) -> Decimal {
+                    Decimal::from(self).$method(other)
+                }
+            }
+        )*
+    };
+}
+
+impl_int_binop!(Add, add, i32, i64, u64);
+impl_int_binop!(Sub, sub, i32, i64, u64);
+impl_int_binop!(Mul, mul, i32, i64, u64);
+impl_int_binop!(Div, div, i32, i64, u64);
+
+macro_rules! impl_assign_op {
+    ($assign_trait:ident, $assign_method:ident, $method:ident) => {
+        impl $assign_trait for Decimal {
+            #[inline]
+            fn $assign_method(&mut self, other: Decimal) {
+                *self = self.$method(other);
+            }
+        }
+
+        impl $assign_trait<&Decimal> for Decimal {
+            #[inline]
+            fn $assign_method(&mut self, other: &Decimal) {
+                *self = self.$method(*other);
+            }
+        }
+    };
+}
+
+impl_assign_op!(AddAssign, add_assign, add);
+impl_assign_op!(SubAssign, sub_assign, sub);
+impl_assign_op!(MulAssign, mul_assign, mul);
+impl_assign_op!(DivAssign, div_assign, div);
+impl_assign_op!(RemAssign, rem_assign, rem);
+
+macro_rules! impl_iter_op {
+    ($trait:ident, $method:ident, $op:ident, $identity:expr, $doc:expr) => {
+        impl $trait for Decimal {
+            #[doc = $doc]
+            #[inline]
+            fn $method<I: Iterator<Item = Decimal>>(iter: I) -> Decimal {
+                iter.fold($identity, |acc, d| acc.$op(d))
+            }
+        }
+
+        impl<'a> $trait<&'a Decimal> for Decimal {
+            #[doc = $doc]
+            #[inline]
+            fn $method<I: Iterator<Item = &'a Decimal>>(iter: I) -> Decimal {
+                iter.fold($identity, |acc, d| acc.$op(*d))
+            }
+        }
+    };
+}
+
+impl_iter_op!(
+    Sum,
+    sum,
+    add,
+    Decimal::ZERO,
+    "Adds the decimals in order with [`Add`], an empty iterator gives zero.\n\n\
+     # Panics\n\n\
+     Panics if a partial sum overflows, as `+` does."
+);
+impl_iter_op!(
+    Product,
+    product,
+    mul,
+    Decimal::ONE,
+    "Multiplies the decimals in order with [`Mul`], an empty iterator gives one.\n\n\
+     # Panics\n\n\
+     Panics if a partial product overflows, as `*` does."
+);
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decimal, RoundingStrategy};
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn division_by_zero_is_nan() {
+        for (u, v) in [("1", "0"), ("-1.5", "0.00"), ("0", "0"), ("0.000", "-0")] {
+            let (u, v) = (dec(u), dec(v));
+            assert!((u / v).is_nan(), "{} / {}", u, v);
+            assert!(
+                u.div_with_scale(&v, 2, RoundingStrategy::HalfUp).is_nan(),
+                "{} / {}",
+                u,
+                v
+            );
+            let mut w = u;
+            w /= v;
+            assert!(w.is_nan());
+        }
+        assert!(dec("0").recip(None).is_nan());
+        assert_eq!(dec("0").checked_recip(None), None);
+
+        // A NaN dividend is quieted and keeps its payload.
+        let nan = dec("sNaN12");
+        assert_eq!((nan / Decimal::ZERO).to_string(), "NaN12");
+        let quotient = nan.div_with_scale(&Decimal::ZERO, 0, RoundingStrategy::Down);
+        assert_eq!(quotient.to_string(), "NaN12");
+    }
+
+    #[test]
+    fn result_scales() {
+        let cases = [
+            (dec("1.5") + dec("2.25"), "3.75"),
+            (dec("1.50") + dec("-1.5"), "0.00"),
+            (dec("-1.000") - dec("1"), "-2.000"),
+            (dec("1.5") * dec("2.25"), "3.375"),
+            (dec("1.50") * dec("2.0"), "3.000"),
+            (dec("1.0") / dec("4"), "0.25"),
+            (dec("6") / dec("2"), "3"),
+            (dec("6.00") / dec("2"), "3.00"),
+            (dec("1") / dec("3"), "0.333333333333333333333333333333333333"),
+            (dec("-2") / dec("3"), "-0.666666666666666666666666666666666667"),
+            (dec("-7.5") % dec("2"), "-1.5"),
+            (dec("7") % dec("0.25"), "0.00"),
+            // Digits beyond `MAX_PRECISION` or `MAX_SCALE` are rounded half up.
+            (dec("1e30") + dec("0.0000005"), "1000000000000000000000000000000.00000"),
+        ];
+        for (result, expected) in cases {
+            assert_eq!(result.to_string(), expected);
+        }
+        let tiny = dec("1e-100") * dec("5e-31");
+        assert_eq!(tiny.to_string(), format!("0.{}1", "0".repeat(129)));
+
+        assert_eq!(dec("1.50").checked_add(&dec("1")).unwrap().to_string(), "2.50");
+        assert_eq!(dec("1.50").checked_sub(&dec("1")).unwrap().to_string(), "0.50");
+        assert_eq!(dec("1.50").checked_mul(&dec("1.0")).unwrap().to_string(), "1.500");
+        assert_eq!(dec("1.50").checked_div(&dec("0.5")).unwrap().to_string(), "3.00");
+        assert_eq!(dec("1.50").checked_rem(&dec("0.4")).unwrap().to_string(), "0.30");
+        assert_eq!(dec("1.50").checked_div(&Decimal::ZERO), None);
+        assert_eq!(dec("1.50").checked_rem(&Decimal::ZERO), None);
+        assert_eq!(Decimal::MAX.checked_add(&Decimal::MAX), None);
+        assert_eq!(Decimal::MIN.checked_sub(&Decimal::MAX), None);
+        assert_eq!(Decimal::MAX.checked_mul(&dec("1.1")), None);
+        assert_eq!(Decimal::MAX.checked_div(&dec("0.1")), None);
+
+        let (quotient, remainder) = dec("-7.5").div_rem(&dec("2"));
+        assert_eq!(
+            (quotient.to_string(), remainder.to_string()),
+            ("-3".into(), "-1.5".into())
+        );
+    }
+
+    #[test]
+    fn reference_and_assign_operators() {
+        let (a, b) = (dec("1.5"), dec("0.25"));
+        let values = [a, b];
+        let (x, y) = (&values[0], &values[1]);
+        assert_eq!(x + y, a + b);
+        assert_eq!(a - y, a - b);
+        assert_eq!(x * b, a * b);
+        assert_eq!(x / y, a / b);
+        assert_eq!(x % y, a % b);
+        assert_eq!(-x, dec("-1.5"));
+
+        let mut x = a;
+        x += b;
+        x -= &b;
+        x *= b;
+        x /= &b;
+        assert_eq!(x.to_string(), "1.5000");
+        x %= dec("0.4");
+        assert_eq!(x.to_string(), "0.3000");
+    }
+
+    #[test]
+    fn integer_operands() {
+        let x = dec("1.5");
+        assert_eq!((x + 2i32).to_string(), "3.5");
+        assert_eq!((2i32 + x).to_string(), "3.5");
+        assert_eq!((x - 10i64).to_string(), "-8.5");
+        assert_eq!((10i64 - x).to_string(), "8.5");
+        assert_eq!((x * 3u64).to_string(), "4.5");
+        assert_eq!((3u64 * x).to_string(), "4.5");
+        assert_eq!((x / 4i32).to_string(), "0.375");
+        assert_eq!((3i32 / x).to_string(), "2.0");
+        assert_eq!((dec("1") * i64::MIN).to_string(), i64::MIN.to_string());
+        assert_eq!((u64::MAX - dec("0.5")).to_string(), "18446744073709551614.5");
+    }
+
+    #[test]
+    fn overflowing_flags() {
+        let (one, max, min) = (dec("1"), Decimal::MAX, Decimal::MIN);
+        assert_eq!(dec("1.5").overflowing_add(&one), (dec("2.5"), false));
+        assert_eq!(dec("1.5").overflowing_sub(&one), (dec("0.5"), false));
+        assert_eq!(dec("0.1").overflowing_mul(&dec("0.1")), (dec("0.01"), false));
+
+        // Digits beyond `MAX_PRECISION` are rounded away.
+        let (sum, rounded) = one.overflowing_add(&dec("1e-40"));
+        assert_eq!((sum.to_string().len(), rounded), (37, true));
+        assert_eq!(max.overflowing_add(&one), (max, true));
+        let (product, rounded) = dec("0.3333333333333333333").overflowing_mul(&dec("3.000000000000000001"));
+        assert!(rounded);
+        assert_eq!(product.to_string(), "1.00000000000000000023333333333333333");
+
+        // An overflowing result is clamped.
+        assert_eq!(max.overflowing_add(&max), (max, true));
+        assert_eq!(min.overflowing_sub(&max), (min, true));
+        assert_eq!(max.overflowing_mul(&dec("-2")), (min, true));
+        assert!(Decimal::NAN.overflowing_add(&one).0.is_nan());
+    }
+
+    #[test]
+    fn euclidean_division() {
+        for (u, v, q, r) in [
+            ("7.5", "2", "3", "1.5"),
+            ("-7.5", "2", "-4", "0.5"),
+            ("7.5", "-2", "-3", "1.5"),
+            ("-7.5", "-2", "4", "0.5"),
+            ("-6", "2", "-3", "0"),
+            ("-0.01", "0.5", "-1", "0.49"),
+        ] {
+            let (u, v) = (dec(u), dec(v));
+            assert_eq!(u.div_euclid(&v).to_string(), q, "{} div_euclid {}", u, v);
+            assert_eq!(u.rem_euclid(&v).to_string(), r, "{} rem_euclid {}", u, v);
+            assert!(!u.rem_euclid(&v).is_sign_negative());
+            assert_eq!(u.div_euclid(&v) * v + u.rem_euclid(&v), u);
+        }
+        assert!(Decimal::NAN.div_euclid(&dec("2")).is_nan());
+        assert!(dec("2").rem_euclid(&Decimal::NAN).is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "division by zero")]
+    fn euclidean_division_by_zero() {
+        dec("1").rem_euclid(&Decimal::ZERO);
+    }
+
+    #[test]
+    fn mul_add_rounds_once() {
+        // `x * x` needs 71 digits and is rounded before the subtraction with the operators.
+        let x = dec("1.00000000000000000000000000000000001");
+        let one = dec("-1");
+        assert_eq!((x * x + one).to_string(), "0.00000000000000000000000000000000002");
+        assert_eq!(
+            x.mul_add(&x, &one).to_string(),
+            format!("0.{}2{}1", "0".repeat(34), "0".repeat(34))
+        );
+        assert_eq!(dec("1.5").mul_add(&dec("2"), &dec("0.25")).to_string(), "3.25");
+        assert!(Decimal::NAN.mul_add(&x, &one).is_nan());
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when computing a fused multiply-add")]
+    fn mul_add_overflow() {
+        Decimal::MAX.mul_add(&dec("2"), &dec("1"));
+    }
+
+    #[test]
+    fn exact_division() {
+        assert_eq!(dec("1").try_div_exact(&dec("8")), Some(dec("0.125")));
+        assert_eq!(dec("10.0").try_div_exact(&dec("4")).unwrap().to_string(), "2.5");
+        assert_eq!(dec("1").try_div_exact(&dec("1024")), Some(dec("0.0009765625")));
+        // Non-terminating quotients, or terminating beyond `MAX_PRECISION` digits.
+        assert_eq!(dec("1").try_div_exact(&dec("3")), None);
+        assert_eq!(dec("2").try_div_exact(&dec("7")), None);
+        assert_eq!(dec("1").try_div_exact(&dec("2").powi(120)), None);
+        assert_eq!(dec("1").try_div_exact(&Decimal::ZERO), None);
+        assert_eq!(Decimal::NAN.try_div_exact(&dec("1")), None);
+        assert!(dec("100").divides_exactly(&dec("4")));
+        assert!(!dec("100").divides_exactly(&dec("3")));
+        assert!(!dec("100").divides_exactly(&Decimal::ZERO));
+    }
+
+    #[test]
+    fn signs() {
+        assert_eq!(dec("-1.50").abs().to_string(), "1.50");
+        assert_eq!(dec("-1.50").signum().to_string(), "-1");
+        assert_eq!(dec("0.00").signum().to_string(), "0");
+        assert_eq!(dec("2.5").copysign(&dec("-0.1")).to_string(), "-2.5");
+        assert_eq!(dec("-2.5").copysign(&Decimal::NAN).to_string(), "2.5");
+        assert_eq!(Decimal::ZERO.copysign(&dec("-1")), Decimal::ZERO);
+        assert!(Decimal::NAN.abs().is_nan() && Decimal::NAN.signum().is_nan());
+        let mut x = dec("3");
+        x.negate();
+        assert_eq!(x.to_string(), "-3");
+    }
+
+    #[test]
+    fn sum_and_product() {
+        let values = [dec("1.5"), dec("2.25"), dec("-1")];
+        assert_eq!(values.iter().sum::<Decimal>().to_string(), "2.75");
+        assert_eq!(values.into_iter().product::<Decimal>().to_string(), "-3.375");
+        assert_eq!(std::iter::empty::<Decimal>().sum::<Decimal>(), Decimal::ZERO);
+        assert_eq!(std::iter::empty::<&Decimal>().product::<Decimal>(), Decimal::ONE);
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when adding decimals")]
+    fn sum_overflow() {
+        // A partial sum overflows, even though the total would fit.
+        [Decimal::MAX, Decimal::MAX, Decimal::MIN].iter().sum::<Decimal>();
+    }
+
+    #[test]
+    #[should_panic(expected = "overflow when multiplying decimals")]
+    fn product_overflow() {
+        [Decimal::MAX, dec("10"), dec("0.01")].into_iter().product::<Decimal>();
+    }
+}
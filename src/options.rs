@@ -0,0 +1,431 @@
+//! Configurable parsing and formatting.
+
+use crate::decimal::{Decimal, MAX_INT_DIGITS, MAX_SCALE};
+use crate::error::DecimalParseError;
+use crate::fmt::Buf;
+use crate::parse::{mantissa_digits, scan, to_decimal_rounded, to_nan, Scanned};
+use crate::round::RoundingStrategy;
+use crate::scratch::with_string;
+use crate::var::Var;
+
+/// Options for [`Decimal::parse_with`].
+///
+/// The defaults accept the same literals as [`FromStr`](std::str::FromStr).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ParseOptions {
+    decimal_separator: char,
+    group_separator: Option<char>,
+    lenient_nan: bool,
+    max_length: Option<usize>,
+    max_digits: Option<usize>,
+    rounding: Option<RoundingStrategy>,
+    saturating: bool,
+    strict: bool,
+}
+
+impl ParseOptions {
+    /// Default options, the same as [`ParseOptions::new`].
+    pub const DEFAULT: ParseOptions = ParseOptions::new();
+
+    /// Accepts exactly the output of [`Decimal::to_machine_string`].
+    pub const STRICT: ParseOptions = ParseOptions::new().strict(true);
+
+    /// Creates the default options.
+    #[inline]
+    pub const fn new() -> ParseOptions {
+        ParseOptions {
+            decimal_separator: '.',
+            group_separator: None,
+            lenient_nan: false,
+            max_length: None,
+            max_digits: None,
+            rounding: Some(RoundingStrategy::HalfUp),
+            saturating: false,
+            strict: false,
+        }
+    }
+
+    /// Sets the decimal separator, `.` by default.
+    #[inline]
+    pub const fn decimal_separator(mut self, separator: char) -> ParseOptions {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Sets the digit group separator, which is ignored in the integral part. None by default.
+    #[inline]
+    pub const fn group_separator(mut self, separator: Option<char>) -> ParseOptions {
+        self.group_separator = separator;
+        self
+    }
+
+    /// Sets whether a sign is accepted before `NaN`, e.g. `-NaN`. `false` by default.
+    #[inline]
+    pub const fn lenient_nan(mut self, lenient: bool) -> ParseOptions {
+        self.lenient_nan = lenient;
+        self
+    }
+
+    /// Sets the maximum length of the input in bytes, unlimited by default.
+    ///
+    /// Longer inputs are rejected with [`DecimalParseError::TooLong`] before they are scanned.
+    #[inline]
+    pub const fn max_length(mut self, max_length: Option<usize>) -> ParseOptions {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Sets the maximum number of ASCII digits in the input, including those of the exponent and
+    /// of a NaN payload, unlimited by default.
+    ///
+    /// Inputs with more digits are rejected with [`DecimalParseError::TooLong`] before they are
+    /// scanned.
+    #[inline]
+    pub const fn max_digits(mut self, max_digits: Option<usize>) -> ParseOptions {
+        self.max_digits = max_digits;
+        self
+    }
+
+    /// Rejects inputs beyond the length limits, before any other processing.
+    #[inline]
+    fn check_length(&self, s: &str) -> Result<(), DecimalParseError> {
+        if let Some(max_length) = self.max_length {
+            if s.len() > max_length {
+                return Err(DecimalParseError::TooLong);
+            }
+        }
+        if let Some(max_digits) = self.max_digits {
+            if s.bytes().filter(u8::is_ascii_digit).nth(max_digits).is_some() {
+                return Err(DecimalParseError::TooLong);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the rounding of digits that don't fit in a decimal, `HalfUp` by default.
+    ///
+    /// If `None`, such literals are rejected with [`DecimalParseError::Overflow`].
+    #[inline]
+    pub const fn rounding(mut self, rounding: Option<RoundingStrategy>) -> ParseOptions {
+        self.rounding = rounding;
+        self
+    }
+
+    /// Sets whether numbers out of the range of a decimal are clamped to [`Decimal::MAX`] or
+    /// [`Decimal::MIN`] instead of rejected with [`DecimalParseError::Overflow`]. `false` by
+    /// default.
+    ///
+    /// [`Decimal::parse_with_clamped`] tells whether a value was clamped.
+    #[inline]
+    pub const fn saturating(mut self, saturating: bool) -> ParseOptions {
+        self.saturating = saturating;
+        self
+    }
+
+    /// Sets whether only the grammar of [`Decimal::to_machine_string`] is accepted, `false` by
+    /// default.
+    ///
+    /// The strict grammar is `-?(0|[1-9][0-9]*)(\.[0-9]+)?` without negative zero, or `NaN` and
+    /// `sNaN` with an optional payload without leading zeros. Literals that don't fit exactly are
+    /// rejected, and the other options except the length limits are ignored.
+    #[inline]
+    pub const fn strict(mut self, strict: bool) -> ParseOptions {
+        self.strict = strict;
+        self
+    }
+
+    /// Rewrites the separators of `s` to the syntax of [`FromStr`](std::str::FromStr), into
+    /// `buf` if they differ.
+    fn normalize<'a>(&self, s: &'a str, buf: &'a mut String) -> Result<&'a str, DecimalParseError> {
+        if self.decimal_separator == '.' && self.group_separator.is_none() {
+            return Ok(s);
+        }
+
+        buf.reserve(s.len());
+        let mut fraction = false;
+        for c in s.chars() {
+            if c == self.decimal_separator && !fraction {
+                buf.push('.');
+                fraction = true;
+            } else if Some(c) == self.group_separator && !fraction {
+                continue;
+            } else if c == '.' {
+                return Err(DecimalParseError::Invalid);
+            } else {
+                buf.push(c);
+            }
+        }
+        Ok(buf)
+    }
+}
+
+/// Parses a literal whose separators are normalized, with the other options.
+fn parse_normalized(mut bytes: &[u8], options: &ParseOptions) -> Result<(Decimal, bool), DecimalParseError> {
+    if options.lenient_nan && bytes.len() > 1 && (bytes[0] == b'-' || bytes[0] == b'+') {
+        if let Ok(Scanned::NaN { .. }) = scan(&bytes[1..]) {
+            bytes = &bytes[1..];
+        }
+    }
+
+    let (negative, int, frac, exp) = match scan(bytes)? {
+        Scanned::NaN { signaling, payload } => return to_nan(signaling, payload).map(|d| (d, false)),
+        Scanned::Number {
+            negative,
+            int,
+            frac,
+            exp,
+        } => (negative, int, frac, exp),
+    };
+
+    match to_decimal_rounded(negative, int, frac, exp, options.rounding) {
+        // Without rounding, an overflow may also come from digits that don't fit in range.
+        Err(DecimalParseError::Overflow)
+            if options.saturating && (options.rounding.is_some() || out_of_range(int, frac, exp)) =>
+        {
+            record_loss!(record(Parse, 0, true));
+            Ok((if negative { Decimal::MIN } else { Decimal::MAX }, true))
+        }
+        result => result.map(|d| (d, false)),
+    }
+}
+
+/// Parses the grammar of [`Decimal::to_machine_string`].
+fn parse_strict(s: &[u8]) -> Result<Decimal, DecimalParseError> {
+    if s.is_empty() {
+        return Err(DecimalParseError::Empty);
+    }
+
+    let payload = s.strip_prefix(b"NaN").or_else(|| s.strip_prefix(b"sNaN"));
+    if let Some(payload) = payload {
+        if payload.first() == Some(&b'0') || !payload.iter().all(u8::is_ascii_digit) {
+            return Err(DecimalParseError::Invalid);
+        }
+        return to_nan(s[0] == b's', payload);
+    }
+
+    let (negative, unsigned) = match s.strip_prefix(b"-") {
+        Some(unsigned) => (true, unsigned),
+        None => (false, s),
+    };
+    let (int, frac) = match unsigned.iter().position(|&c| c == b'.') {
+        Some(i) => (&unsigned[..i], Some(&unsigned[i + 1..])),
+        None => (unsigned, None),
+    };
+
+    let digits = |part: &[u8]| !part.is_empty() && part.iter().all(u8::is_ascii_digit);
+    let valid = digits(int)
+        && (int.len() == 1 || int[0] != b'0')
+        && frac.map_or(true, digits)
+        && !(negative && int == b"0" && frac.map_or(true, |frac| frac.iter().all(|&c| c == b'0')));
+    if !valid {
+        return Err(DecimalParseError::Invalid);
+    }
+
+    let frac = frac.unwrap_or(&[]);
+    if frac.len() > MAX_SCALE as usize {
+        return Err(DecimalParseError::Overflow);
+    }
+    to_decimal_rounded(negative, int, frac, 0, None)
+}
+
+/// Returns `true` if the integral part of a scanned number has more than `MAX_INT_DIGITS` digits.
+fn out_of_range(int: &[u8], frac: &[u8], exp: i64) -> bool {
+    let leading_zeros = mantissa_digits(int, frac).take_while(|&d| d == 0).count();
+    let len = int.len() + frac.len() - leading_zeros;
+    len > 0 && len as i64 + exp - frac.len() as i64 > MAX_INT_DIGITS as i64
+}
+
+impl Default for ParseOptions {
+    #[inline]
+    fn default() -> Self {
+        ParseOptions::DEFAULT
+    }
+}
+
+/// How the sign is displayed by [`Decimal::format_with`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SignDisplay {
+    /// Displays `-` for negative numbers only.
+    Negative,
+    /// Displays `-` for negative numbers and `+` for the others.
+    Always,
+    /// Never displays a sign.
+    Never,
+    /// Encloses negative numbers in parentheses, e.g. `(1.50)`, as in accounting.
+    Parentheses,
+    /// Appends ` CR` to negative numbers and ` DR` to the others, as in ledgers.
+    CreditDebit,
+}
+
+/// Options for [`Decimal::format_with`].
+///
+/// The defaults format the same as [`Display`](std::fmt::Display).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FormatOptions {
+    decimal_separator: char,
+    group_separator: Option<char>,
+    sign: SignDisplay,
+    width: usize,
+    scale: Option<(i32, RoundingStrategy)>,
+}
+
+impl FormatOptions {
+    /// Default options, the same as [`FormatOptions::new`].
+    pub const DEFAULT: FormatOptions = FormatOptions::new();
+
+    /// Creates the default options.
+    #[inline]
+    pub const fn new() -> FormatOptions {
+        FormatOptions {
+            decimal_separator: '.',
+            group_separator: None,
+            sign: SignDisplay::Negative,
+            width: 0,
+            scale: None,
+        }
+    }
+
+    /// Sets the decimal separator, `.` by default.
+    #[inline]
+    pub const fn decimal_separator(mut self, separator: char) -> FormatOptions {
+        self.decimal_separator = separator;
+        self
+    }
+
+    /// Sets the separator inserted between groups of three integral digits. None by default.
+    #[inline]
+    pub const fn group_separator(mut self, separator: Option<char>) -> FormatOptions {
+        self.group_separator = separator;
+        self
+    }
+
+    /// Sets how the sign is displayed, [`SignDisplay::Negative`] by default.
+    #[inline]
+    pub const fn sign(mut self, sign: SignDisplay) -> FormatOptions {
+        self.sign = sign;
+        self
+    }
+
+    /// Sets the minimum width in characters, 0 by default.
+    ///
+    /// Numbers are padded with spaces between the sign and the digits, so signs line up in the
+    /// first column. With [`SignDisplay::Parentheses`], the others get a trailing space in
+    /// place of `)`, so decimal points line up in a column.
+    #[inline]
+    pub const fn width(mut self, width: usize) -> FormatOptions {
+        self.width = width;
+        self
+    }
+
+    /// Sets the scale numbers are rounded or padded to with `strategy`, or `None` to keep their
+    /// scales, the default.
+    ///
+    /// A number that would need more than `MAX_PRECISION` digits at `scale` is formatted with
+    /// fewer digits after the decimal point.
+    #[inline]
+    pub const fn scale(mut self, scale: Option<i32>, strategy: RoundingStrategy) -> FormatOptions {
+        self.scale = match scale {
+            Some(scale) => Some((scale, strategy)),
+            None => None,
+        };
+        self
+    }
+}
+
+impl Default for FormatOptions {
+    #[inline]
+    fn default() -> Self {
+        FormatOptions::DEFAULT
+    }
+}
+
+impl Decimal {
+    /// Parses a decimal with the given options.
+    #[inline]
+    pub fn parse_with(s: &str, options: &ParseOptions) -> Result<Decimal, DecimalParseError> {
+        Decimal::parse_with_clamped(s, options).map(|(d, _)| d)
+    }
+
+    /// Parses a decimal with the given options, also returning `true` if the number was out of
+    /// range and clamped as enabled by [`ParseOptions::saturating`].
+    pub fn parse_with_clamped(s: &str, options: &ParseOptions) -> Result<(Decimal, bool), DecimalParseError> {
+        options.check_length(s)?;
+        if options.strict {
+            return parse_strict(s.as_bytes()).map(|d| (d, false));
+        }
+
+        with_string(|buf| {
+            let s = options.normalize(s, buf)?;
+            parse_normalized(s.as_bytes(), options)
+        })
+    }
+
+    /// Formats the decimal with the given options.
+    ///
+    /// NaN is formatted as by [`Display`](std::fmt::Display).
+    ///
+    /// # Panics
+    ///
+    /// Panics if rounding to the scale set by [`FormatOptions::scale`] overflows.
+    pub fn format_with(&self, options: &FormatOptions) -> String {
+        if self.is_nan() {
+            return format!("{:>1$}", self, options.width);
+        }
+
+        let rounded = match options.scale {
+            Some((scale, strategy)) => {
+                let mut var = Var::from_decimal(self);
+                var.round(scale, strategy, false);
+                let (rounded, _) = var.into_decimal(strategy).expect("overflow when rounding decimal");
+                rounded
+            }
+            None => *self,
+        };
+
+        let mut buf = Buf::new();
+        rounded.fmt_abs(&mut buf);
+        let s = buf.as_str();
+        let (int, frac) = match s.find('.') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+
+        let negative = rounded.is_sign_negative();
+        let (prefix, suffix) = match options.sign {
+            SignDisplay::Negative if negative => ("-", ""),
+            SignDisplay::Always if negative => ("-", ""),
+            SignDisplay::Always => ("+", ""),
+            SignDisplay::Parentheses if negative => ("(", ")"),
+            SignDisplay::Parentheses if options.width > 0 => ("", " "),
+            SignDisplay::CreditDebit if negative => ("", " CR"),
+            SignDisplay::CreditDebit => ("", " DR"),
+            _ => ("", ""),
+        };
+
+        with_string(|body| {
+            body.reserve(s.len() + s.len() / 3 + 1);
+            for (i, c) in int.chars().enumerate() {
+                if let Some(separator) = options.group_separator {
+                    if i > 0 && (int.len() - i) % 3 == 0 {
+                        body.push(separator);
+                    }
+                }
+                body.push(c);
+            }
+
+            if let Some(frac) = frac {
+                body.push(options.decimal_separator);
+                body.push_str(frac);
+            }
+
+            let len = prefix.len() + body.chars().count() + suffix.len();
+            let padding = options.width.saturating_sub(len);
+            let mut result = String::with_capacity(len + padding);
+            result.push_str(prefix);
+            result.extend(std::iter::repeat(' ').take(padding));
+            result.push_str(body);
+            result.push_str(suffix);
+            result
+        })
+    }
+}
@@ -0,0 +1,532 @@
+//! Decimal parsing.
+
+use crate::decimal::{Decimal, MAX_INT_DIGITS, MAX_PRECISION, MAX_SCALE};
+use crate::error::DecimalParseError;
+use crate::round::RoundingStrategy;
+use crate::scratch::with_string;
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// Exponents beyond this magnitude are saturated, they can't produce a representable value anyway.
+pub(crate) const MAX_EXPONENT: i64 = 1 << 40;
+
+/// Syntax of a scanned decimal literal.
+pub(crate) enum Scanned<'a> {
+    NaN {
+        signaling: bool,
+        payload: &'a [u8],
+    },
+    Number {
+        negative: bool,
+        int: &'a [u8],
+        frac: &'a [u8],
+        exp: i64,
+    },
+}
+
+/// Error found when scanning a decimal literal, with the byte offset where it was detected.
+pub(crate) struct ScanError {
+    pub(crate) kind: DecimalParseError,
+    pub(crate) position: usize,
+}
+
+impl From<ScanError> for DecimalParseError {
+    #[inline]
+    fn from(e: ScanError) -> Self {
+        e.kind
+    }
+}
+
+#[inline]
+fn invalid(input: &[u8], rest: &[u8]) -> ScanError {
+    ScanError {
+        kind: DecimalParseError::Invalid,
+        position: input.len() - rest.len(),
+    }
+}
+
+/// Scans the syntax of a decimal literal without interpreting its digits.
+///
+/// The grammar is `[+-]digits[.digits][(e|E)[+-]digits]` with at least one digit in the
+/// mantissa, or a case-insensitive `NaN` or `sNaN` followed by optional payload digits.
+pub(crate) fn scan(input: &[u8]) -> Result<Scanned<'_>, ScanError> {
+    if input.is_empty() {
+        return Err(ScanError {
+            kind: DecimalParseError::Empty,
+            position: 0,
+        });
+    }
+
+    let nan = |prefix: &[u8]| input.len() >= prefix.len() && input[..prefix.len()].eq_ignore_ascii_case(prefix);
+    if nan(b"nan") || nan(b"snan") {
+        let signaling = nan(b"snan");
+        let payload = &input[if signaling { 4 } else { 3 }..];
+        return match payload.iter().position(|c| !c.is_ascii_digit()) {
+            None => Ok(Scanned::NaN { signaling, payload }),
+            Some(i) => Err(invalid(input, &payload[i..])),
+        };
+    }
+
+    let (negative, s) = match input[0] {
+        b'-' => (true, &input[1..]),
+        b'+' => (false, &input[1..]),
+        _ => (false, input),
+    };
+
+    let int_len = s.iter().take_while(|c| c.is_ascii_digit()).count();
+    let (int, s) = s.split_at(int_len);
+
+    let (frac, s) = match s.first() {
+        Some(b'.') => {
+            let s = &s[1..];
+            let frac_len = s.iter().take_while(|c| c.is_ascii_digit()).count();
+            s.split_at(frac_len)
+        }
+        _ => (&s[..0], s),
+    };
+
+    if int.is_empty() && frac.is_empty() {
+        return Err(invalid(input, s));
+    }
+
+    let exp = match s.first() {
+        None => 0,
+        Some(b'e') | Some(b'E') => scan_exponent(input, &s[1..])?,
+        Some(_) => return Err(invalid(input, s)),
+    };
+
+    Ok(Scanned::Number {
+        negative,
+        int,
+        frac,
+        exp,
+    })
+}
+
+fn scan_exponent(input: &[u8], s: &[u8]) -> Result<i64, ScanError> {
+    let (negative, s) = match s.first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+
+    if s.is_empty() {
+        return Err(invalid(input, s));
+    }
+
+    let mut exp = 0i64;
+    for (i, &c) in s.iter().enumerate() {
+        if !c.is_ascii_digit() {
+            return Err(invalid(input, &s[i..]));
+        }
+        exp = (exp * 10 + (c - b'0') as i64).min(MAX_EXPONENT);
+    }
+
+    Ok(if negative { -exp } else { exp })
+}
+
+/// Iterates the mantissa digits of a scanned literal as values in `0..=9`.
+#[inline]
+pub(crate) fn mantissa_digits<'a>(int: &'a [u8], frac: &'a [u8]) -> impl Iterator<Item = u8> + 'a {
+    int.iter().chain(frac.iter()).map(|c| c - b'0')
+}
+
+/// Converts a scanned number to a decimal, rounding half up digits beyond the representable scale.
+#[inline]
+pub(crate) fn to_decimal(negative: bool, int: &[u8], frac: &[u8], exp: i64) -> Result<Decimal, DecimalParseError> {
+    to_decimal_rounded(negative, int, frac, exp, Some(RoundingStrategy::HalfUp))
+}
+
+/// Converts a scanned number to a decimal, rounding digits beyond the representable scale with
+/// `rounding`, or returning [`DecimalParseError::Overflow`] for them if it is `None`.
+pub(crate) fn to_decimal_rounded(
+    negative: bool,
+    int: &[u8],
+    frac: &[u8],
+    exp: i64,
+    rounding: Option<RoundingStrategy>,
+) -> Result<Decimal, DecimalParseError> {
+    bench_count!(PARSE_DIGITS, int.len() + frac.len());
+    let dscale = (frac.len() as i64 - exp).clamp(0, MAX_SCALE as i64) as i32;
+
+    let leading_zeros = mantissa_digits(int, frac).take_while(|&d| d == 0).count();
+    let len = int.len() + frac.len() - leading_zeros;
+    if len == 0 {
+        return Ok(Decimal::from_decimal_digits(false, &[], 0, dscale));
+    }
+
+    // The value is `significant digits * 10^last`, and `10^(e-1) <= value < 10^e`.
+    let last = exp - frac.len() as i64;
+    let e = len as i64 + last;
+    if e > MAX_INT_DIGITS as i64 {
+        return Err(DecimalParseError::Overflow);
+    }
+    // Smaller values round alike, as all their digits are below `10^-(MAX_SCALE + 1)`.
+    let e = e.max(-(MAX_SCALE as i64) - 1) as i32;
+
+    // A negative scale rounds an integer beyond `MAX_PRECISION` digits to implied zeros.
+    let mut scale = dscale.min(MAX_PRECISION as i32 - e);
+    let significant = mantissa_digits(int, frac).skip(leading_zeros);
+
+    if -last <= scale as i64 {
+        // All digits are kept.
+        let mut buf = [0u8; MAX_PRECISION as usize];
+        for (b, d) in buf.iter_mut().zip(significant) {
+            *b = d;
+        }
+        return Ok(Decimal::from_decimal_digits(
+            negative,
+            &buf[..len],
+            last as i32,
+            scale.max(0),
+        ));
+    }
+
+    // Keeps digits down to `10^-scale` and rounds the rest.
+    let keep = e + scale;
+    let mut buf = [0u8; MAX_PRECISION as usize + 1];
+    let mut significant = significant;
+    for b in buf[1..].iter_mut().take(keep.max(0) as usize) {
+        *b = significant.next().unwrap();
+    }
+    let (next, rest) = if keep >= 0 {
+        (significant.next().unwrap(), significant.any(|d| d != 0))
+    } else {
+        (0, true)
+    };
+    let inexact = next != 0 || rest;
+    let round_up = match rounding {
+        Some(strategy) => {
+            let half = next
+                .cmp(&5)
+                .then(if rest { Ordering::Greater } else { Ordering::Equal });
+            let odd = keep > 0 && buf[keep as usize] % 2 == 1;
+            strategy.round_up(negative, odd, half, inexact)
+        }
+        None if inexact => return Err(DecimalParseError::Overflow),
+        None => false,
+    };
+    record_loss!(parsed(Parse, int, frac, keep, inexact));
+
+    let mut start = 1;
+    let mut end = 1 + keep.max(0) as usize;
+    if round_up {
+        let mut i = end;
+        loop {
+            i -= 1;
+            if buf[i] < 9 {
+                buf[i] += 1;
+                break;
+            }
+            buf[i] = 0;
+        }
+        if i == 0 {
+            start = 0;
+            if end - start > MAX_PRECISION as usize {
+                // Carried into a new digit, drops a trailing zero to stay within precision.
+                if e >= MAX_INT_DIGITS {
+                    return Err(DecimalParseError::Overflow);
+                }
+                scale -= 1;
+                end -= 1;
+            }
+        }
+    }
+
+    let digits = &buf[start..end];
+    match digits.iter().position(|&d| d != 0) {
+        Some(p) => Ok(Decimal::from_decimal_digits(
+            negative,
+            &digits[p..],
+            -scale,
+            scale.max(0),
+        )),
+        None => Ok(Decimal::from_decimal_digits(false, &[], 0, scale.max(0))),
+    }
+}
+
+/// Converts a scanned NaN to a decimal.
+pub(crate) fn to_nan(signaling: bool, payload: &[u8]) -> Result<Decimal, DecimalParseError> {
+    let leading_zeros = payload.iter().take_while(|&&c| c == b'0').count();
+    let payload = &payload[leading_zeros..];
+    if payload.len() > MAX_PRECISION as usize {
+        return Err(DecimalParseError::Overflow);
+    }
+
+    let value = payload.iter().fold(0u128, |acc, &c| acc * 10 + (c - b'0') as u128);
+    Ok(Decimal::nan_with_payload(value, signaling).unwrap())
+}
+
+/// Offsets of the parts of a scanned literal, which can be stored and materialized later.
+///
+/// Scanning checks the syntax without interpreting digits, so a query engine can validate a
+/// column cheaply and only materialize the rows it keeps, with
+/// [`materialize`](ParsedLayout::materialize) and the same string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ParsedLayout {
+    nan: bool,
+    signaling: bool,
+    negative: bool,
+    int: (u32, u32),
+    frac: (u32, u32),
+    exp: i64,
+}
+
+impl ParsedLayout {
+    /// Returns `true` if the literal is a quiet or signaling NaN.
+    #[inline]
+    pub const fn is_nan(&self) -> bool {
+        self.nan
+    }
+
+    /// Returns `true` if the literal has a `-` sign.
+    #[inline]
+    pub const fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// Returns the byte range of the integral digits, or of the payload digits of a NaN.
+    #[inline]
+    pub fn integral(&self) -> Range<usize> {
+        self.int.0 as usize..self.int.1 as usize
+    }
+
+    /// Returns the byte range of the fraction digits, empty if there is no fraction.
+    #[inline]
+    pub fn fraction(&self) -> Range<usize> {
+        self.frac.0 as usize..self.frac.1 as usize
+    }
+
+    /// Returns the value of the exponent, 0 if there is no exponent.
+    #[inline]
+    pub const fn exponent(&self) -> i64 {
+        self.exp
+    }
+
+    /// Converts the scanned literal to a decimal as [`FromStr`] does.
+    ///
+    /// `s` must be the string that was scanned, otherwise the result is unspecified, though a
+    /// string too short or with non-digits at the offsets gives [`DecimalParseError::Invalid`].
+    pub fn materialize(&self, s: &str) -> Result<Decimal, DecimalParseError> {
+        let digits = |range: Range<usize>| match s.as_bytes().get(range) {
+            Some(digits) if digits.iter().all(u8::is_ascii_digit) => Ok(digits),
+            _ => Err(DecimalParseError::Invalid),
+        };
+        let int = digits(self.integral())?;
+        if self.nan {
+            return to_nan(self.signaling, int);
+        }
+        to_decimal(self.negative, int, digits(self.fraction())?, self.exp)
+    }
+}
+
+impl Decimal {
+    /// Scans the syntax of `s` without converting it, returning the layout of its parts.
+    ///
+    /// `s` is accepted with the same grammar as [`FromStr`], and errors other than overflow are
+    /// detected. Literals longer than `u32::MAX` bytes are rejected with
+    /// [`DecimalParseError::Overflow`].
+    pub fn parse_layout(s: &str) -> Result<ParsedLayout, DecimalParseError> {
+        let input = s.as_bytes();
+        if input.len() > u32::MAX as usize {
+            return Err(DecimalParseError::Overflow);
+        }
+        let range = |part: &[u8]| {
+            let start = part.as_ptr() as usize - input.as_ptr() as usize;
+            (start as u32, (start + part.len()) as u32)
+        };
+
+        let layout = match scan(input)? {
+            Scanned::NaN { signaling, payload } => ParsedLayout {
+                nan: true,
+                signaling,
+                negative: false,
+                int: range(payload),
+                frac: (0, 0),
+                exp: 0,
+            },
+            Scanned::Number {
+                negative,
+                int,
+                frac,
+                exp,
+            } => ParsedLayout {
+                nan: false,
+                signaling: false,
+                negative,
+                int: range(int),
+                frac: range(frac),
+                exp,
+            },
+        };
+        Ok(layout)
+    }
+
+    /// Parses a decimal from a stream of chars, such as the output of a tokenizer after
+    /// unescaping, without first collecting it into a `String`.
+    ///
+    /// The chars are accepted with the same grammar as [`FromStr`]. They are buffered on the
+    /// stack, so only literals longer than 64 chars need a temporary string, and the stream is
+    /// consumed up to the first char that isn't ASCII, which is rejected.
+    pub fn from_chars<I: IntoIterator<Item = char>>(chars: I) -> Result<Decimal, DecimalParseError> {
+        let mut chars = chars.into_iter();
+        let mut inline = [0; INLINE_CHARS];
+        let mut len = 0;
+        while let Some(c) = chars.next() {
+            if !c.is_ascii() {
+                return Err(DecimalParseError::Invalid);
+            }
+            if len == INLINE_CHARS {
+                // Spills the buffer and the rest of the stream into a temporary string.
+                return with_string(|spill| {
+                    spill.extend(inline.iter().map(|&b| b as char));
+                    spill.push(c);
+                    for c in chars {
+                        if !c.is_ascii() {
+                            return Err(DecimalParseError::Invalid);
+                        }
+                        spill.push(c);
+                    }
+                    parse_bytes(spill.as_bytes())
+                });
+            }
+            inline[len] = c as u8;
+            len += 1;
+        }
+        parse_bytes(&inline[..len])
+    }
+}
+
+/// Number of chars buffered on the stack by [`Decimal::from_chars`].
+const INLINE_CHARS: usize = 64;
+
+/// Parses the grammar of [`FromStr`].
+#[inline]
+fn parse_bytes(input: &[u8]) -> Result<Decimal, DecimalParseError> {
+    match scan(input)? {
+        Scanned::NaN { signaling, payload } => to_nan(signaling, payload),
+        Scanned::Number {
+            negative,
+            int,
+            frac,
+            exp,
+        } => to_decimal(negative, int, frac, exp),
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = DecimalParseError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_bytes(s.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decimal, DecimalParseError, ParseOptions};
+
+    fn roundtrip(s: &str) -> String {
+        s.parse::<Decimal>().unwrap().to_string()
+    }
+
+    #[test]
+    fn roundtrip_limb_boundaries() {
+        for s in [
+            "0.00000001",
+            "0.000000001",
+            "0.0000000001",
+            "99999999.99999999",
+            "999999999.999999999",
+            "1000000000",
+            "1000000000.000000001",
+            "123456789.123456789",
+            "123456789012345678.123456789012345678",
+            "999999999999999999999999999999999999",
+            "0.999999999999999999999999999999999999",
+        ] {
+            assert_eq!(roundtrip(s), s);
+            let negative = format!("-{}", s);
+            assert_eq!(roundtrip(&negative), negative);
+        }
+    }
+
+    #[test]
+    fn roundtrip_zeros() {
+        assert_eq!(roundtrip("000123.4500"), "123.4500");
+        assert_eq!(roundtrip("0.000000000"), "0.000000000");
+        assert_eq!(roundtrip(".5"), "0.5");
+        assert_eq!(roundtrip("+7"), "7");
+        assert_eq!(roundtrip("1.5e-3"), "0.0015");
+        assert_eq!(roundtrip("-.5e2"), "-50");
+        assert_eq!(roundtrip("1E+5"), "100000");
+    }
+
+    #[test]
+    fn implied_zeros() {
+        assert_eq!(roundtrip("1e44"), format!("1{}", "0".repeat(44)));
+        assert_eq!(roundtrip("-1.5e40"), format!("-15{}", "0".repeat(39)));
+        assert_eq!(
+            roundtrip("1234567890123456789012345678901234567890.5"),
+            "1234567890123456789012345678901234570000"
+        );
+        assert_eq!(roundtrip(&"9".repeat(38)), format!("1{}", "0".repeat(38)));
+        assert_eq!(roundtrip(&"9".repeat(44)), format!("1{}", "0".repeat(44)));
+        assert_eq!("9".repeat(45).parse::<Decimal>(), Err(DecimalParseError::Overflow));
+        let max = format!("{}{}", "9".repeat(36), "0".repeat(9));
+        assert_eq!(max.parse::<Decimal>().unwrap(), Decimal::MAX);
+        let d: Decimal = "123e40".parse().unwrap();
+        assert!(d.is_internally_consistent());
+        assert_eq!(d.scale(), 0);
+
+        let exact = ParseOptions::new().rounding(None);
+        assert_eq!(
+            Decimal::parse_with("1234567890123456789012345678901234567890", &exact),
+            Err(DecimalParseError::Overflow)
+        );
+        assert_eq!(
+            Decimal::parse_with("1234567890123456789012345678901234560000", &exact)
+                .unwrap()
+                .to_string(),
+            "1234567890123456789012345678901234560000"
+        );
+    }
+
+    #[test]
+    fn negative_zero_is_zero() {
+        assert_eq!(roundtrip("-0"), "0");
+        assert_eq!(roundtrip("-0.00"), "0.00");
+        assert!(!"-0".parse::<Decimal>().unwrap().is_sign_negative());
+    }
+
+    #[test]
+    fn nan_payloads() {
+        assert_eq!(roundtrip("nan"), "NaN");
+        assert_eq!(roundtrip("NAN"), "NaN");
+        assert_eq!(roundtrip("snan"), "sNaN");
+        assert_eq!(roundtrip("sNaN12"), "sNaN12");
+        assert_eq!(roundtrip("nan007"), "NaN7");
+        assert_eq!("-nan".parse::<Decimal>(), Err(DecimalParseError::Invalid));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!("".parse::<Decimal>(), Err(DecimalParseError::Empty));
+        assert_eq!("abc".parse::<Decimal>(), Err(DecimalParseError::Invalid));
+        assert_eq!("1e45".parse::<Decimal>(), Err(DecimalParseError::Overflow));
+        assert_eq!(
+            format!("{}.5", "9".repeat(45)).parse::<Decimal>(),
+            Err(DecimalParseError::Overflow)
+        );
+        assert_eq!(roundtrip("1e35"), format!("1{}", "0".repeat(35)));
+
+        let options = ParseOptions::new().max_length(Some(5));
+        assert_eq!(Decimal::parse_with("123456", &options), Err(DecimalParseError::TooLong));
+        assert_eq!(Decimal::parse_with("1e99", &options), Err(DecimalParseError::Overflow));
+        let options = ParseOptions::new().max_digits(Some(3));
+        assert_eq!(Decimal::parse_with("1.234", &options), Err(DecimalParseError::TooLong));
+        assert_eq!(Decimal::parse_with("1.23", &options).unwrap().to_string(), "1.23");
+    }
+}
@@ -0,0 +1,134 @@
+//! Percentages.
+
+use crate::decimal::Decimal;
+use crate::error::DecimalParseError;
+use std::fmt;
+use std::ops::Mul;
+use std::str::FromStr;
+
+/// A percentage, stored as the exact fraction it stands for, so `12.5%` is `0.125`.
+///
+/// Keeping percentages apart from plain decimals makes the conversion explicit, so a rate is
+/// never multiplied or divided by 100 twice. Multiplying a decimal by a percentage applies the
+/// fraction, e.g. `200 * 12.5%` is `25.0000`. Equality, order and hashing are those of the
+/// fraction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Percent(Decimal);
+
+impl Percent {
+    /// Creates a percentage from a fraction, e.g. `0.125` for `12.5%`.
+    #[inline]
+    pub const fn from_fraction(fraction: Decimal) -> Percent {
+        Percent(fraction)
+    }
+
+    /// Creates a percentage from its number of percent, e.g. `12.5` for `12.5%`.
+    ///
+    /// Returns `None` if the fraction would need more than `MAX_SCALE` digits after the decimal
+    /// point.
+    #[inline]
+    pub fn from_percent(percent: Decimal) -> Option<Percent> {
+        percent.checked_mul_pow10(-2).map(Percent)
+    }
+
+    /// Returns the fraction, e.g. `0.125` for `12.5%`.
+    #[inline]
+    pub const fn fraction(&self) -> Decimal {
+        self.0
+    }
+
+    /// Returns the number of percent, e.g. `12.5` for `12.5%`, or `None` if it doesn't fit in a
+    /// decimal.
+    #[inline]
+    pub fn percent(&self) -> Option<Decimal> {
+        self.0.checked_mul_pow10(2)
+    }
+}
+
+impl From<Percent> for Decimal {
+    /// Returns the fraction.
+    #[inline]
+    fn from(percent: Percent) -> Decimal {
+        percent.0
+    }
+}
+
+impl Mul<Percent> for Decimal {
+    type Output = Decimal;
+
+    /// Multiplies by the fraction, as [`Decimal`] multiplication.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product overflows.
+    #[inline]
+    fn mul(self, percent: Percent) -> Decimal {
+        self * percent.0
+    }
+}
+
+impl Mul<Decimal> for Percent {
+    type Output = Decimal;
+
+    /// Multiplies the fraction, as [`Decimal`] multiplication.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the product overflows.
+    #[inline]
+    fn mul(self, amount: Decimal) -> Decimal {
+        self.0 * amount
+    }
+}
+
+impl FromStr for Percent {
+    type Err = DecimalParseError;
+
+    /// Parses a number of percent followed by `%`, e.g. `12.5%`, as [`Decimal`] parses the
+    /// number.
+    ///
+    /// Returns [`DecimalParseError::Invalid`] without the `%`, and
+    /// [`DecimalParseError::Overflow`] if the fraction needs more than `MAX_SCALE` digits after
+    /// the decimal point.
+    fn from_str(s: &str) -> Result<Percent, DecimalParseError> {
+        let number = match s.strip_suffix('%') {
+            Some(number) => number,
+            None if s.is_empty() => return Err(DecimalParseError::Empty),
+            None => return Err(DecimalParseError::Invalid),
+        };
+        let percent = number.parse::<Decimal>()?;
+        Percent::from_percent(percent).ok_or(DecimalParseError::Overflow)
+    }
+}
+
+impl fmt::Display for Percent {
+    /// Displays the number of percent followed by `%`, e.g. `12.5%`, with the scale of the
+    /// fraction less 2. NaN is displayed as `NaN%`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fraction = self.0.to_string();
+        if self.0.is_nan() {
+            return f.pad(&format!("{}%", fraction));
+        }
+
+        // Moves the decimal point of the fraction two digits to the right.
+        let (sign, digits) = match fraction.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", fraction.as_str()),
+        };
+        let (int, frac) = match digits.find('.') {
+            Some(i) => (&digits[..i], &digits[i + 1..]),
+            None => (digits, ""),
+        };
+        let moved = frac.len().min(2);
+        let mut int = format!("{}{}{}", int, &frac[..moved], "0".repeat(2 - moved));
+        let leading_zeros = int.bytes().take_while(|&c| c == b'0').count().min(int.len() - 1);
+        int.drain(..leading_zeros);
+
+        let frac = &frac[moved..];
+        if frac.is_empty() {
+            f.pad(&format!("{}{}%", sign, int))
+        } else {
+            f.pad(&format!("{}{}.{}%", sign, int, frac))
+        }
+    }
+}
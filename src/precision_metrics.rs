@@ -0,0 +1,160 @@
+//! Metrics on precision loss.
+//!
+//! Available with the `precision-metrics` feature, so production services can alert on
+//! unexpected precision loss without auditing every call site. Every result of arithmetic,
+//! division, parsing and rescaling that is rounded to fit in a decimal or clamped to its range
+//! is counted in global relaxed atomics and passed to the callback set by [`set_callback`].
+//!
+//! Operations with a [`Context`](crate::Context) report their losses in a
+//! [`Status`](crate::Status) instead, and mathematical functions, whose results are rarely
+//! exact, are not recorded.
+
+use crate::decimal::Decimal;
+use crate::var::Var;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// The kind of an operation that lost precision.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Operation {
+    /// Addition, as by `+`, `checked_add`, `overflowing_add` and sums.
+    Add,
+    /// Subtraction, as by `-`, `checked_sub` and `overflowing_sub`.
+    Sub,
+    /// Multiplication, as by `*`, `checked_mul`, `overflowing_mul` and products.
+    Mul,
+    /// [`Decimal::mul_add`].
+    MulAdd,
+    /// Division, as by `/`, `checked_div`, `div_with_scale` and `ratio`.
+    Div,
+    /// Parsing of literals with more digits than fit, or out of range with saturation.
+    Parse,
+    /// Rounding to a scale, as by `with_scale`, `quantize` and `trunc_with_scale`.
+    Rescale,
+}
+
+/// A result that lost precision, passed to the callback set by [`set_callback`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PrecisionLoss {
+    /// The operation.
+    pub operation: Operation,
+    /// Number of digits rounded away after the last kept digit, up to the last non-zero digit
+    /// of the exact result. Quotients are only computed to a guard digit, so for them it is a
+    /// lower bound. Zero for clamped results.
+    pub digits_lost: u32,
+    /// Whether the integral part overflowed and the result was clamped to [`Decimal::MAX`] or
+    /// [`Decimal::MIN`].
+    pub clamped: bool,
+}
+
+static ROUNDED: AtomicU64 = AtomicU64::new(0);
+static CLAMPED: AtomicU64 = AtomicU64::new(0);
+static DIGITS_LOST: AtomicU64 = AtomicU64::new(0);
+/// The callback as a function pointer, or 0 for none.
+static CALLBACK: AtomicUsize = AtomicUsize::new(0);
+
+/// A snapshot of the counters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Counters {
+    /// Results rounded to fit in a decimal.
+    pub rounded: u64,
+    /// Results clamped to the range of a decimal.
+    pub clamped: u64,
+    /// Total digits lost by rounded results.
+    pub digits_lost: u64,
+}
+
+/// Returns the current values of the counters.
+pub fn counters() -> Counters {
+    Counters {
+        rounded: ROUNDED.load(Ordering::Relaxed),
+        clamped: CLAMPED.load(Ordering::Relaxed),
+        digits_lost: DIGITS_LOST.load(Ordering::Relaxed),
+    }
+}
+
+/// Resets all counters to zero.
+pub fn reset() {
+    for counter in [&ROUNDED, &CLAMPED, &DIGITS_LOST] {
+        counter.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Sets the function called on every loss, replacing the previous one, or removes it with
+/// `None`.
+///
+/// The callback runs synchronously on the thread of the operation, so it should be quick, such
+/// as incrementing a metric labeled by the operation. Losses in the callback itself are
+/// recorded as well.
+pub fn set_callback(callback: Option<fn(&PrecisionLoss)>) {
+    CALLBACK.store(callback.map_or(0, |f| f as usize), Ordering::Release);
+}
+
+/// Records a loss of `digits_lost` digits or a clamped result, ignoring exact results.
+pub(crate) fn record(operation: Operation, digits_lost: u32, clamped: bool) {
+    if clamped {
+        CLAMPED.fetch_add(1, Ordering::Relaxed);
+    } else if digits_lost > 0 {
+        ROUNDED.fetch_add(1, Ordering::Relaxed);
+        DIGITS_LOST.fetch_add(digits_lost as u64, Ordering::Relaxed);
+    } else {
+        return;
+    }
+
+    let callback = CALLBACK.load(Ordering::Acquire);
+    if callback != 0 {
+        // SAFETY: non-zero values are only stored from a `fn(&PrecisionLoss)` by `set_callback`.
+        let callback = unsafe { std::mem::transmute::<usize, fn(&PrecisionLoss)>(callback) };
+        callback(&PrecisionLoss {
+            operation,
+            digits_lost,
+            clamped,
+        });
+    }
+}
+
+/// Records the conversion of an exact result by [`Var::into_decimal`], ignoring an overflow.
+pub(crate) fn rounded(operation: Operation, exact: &Var, result: Option<&(Decimal, bool)>) {
+    if let Some(&(result, true)) = result {
+        record(operation, digits_below(exact, result.scale()), false);
+    }
+}
+
+/// Records the conversion of an exact result as [`rounded`], an overflow being clamped.
+pub(crate) fn overflowed(operation: Operation, exact: &Var, result: Option<&(Decimal, bool)>) {
+    match result {
+        Some(result) => rounded(operation, exact, Some(result)),
+        None => record(operation, 0, true),
+    }
+}
+
+/// Records a result rounded from `exact` if their values differ.
+pub(crate) fn rescaled(operation: Operation, exact: &Var, result: &Decimal) {
+    if !exact.is_nan() && !exact.sub(&Var::from_decimal(result)).is_zero() {
+        record(operation, digits_below(exact, result.scale()), false);
+    }
+}
+
+/// Records a quotient returned with its number of lost digits.
+pub(crate) fn divided(operation: Operation, quotient: Option<&(Decimal, u32)>) {
+    if let Some(&(_, digits_lost)) = quotient {
+        record(operation, digits_lost, false);
+    }
+}
+
+/// Records a parsed literal whose significant digits from index `keep` on were rounded away.
+pub(crate) fn parsed(operation: Operation, int: &[u8], frac: &[u8], keep: i32, inexact: bool) {
+    if inexact {
+        let digits = || int.iter().chain(frac);
+        let leading_zeros = digits().take_while(|&&c| c == b'0').count();
+        let trailing_zeros = digits().rev().take_while(|&&c| c == b'0').count();
+        let significant = (int.len() + frac.len()).saturating_sub(leading_zeros + trailing_zeros);
+        record(operation, (significant as i32 - keep).max(1) as u32, false);
+    }
+}
+
+/// Returns the number of digits of `exact` beyond `scale`, at least 1.
+#[inline]
+fn digits_below(exact: &Var, scale: i32) -> u32 {
+    (exact.min_scale() - scale).max(1) as u32
+}
@@ -0,0 +1,446 @@
+//! Rounding strategies.
+
+use crate::decimal::{Decimal, MAX_NDIGITS, MAX_PRECISION, MAX_SCALE, NBASE};
+use crate::error::DecimalError;
+use crate::var::Var;
+use std::cmp::Ordering;
+
+/// Strategy for rounding away discarded digits.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingStrategy {
+    /// Rounds to the nearest neighbor, ties away from zero.
+    HalfUp,
+    /// Rounds to the nearest neighbor, ties towards zero.
+    HalfDown,
+    /// Rounds to the nearest neighbor, ties to the even neighbor, also known as banker's rounding.
+    HalfEven,
+    /// Rounds away from zero.
+    Up,
+    /// Rounds towards zero, truncating the discarded digits.
+    Down,
+    /// Rounds towards negative infinity.
+    Floor,
+    /// Rounds towards positive infinity.
+    Ceiling,
+}
+
+impl RoundingStrategy {
+    /// Returns `true` if the magnitude of the kept digits should be incremented.
+    ///
+    /// `half` compares the discarded digits with half a unit of the last kept digit, and is
+    /// only meaningful when `inexact` is `true`.
+    #[inline]
+    pub(crate) fn round_up(self, negative: bool, odd: bool, half: Ordering, inexact: bool) -> bool {
+        if !inexact {
+            return false;
+        }
+
+        match self {
+            RoundingStrategy::HalfUp => half != Ordering::Less,
+            RoundingStrategy::HalfDown => half == Ordering::Greater,
+            RoundingStrategy::HalfEven => half == Ordering::Greater || (half == Ordering::Equal && odd),
+            RoundingStrategy::Up => true,
+            RoundingStrategy::Down => false,
+            RoundingStrategy::Floor => negative,
+            RoundingStrategy::Ceiling => !negative,
+        }
+    }
+
+    /// Rounds a buffer of decimal digits in `0..=9`, most significant first, to its first
+    /// `keep` digits, for algorithms producing digits one at a time such as long division.
+    ///
+    /// `negative` is the sign of the number, which directed strategies need. The discarded
+    /// digits `digits[keep..]` are set to zero, and the kept digits are incremented with carry
+    /// propagation when the strategy rounds up. Returns `(inexact, carried)`: `inexact` tells
+    /// that a discarded digit was non-zero, and `carried` that the increment carried out of
+    /// the first digit, so the kept digits became `1` followed by zeros and the number gained
+    /// one digit of magnitude, which the caller accounts for by shifting its exponent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `keep` is zero or greater than the length of `digits`.
+    pub fn round_digits(self, digits: &mut [u8], keep: usize, negative: bool) -> (bool, bool) {
+        debug_assert!(digits.iter().all(|&d| d <= 9));
+        self.round_limbs(digits, keep, 1, 10, negative, false)
+    }
+
+    /// Rounds limbs in base `base`, most significant first, to the multiples of `unit` in the
+    /// first `keep` limbs, as [`round_digits`](RoundingStrategy::round_digits) does for digits.
+    ///
+    /// `unit` divides `base`, and `sticky` tells that non-zero digits were already discarded
+    /// beyond the limbs. Returns `(inexact, carried)` as `round_digits`.
+    pub(crate) fn round_limbs<L: Limb>(
+        self,
+        limbs: &mut [L],
+        keep: usize,
+        unit: u32,
+        base: u32,
+        negative: bool,
+        sticky: bool,
+    ) -> (bool, bool) {
+        assert!(keep > 0 && keep <= limbs.len(), "rounding position out of bounds");
+
+        let (kept, discarded) = limbs.split_at_mut(keep);
+        let last = kept[keep - 1].get();
+        let rest = discarded.iter().any(|l| l.get() != 0) || sticky;
+        // The discarded value at the position below the last kept unit, compared with half.
+        let (next, rest, half_unit) = if unit > 1 {
+            (last % unit, rest, unit / 2)
+        } else {
+            let rest = discarded.iter().skip(1).any(|l| l.get() != 0) || sticky;
+            (discarded.first().map_or(0, |l| l.get()), rest, base / 2)
+        };
+        let inexact = next != 0 || rest;
+        let half = next
+            .cmp(&half_unit)
+            .then(if rest { Ordering::Greater } else { Ordering::Equal });
+        let odd = (last / unit) % 2 == 1;
+        kept[keep - 1] = L::new(last - last % unit);
+        for l in discarded.iter_mut() {
+            *l = L::new(0);
+        }
+        if !self.round_up(negative, odd, half, inexact) {
+            return (inexact, false);
+        }
+
+        let mut carry = unit;
+        for l in kept.iter_mut().rev() {
+            let sum = l.get() + carry;
+            if sum < base {
+                *l = L::new(sum);
+                return (inexact, false);
+            }
+            *l = L::new(sum - base);
+            carry = 1;
+        }
+        kept[0] = L::new(1);
+        (inexact, true)
+    }
+}
+
+/// A digit or limb rounded by [`RoundingStrategy::round_limbs`].
+pub(crate) trait Limb: Copy {
+    fn get(self) -> u32;
+    fn new(value: u32) -> Self;
+}
+
+impl Limb for u8 {
+    #[inline]
+    fn get(self) -> u32 {
+        self as u32
+    }
+
+    #[inline]
+    fn new(value: u32) -> Self {
+        value as u8
+    }
+}
+
+impl Limb for u32 {
+    #[inline]
+    fn get(self) -> u32 {
+        self
+    }
+
+    #[inline]
+    fn new(value: u32) -> Self {
+        value
+    }
+}
+
+impl Decimal {
+    /// Rounds or pads to the scale of `exp` with `strategy`, e.g. quantizing to `0.01` gives
+    /// two digits after the decimal point, as Python's `Decimal.quantize`.
+    ///
+    /// The value of `exp` is ignored. Returns [`DecimalError::Overflow`] if the result would need
+    /// more than `MAX_PRECISION` digits at that scale. NaN operands give NaN.
+    pub fn quantize(&self, exp: &Decimal, strategy: RoundingStrategy) -> Result<Decimal, DecimalError> {
+        let var = Var::from_decimal(self);
+        // The scale of a decimal is always valid, so only the precision can overflow.
+        match var.nan_operand(&Var::from_decimal(exp)) {
+            Some(nan) => rescaled(nan, 0, strategy),
+            None => rescaled(var, exp.scale(), strategy),
+        }
+    }
+
+    /// Rounds half up or pads with zeros to `scale` digits after the decimal point in place.
+    ///
+    /// A negative `scale` rounds to tens, hundreds, and so on, with scale 0. Leaves the decimal
+    /// unchanged and returns [`DecimalError::InvalidScale`] if `scale` exceeds `MAX_SCALE`, or
+    /// [`DecimalError::Overflow`] if the result would need more than `MAX_PRECISION` digits. NaN
+    /// is unchanged.
+    #[inline]
+    pub fn rescale(&mut self, scale: i32) -> Result<(), DecimalError> {
+        *self = self.with_scale(scale)?;
+        Ok(())
+    }
+
+    /// Returns the decimal rounded half up or padded with zeros to `scale` digits after the
+    /// decimal point, as [`rescale`](Decimal::rescale).
+    #[inline]
+    pub fn with_scale(self, scale: i32) -> Result<Decimal, DecimalError> {
+        rescaled(Var::from_decimal(&self), scale, RoundingStrategy::HalfUp)
+    }
+
+    /// Returns the decimal with digits beyond `scale` places after the decimal point chopped
+    /// off, rounding towards zero, e.g. `2.789` truncated to 2 places is `2.78`.
+    ///
+    /// A negative `scale` chops tens, hundreds, and so on, with scale 0. Decimals with at most
+    /// `scale` digits after the decimal point, and NaN, are returned unchanged.
+    #[inline]
+    pub fn trunc_with_scale(&self, scale: i32) -> Decimal {
+        if scale >= self.scale() {
+            return *self;
+        }
+        // Dropping digits never needs more precision.
+        rescaled(Var::from_decimal(self), scale, RoundingStrategy::Down).unwrap()
+    }
+
+    /// Returns the integral part, rounding towards zero, with scale 0.
+    #[inline]
+    pub fn trunc(&self) -> Decimal {
+        self.integral_part(false)
+    }
+
+    /// Returns the largest integer less than or equal to this decimal, with scale 0.
+    #[inline]
+    pub fn floor(&self) -> Decimal {
+        self.integral_part(self.is_sign_negative())
+    }
+
+    /// Returns the smallest integer greater than or equal to this decimal, with scale 0.
+    #[inline]
+    pub fn ceil(&self) -> Decimal {
+        self.integral_part(self.is_sign_positive())
+    }
+
+    /// Returns the fractional part, `self - self.trunc()`, keeping the sign and scale.
+    ///
+    /// NaN is unchanged, and a zero fractional part is positive.
+    pub fn fract(&self) -> Decimal {
+        if self.is_nan() {
+            return *self;
+        }
+
+        let limbs = self.limbs();
+        let int_len = (self.weight as i32 + 1).clamp(0, limbs.len() as i32) as usize;
+        let first = match limbs[int_len..].iter().position(|&l| l != 0) {
+            Some(p) => int_len + p,
+            None => {
+                return Decimal {
+                    dscale: self.dscale,
+                    ..Decimal::ZERO
+                }
+            }
+        };
+
+        let mut digits = [0; MAX_NDIGITS];
+        digits[..limbs.len() - first].copy_from_slice(&limbs[first..]);
+        Decimal {
+            ndigits: (limbs.len() - first) as u8,
+            sign: self.sign,
+            weight: self.weight - first as i8,
+            dscale: self.dscale,
+            digits,
+        }
+    }
+
+    /// Returns the integral part, with its magnitude incremented if `increment` is `true` and
+    /// there is a non-zero fractional part.
+    ///
+    /// A non-zero fractional part leaves at most `MAX_PRECISION - 1` integral digits, so the
+    /// increment never overflows.
+    fn integral_part(&self, increment: bool) -> Decimal {
+        if self.is_nan() {
+            return *self;
+        }
+
+        let limbs = self.limbs();
+        let int_len = (self.weight as i32 + 1).max(0) as usize;
+        let mut digits = [0; MAX_NDIGITS];
+        for (i, digit) in digits[..int_len].iter_mut().enumerate() {
+            *digit = limbs.get(i).copied().unwrap_or(0);
+        }
+        let mut n = int_len;
+        let mut weight = self.weight as i32;
+
+        if increment && limbs.len() > int_len {
+            let mut carry = true;
+            for digit in digits[..n].iter_mut().rev() {
+                *digit += 1;
+                if *digit < NBASE {
+                    carry = false;
+                    break;
+                }
+                *digit = 0;
+            }
+            if carry {
+                debug_assert!(n < MAX_NDIGITS - 1);
+                digits.copy_within(..n, 1);
+                digits[0] = 1;
+                n += 1;
+                weight = n as i32 - 1;
+            }
+        }
+
+        while n > 0 && digits[n - 1] == 0 {
+            n -= 1;
+        }
+        if n == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal {
+            ndigits: n as u8,
+            sign: self.sign,
+            weight: weight as i8,
+            dscale: 0,
+            digits,
+        }
+    }
+}
+
+/// Rounds to `scale` digits after the decimal point, failing if the result doesn't fit at that
+/// scale exactly.
+pub(crate) fn rescaled(mut var: Var, scale: i32, strategy: RoundingStrategy) -> Result<Decimal, DecimalError> {
+    if var.is_nan() {
+        let (nan, _) = var.into_decimal(strategy).unwrap();
+        return Ok(nan);
+    }
+    if scale > MAX_SCALE {
+        return Err(DecimalError::InvalidScale);
+    }
+
+    #[cfg(feature = "precision-metrics")]
+    let exact = var;
+    var.round(scale, strategy, false);
+    if scale > 0 && !var.is_zero() && var.exponent() + scale > MAX_PRECISION as i32 {
+        return Err(DecimalError::Overflow);
+    }
+    // An integer beyond `MAX_PRECISION` digits is rounded again if its digits don't fit.
+    let (result, inexact) = var.into_decimal(strategy).ok_or(DecimalError::Overflow)?;
+    if inexact {
+        return Err(DecimalError::Overflow);
+    }
+    record_loss!(rescaled(Rescale, &exact, &result));
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Decimal, DecimalError, RoundingStrategy, MAX_SCALE};
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn rescale() {
+        let mut d = dec("1.005");
+        assert_eq!(d.rescale(2), Ok(()));
+        assert_eq!(d.to_string(), "1.01");
+        assert_eq!(d.rescale(4), Ok(()));
+        assert_eq!(d.to_string(), "1.0100");
+        assert_eq!(dec("1250").with_scale(-2).unwrap().to_string(), "1300");
+        assert_eq!(dec("NaN").with_scale(2).unwrap().to_string(), "NaN");
+    }
+
+    #[test]
+    fn rescale_errors() {
+        let mut d = dec("1.5");
+        assert_eq!(d.rescale(MAX_SCALE + 1), Err(DecimalError::InvalidScale));
+        assert_eq!(d.to_string(), "1.5");
+
+        let mut d = dec("1e35");
+        assert_eq!(d.rescale(2), Err(DecimalError::Overflow));
+        assert_eq!(d.to_string(), "100000000000000000000000000000000000");
+        assert_eq!(dec("1e-100").with_scale(MAX_SCALE).map(|d| d.scale()), Ok(MAX_SCALE));
+    }
+
+    #[test]
+    fn quantize() {
+        use RoundingStrategy::*;
+
+        let cent = dec("0.01");
+        assert_eq!(dec("2.675").quantize(&cent, HalfEven).unwrap().to_string(), "2.68");
+        assert_eq!(dec("2.665").quantize(&cent, HalfEven).unwrap().to_string(), "2.66");
+        assert_eq!(dec("-2.675").quantize(&cent, Floor).unwrap().to_string(), "-2.68");
+        assert_eq!(dec("7").quantize(&dec("123.000"), Down).unwrap().to_string(), "7.000");
+        // Integers have scale 0, whatever their exponent.
+        assert_eq!(dec("1250.5").quantize(&dec("1e2"), HalfUp).unwrap().to_string(), "1251");
+        assert_eq!(dec("0.004").quantize(&cent, Down).unwrap().to_string(), "0.00");
+
+        // NaN operands give NaN, keeping the payload of the first.
+        assert_eq!(dec("NaN5").quantize(&cent, Up).unwrap().to_string(), "NaN5");
+        assert_eq!(dec("1.5").quantize(&dec("NaN3"), Up).unwrap().to_string(), "NaN3");
+
+        assert_eq!(dec("1e35").quantize(&cent, Up), Err(DecimalError::Overflow));
+        assert_eq!(
+            dec("1e-100").quantize(&dec("1e-130"), Up).map(|d| d.scale()),
+            Ok(MAX_SCALE)
+        );
+    }
+
+    fn round_digits(strategy: RoundingStrategy, digits: &str, keep: usize, negative: bool) -> (String, bool, bool) {
+        let mut buf: Vec<u8> = digits.bytes().map(|c| c - b'0').collect();
+        let (inexact, carried) = strategy.round_digits(&mut buf, keep, negative);
+        let digits = buf.iter().map(|&d| (b'0' + d) as char).collect();
+        (digits, inexact, carried)
+    }
+
+    #[test]
+    fn round_digits_strategies() {
+        use RoundingStrategy::*;
+
+        // Digits `12|5`, `12|51`, `13|5` and `12|49`, positive and negative.
+        let cases = [
+            (HalfUp, ["130", "1300", "140", "1200"], ["130", "1300", "140", "1200"]),
+            (HalfDown, ["120", "1300", "130", "1200"], ["120", "1300", "130", "1200"]),
+            (HalfEven, ["120", "1300", "140", "1200"], ["120", "1300", "140", "1200"]),
+            (Up, ["130", "1300", "140", "1300"], ["130", "1300", "140", "1300"]),
+            (Down, ["120", "1200", "130", "1200"], ["120", "1200", "130", "1200"]),
+            (Floor, ["120", "1200", "130", "1200"], ["130", "1300", "140", "1300"]),
+            (Ceiling, ["130", "1300", "140", "1300"], ["120", "1200", "130", "1200"]),
+        ];
+        for (strategy, positive, negative) in cases {
+            for (digits, (p, n)) in ["125", "1251", "135", "1249"].iter().zip(positive.iter().zip(negative)) {
+                assert_eq!(
+                    round_digits(strategy, digits, 2, false).0,
+                    *p,
+                    "{:?} {}",
+                    strategy,
+                    digits
+                );
+                assert_eq!(
+                    round_digits(strategy, digits, 2, true).0,
+                    n,
+                    "-{:?} {}",
+                    strategy,
+                    digits
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn round_digits_flags() {
+        use RoundingStrategy::*;
+
+        assert_eq!(round_digits(Up, "1200", 2, false), ("1200".to_string(), false, false));
+        assert_eq!(round_digits(Down, "1201", 2, false), ("1200".to_string(), true, false));
+        assert_eq!(round_digits(HalfUp, "12", 2, false), ("12".to_string(), false, false));
+        assert_eq!(round_digits(HalfUp, "9951", 2, false), ("1000".to_string(), true, true));
+        assert_eq!(round_digits(Floor, "9901", 2, true), ("1000".to_string(), true, true));
+        assert_eq!(
+            round_digits(Ceiling, "9901", 2, true),
+            ("9900".to_string(), true, false)
+        );
+        assert_eq!(round_digits(HalfEven, "95", 1, false), ("10".to_string(), true, true));
+        assert_eq!(round_digits(HalfEven, "85", 1, false), ("80".to_string(), true, false));
+    }
+
+    #[test]
+    #[should_panic(expected = "rounding position out of bounds")]
+    fn round_digits_keeps_a_digit() {
+        RoundingStrategy::Up.round_digits(&mut [5], 0, false);
+    }
+}
@@ -0,0 +1,78 @@
+//! Round-trip assertions for code that stores or transmits decimals.
+//!
+//! Available with the `test_support` feature. The assertions panic like [`assert_eq!`], so they
+//! can be called from tests with values from [`DecimalGenerator`](crate::test_support::DecimalGenerator)
+//! or any other source.
+
+use crate::decimal::Decimal;
+use crate::options::ParseOptions;
+use std::cmp::Ordering;
+
+/// Asserts that `d` is unchanged by formatting and parsing back, with both [`Display`] and
+/// [`Decimal::to_machine_string`].
+///
+/// Unchanged means the same value, scale, and NaN payload.
+///
+/// [`Display`]: std::fmt::Display
+#[track_caller]
+pub fn assert_format_roundtrip(d: &Decimal) {
+    let s = d.to_string();
+    let parsed = s
+        .parse::<Decimal>()
+        .unwrap_or_else(|e| panic!("formatted `{}` failed to parse: {}", s, e));
+    assert_identical(d, &parsed, "formatting and parsing");
+
+    let s = d.to_machine_string();
+    let parsed = Decimal::parse_with(&s, &ParseOptions::STRICT)
+        .unwrap_or_else(|e| panic!("machine string `{}` failed to parse: {}", s, e));
+    assert_identical(d, &parsed, "machine formatting and strict parsing");
+}
+
+/// Asserts that formatting the decimal parsed from `s` gives an equivalent literal.
+///
+/// The formatted literal must have the same value as `s` compared digit by digit, unless `s` had
+/// to be rounded to fit, and must parse back to the same decimal. Literals that fail to parse are
+/// ignored, so arbitrary strings can be passed.
+#[track_caller]
+pub fn assert_parse_roundtrip(s: &str) {
+    let d = match s.parse::<Decimal>() {
+        Ok(d) => d,
+        Err(_) => return,
+    };
+
+    let formatted = d.to_string();
+    let exact = Decimal::parse_with(s, &ParseOptions::new().rounding(None)).is_ok();
+    if exact {
+        assert!(
+            d.cmp_str(s) == Ok(Ordering::Equal),
+            "`{}` was parsed and formatted as `{}` with a different value",
+            s,
+            formatted
+        );
+    }
+    assert_format_roundtrip(&d);
+}
+
+/// Asserts that `d` is unchanged by an encoder and decoder pair, such as the bind and fetch of a
+/// database driver or the write and read of a serializer.
+#[track_caller]
+pub fn assert_roundtrip_with<T, E, D>(d: &Decimal, encode: E, decode: D)
+where
+    E: FnOnce(&Decimal) -> T,
+    D: FnOnce(T) -> Decimal,
+{
+    let decoded = decode(encode(d));
+    assert_identical(d, &decoded, "encoding and decoding");
+}
+
+#[track_caller]
+fn assert_identical(expected: &Decimal, actual: &Decimal, what: &str) {
+    // The display form shows the sign, scale, and NaN payload, so it identifies the value.
+    assert!(
+        expected == actual && expected.to_string() == actual.to_string(),
+        "{} changed `{}` to `{}`",
+        what,
+        expected,
+        actual
+    );
+}
@@ -0,0 +1,60 @@
+//! Scaled integer interchange type.
+
+use crate::decimal::{Decimal, MAX_PRECISION, MAX_SCALE};
+use crate::error::DecimalConvertError;
+use std::convert::TryFrom;
+
+/// A number represented as `value * 10^-scale`.
+///
+/// This is the "integer with a scale" layout used by columnar formats and databases. Every
+/// decimal except NaN and integers beyond the range of `i128` converts to it exactly, and it
+/// converts back to a decimal if `value` has at most `MAX_PRECISION` digits and `scale` is at
+/// most `MAX_SCALE`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ScaledI128 {
+    /// The unscaled value.
+    pub value: i128,
+    /// The number of digits after the decimal point.
+    pub scale: u8,
+}
+
+impl ScaledI128 {
+    /// Creates a scaled integer.
+    #[inline]
+    pub const fn new(value: i128, scale: u8) -> ScaledI128 {
+        ScaledI128 { value, scale }
+    }
+}
+
+impl TryFrom<Decimal> for ScaledI128 {
+    type Error = DecimalConvertError;
+
+    #[inline]
+    fn try_from(d: Decimal) -> Result<Self, Self::Error> {
+        if d.is_nan() {
+            return Err(DecimalConvertError::NaN);
+        }
+
+        let value = d
+            .coefficient()
+            .and_then(|c| i128::try_from(c).ok())
+            .ok_or(DecimalConvertError::Overflow)?;
+        Ok(ScaledI128 {
+            value: if d.is_sign_negative() { -value } else { value },
+            scale: d.scale() as u8,
+        })
+    }
+}
+
+impl TryFrom<ScaledI128> for Decimal {
+    type Error = DecimalConvertError;
+
+    #[inline]
+    fn try_from(s: ScaledI128) -> Result<Self, Self::Error> {
+        let abs = s.value.unsigned_abs();
+        if s.scale as i32 > MAX_SCALE || abs >= 10u128.pow(MAX_PRECISION) {
+            return Err(DecimalConvertError::Overflow);
+        }
+        Ok(Decimal::from_coefficient(s.value < 0, abs, s.scale as i32))
+    }
+}
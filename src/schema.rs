@@ -0,0 +1,55 @@
+//! Column metadata for persisted decimals.
+
+use crate::decimal::Decimal;
+use crate::validate::ValidationErrorKind;
+
+/// The declared type of a column of decimals, as SQL's `NUMERIC(precision, scale)`.
+///
+/// A schema is small enough to be sent alongside batches of encoded decimals, so the receiver
+/// can check each value with [`check`](DecimalSchema::check). With the `serde` feature, it
+/// implements `Serialize` and `Deserialize`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DecimalSchema {
+    /// Maximum number of digits.
+    pub precision: u32,
+    /// Maximum number of digits after the decimal point.
+    pub scale: i32,
+    /// Whether NaN is accepted.
+    pub allows_nan: bool,
+}
+
+impl DecimalSchema {
+    /// Creates a schema that rejects NaN.
+    #[inline]
+    pub const fn new(precision: u32, scale: i32) -> DecimalSchema {
+        DecimalSchema {
+            precision,
+            scale,
+            allows_nan: false,
+        }
+    }
+
+    /// Checks that `d` is stored exactly by a column of this schema.
+    ///
+    /// The scale of `d` must not exceed the scale of the schema, and its integral digits must
+    /// fit in `precision - scale` digits, so `999.99` fits `NUMERIC(5, 2)` but `1000` doesn't.
+    pub fn check(&self, d: &Decimal) -> Result<(), ValidationErrorKind> {
+        if d.is_nan() {
+            return if self.allows_nan {
+                Ok(())
+            } else {
+                Err(ValidationErrorKind::NaN)
+            };
+        }
+
+        if d.scale() > self.scale {
+            return Err(ValidationErrorKind::Scale);
+        }
+        let integral_digits = d.precision() as i64 - d.scale() as i64;
+        if integral_digits > self.precision as i64 - self.scale as i64 {
+            return Err(ValidationErrorKind::Precision);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+//! Scratch buffers reused across calls on a thread.
+
+#[cfg(feature = "scratch")]
+use std::cell::{Cell, RefCell};
+
+#[cfg(feature = "scratch")]
+thread_local! {
+    /// Number of nested [`with_scratch`] scopes on this thread.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    /// String lent to temporaries while a scope is open.
+    static STRING: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Calls `f` with an empty temporary string.
+///
+/// Inside [`with_scratch`], the string is the arena of the thread, so its capacity is reused
+/// from call to call. Otherwise, or when the arena is already lent, it is a new string.
+#[inline]
+pub(crate) fn with_string<R, F: FnOnce(&mut String) -> R>(f: F) -> R {
+    #[cfg(feature = "scratch")]
+    {
+        if DEPTH.with(Cell::get) > 0 {
+            return STRING.with(|string| match string.try_borrow_mut() {
+                Ok(mut string) => {
+                    string.clear();
+                    f(&mut string)
+                }
+                Err(_) => f(&mut String::new()),
+            });
+        }
+    }
+    f(&mut String::new())
+}
+
+/// Runs `f` with a scratch arena on this thread, so [`Decimal::parse_with`],
+/// [`Decimal::format_with`] and long literals in [`Decimal::from_chars`] in `f` reuse one buffer
+/// for their temporaries instead of allocating on every call.
+///
+/// Arithmetic, [`FromStr`] and [`Display`] don't allocate, so they don't use the arena.
+///
+/// Scopes nest, and the arena is freed when the outermost one ends, even if `f` panics.
+///
+/// [`Decimal::parse_with`]: crate::Decimal::parse_with
+/// [`Decimal::format_with`]: crate::Decimal::format_with
+/// [`Decimal::from_chars`]: crate::Decimal::from_chars
+/// [`FromStr`]: std::str::FromStr
+/// [`Display`]: std::fmt::Display
+#[cfg(feature = "scratch")]
+pub fn with_scratch<R, F: FnOnce() -> R>(f: F) -> R {
+    struct Exit;
+
+    impl Drop for Exit {
+        fn drop(&mut self) {
+            let depth = DEPTH.with(|depth| {
+                depth.set(depth.get() - 1);
+                depth.get()
+            });
+            if depth == 0 {
+                STRING.with(|string| *string.borrow_mut() = String::new());
+            }
+        }
+    }
+
+    DEPTH.with(|depth| depth.set(depth.get() + 1));
+    let _exit = Exit;
+    f()
+}
+
+#[cfg(all(test, feature = "scratch"))]
+mod tests {
+    use super::{with_scratch, with_string, DEPTH, STRING};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    /// Returns the address and capacity of the temporary string lent by `with_string`.
+    fn lent() -> (*const u8, usize) {
+        with_string(|s| {
+            assert!(s.is_empty());
+            (s.as_ptr(), s.capacity())
+        })
+    }
+
+    fn arena_capacity() -> usize {
+        STRING.with(|string| string.borrow().capacity())
+    }
+
+    #[test]
+    fn nested_scopes_reuse_the_buffer() {
+        assert_eq!(lent().1, 0);
+        with_scratch(|| {
+            let ptr = with_string(|s| {
+                s.push_str(&"9".repeat(100));
+                s.as_ptr()
+            });
+            assert_eq!(lent(), (ptr, arena_capacity()));
+            with_scratch(|| {
+                assert_eq!(lent(), (ptr, arena_capacity()));
+                // The arena is lent already, so a nested temporary is a new string.
+                with_string(|_| assert_eq!(lent().1, 0));
+            });
+            // Leaving the inner scope keeps the buffer.
+            assert!(arena_capacity() >= 100);
+            assert_eq!(lent().0, ptr);
+        });
+        assert_eq!(arena_capacity(), 0);
+        assert_eq!(DEPTH.with(|depth| depth.get()), 0);
+    }
+
+    #[test]
+    fn panics_release_the_buffer() {
+        with_scratch(|| {
+            with_string(|s| s.reserve(100));
+            let result = catch_unwind(AssertUnwindSafe(|| with_scratch(|| with_string(|_| panic!("inner")))));
+            assert!(result.is_err());
+            // The outer scope is still open and holds the buffer.
+            assert_eq!(DEPTH.with(|depth| depth.get()), 1);
+            assert!(arena_capacity() >= 100);
+        });
+
+        let result = catch_unwind(|| {
+            with_scratch(|| {
+                with_string(|s| s.reserve(100));
+                panic!("outer");
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(DEPTH.with(|depth| depth.get()), 0);
+        assert_eq!(arena_capacity(), 0);
+        assert_eq!(lent().1, 0);
+    }
+}
@@ -0,0 +1,66 @@
+//! Signs of decimals.
+
+use crate::decimal::{Decimal, SIGN_NEG, SIGN_POS};
+use std::ops::Neg;
+
+/// The sign of a decimal, zero counting as positive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sign {
+    /// Positive or zero.
+    Positive,
+    /// Negative.
+    Negative,
+}
+
+impl Neg for Sign {
+    type Output = Sign;
+
+    #[inline]
+    fn neg(self) -> Sign {
+        match self {
+            Sign::Positive => Sign::Negative,
+            Sign::Negative => Sign::Positive,
+        }
+    }
+}
+
+impl Decimal {
+    /// Creates a decimal from a sign and the magnitude of `magnitude`, whose own sign is
+    /// ignored.
+    ///
+    /// Zero stays positive whatever the sign, and a NaN magnitude is unchanged. This is the
+    /// inverse of [`decompose`](Decimal::decompose).
+    #[inline]
+    pub const fn compose(sign: Sign, magnitude: &Decimal) -> Decimal {
+        if magnitude.is_nan() || magnitude.is_zero() {
+            return *magnitude;
+        }
+        let sign = match sign {
+            Sign::Positive => SIGN_POS,
+            Sign::Negative => SIGN_NEG,
+        };
+        Decimal { sign, ..*magnitude }
+    }
+
+    /// Splits into the sign and the magnitude, which is never negative and keeps the scale.
+    ///
+    /// Zero is positive, and NaN gives [`Sign::Positive`] with the NaN unchanged.
+    #[inline]
+    pub const fn decompose(&self) -> (Sign, Decimal) {
+        if self.is_sign_negative() {
+            (Sign::Negative, self.abs())
+        } else {
+            (Sign::Positive, *self)
+        }
+    }
+
+    /// Returns the sign, zero and NaN counting as positive.
+    #[inline]
+    pub const fn sign(&self) -> Sign {
+        if self.is_sign_negative() {
+            Sign::Negative
+        } else {
+            Sign::Positive
+        }
+    }
+}
@@ -0,0 +1,293 @@
+//! Descriptive statistics over slices of decimals.
+//!
+//! Sums of values and of their squares are accumulated exactly, and quotients and roots keep
+//! 72 significant digits, so each statistic is rounded once, half up, to as many digits after
+//! the decimal point as fit. Exact results have only the digits they need, but at least the
+//! scale of the values, or twice that for variances.
+//!
+//! Empty slices and results that overflow, such as the variance of values near `MAX`, give
+//! `None`, and NaN in the values gives the first NaN.
+
+use crate::decimal::{Decimal, MAX_PRECISION, MAX_SCALE};
+use crate::math::{sqrt_var, WORK_DIGITS};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+
+/// Significant digits of an approximate square root checked for being exact.
+const EXACT_ROOT_DIGITS: i32 = WORK_DIGITS / 2 + 4;
+
+/// Returns the arithmetic mean.
+pub fn mean(values: &[Decimal]) -> Option<Decimal> {
+    if let Some(nan) = first_nan(values) {
+        return Some(nan);
+    }
+    if values.is_empty() {
+        return None;
+    }
+
+    let sum = values
+        .iter()
+        .fold(Var::ZERO, |sum, value| sum.add(&Var::from_decimal(value)));
+    let (mean, inexact) = div_sticky(&sum, &Var::from_i64(values.len() as i64));
+    finish(mean, inexact, max_scale(values))
+}
+
+/// Returns the median, the mean of the two middle values for an even number of values.
+///
+/// The values are copied to find the middle in linear time, the slice is left unchanged.
+pub fn median(values: &[Decimal]) -> Option<Decimal> {
+    if let Some(nan) = first_nan(values) {
+        return Some(nan);
+    }
+    if values.is_empty() {
+        return None;
+    }
+
+    let (mid, odd) = (values.len() / 2, values.len() % 2 == 1);
+    let mut values = values.to_vec();
+    let (lower, &mut upper, _) = values.select_nth_unstable(mid);
+    if odd {
+        return Some(upper);
+    }
+
+    // The largest of the lower half is the other middle value.
+    let lower = *lower.iter().max().unwrap();
+    let half = Var::from_limb(500_000_000, -1);
+    let median = Var::from_decimal(&lower).add(&Var::from_decimal(&upper)).mul(&half);
+    finish(median, false, lower.scale().max(upper.scale()))
+}
+
+/// Returns the sample variance, the sum of squared deviations from the mean divided by `n - 1`,
+/// or `None` for fewer than two values.
+pub fn variance(values: &[Decimal]) -> Option<Decimal> {
+    let (variance, inexact) = variance_var(values, 1)?;
+    finish(variance, inexact, 2 * max_scale(values))
+}
+
+/// Returns the sample standard deviation, the square root of [`variance`], or `None` for fewer
+/// than two values.
+pub fn stddev(values: &[Decimal]) -> Option<Decimal> {
+    let (variance, inexact) = variance_var(values, 1)?;
+    root(&variance, inexact, max_scale(values))
+}
+
+/// Returns the population variance, the sum of squared deviations from the mean divided by `n`.
+pub fn population_variance(values: &[Decimal]) -> Option<Decimal> {
+    let (variance, inexact) = variance_var(values, 0)?;
+    finish(variance, inexact, 2 * max_scale(values))
+}
+
+/// Returns the population standard deviation, the square root of [`population_variance`].
+pub fn population_stddev(values: &[Decimal]) -> Option<Decimal> {
+    let (variance, inexact) = variance_var(values, 0)?;
+    root(&variance, inexact, max_scale(values))
+}
+
+/// Returns the smallest value, the first of equal ones.
+pub fn min(values: &[Decimal]) -> Option<Decimal> {
+    match first_nan(values) {
+        Some(nan) => Some(nan),
+        None => values.iter().min().copied(),
+    }
+}
+
+/// Returns the largest value, the last of equal ones.
+pub fn max(values: &[Decimal]) -> Option<Decimal> {
+    match first_nan(values) {
+        Some(nan) => Some(nan),
+        None => values.iter().max().copied(),
+    }
+}
+
+#[inline]
+fn first_nan(values: &[Decimal]) -> Option<Decimal> {
+    values.iter().find(|value| value.is_nan()).copied()
+}
+
+#[inline]
+fn max_scale(values: &[Decimal]) -> i32 {
+    values.iter().map(Decimal::scale).max().unwrap_or(0)
+}
+
+/// Computes the variance as `(n * sum(x^2) - sum(x)^2) / (n * (n - ddof))` from exact sums,
+/// returning it with whether it is inexact, or `None` if there are at most `ddof` values. A NaN
+/// variance is exact.
+fn variance_var(values: &[Decimal], ddof: usize) -> Option<(Var, bool)> {
+    if let Some(nan) = first_nan(values) {
+        return Some((Var::from_decimal(&nan), false));
+    }
+    if values.len() <= ddof {
+        return None;
+    }
+
+    let mut sum = Var::ZERO;
+    let mut sum_squares = Var::ZERO;
+    for value in values {
+        let value = Var::from_decimal(value);
+        sum = sum.add(&value);
+        sum_squares = sum_squares.add(&value.mul(&value));
+    }
+
+    let n = Var::from_i64(values.len() as i64);
+    let deviations = n.mul(&sum_squares).sub(&sum.mul(&sum));
+    if deviations.is_zero() {
+        return Some((Var::ZERO, false));
+    }
+    Some(div_sticky(
+        &deviations,
+        &n.mul(&Var::from_i64((values.len() - ddof) as i64)),
+    ))
+}
+
+/// Returns the square root of a variance computed by [`variance_var`].
+fn root(variance: &Var, inexact: bool, scale: i32) -> Option<Decimal> {
+    if variance.is_zero() || variance.is_nan() {
+        return finish(*variance, false, scale);
+    }
+
+    let root = sqrt_var(variance);
+    if !inexact {
+        // An exact variance has at most `WORK_DIGITS + 2` digits, so an exact root has at most
+        // half as many and is found by rounding the approximation, then rounded only once.
+        let mut candidate = root;
+        candidate.round(
+            EXACT_ROOT_DIGITS - candidate.exponent(),
+            RoundingStrategy::HalfEven,
+            false,
+        );
+        if candidate.mul(&candidate).sub(variance).is_zero() {
+            return finish(candidate, false, scale);
+        }
+    }
+    finish(root, true, scale)
+}
+
+/// Divides with `WORK_DIGITS` significant digits, returning the quotient truncated with a guard
+/// digit and whether it is inexact. `v` must be non-zero.
+#[inline]
+fn div_sticky(u: &Var, v: &Var) -> (Var, bool) {
+    if u.is_zero() || u.is_nan() {
+        return (*u, false);
+    }
+    u.div(v, WORK_DIGITS + 1 - u.exponent() + v.exponent())
+}
+
+/// Rounds a statistic half up to as many digits after the decimal point as fit, or, if it is
+/// exact, to the digits it needs but at least `scale` digits.
+fn finish(mut var: Var, inexact: bool, scale: i32) -> Option<Decimal> {
+    let scale = scale.min(MAX_SCALE);
+    if !var.is_nan() {
+        let rscale = if var.is_zero() {
+            scale
+        } else {
+            MAX_SCALE.min(MAX_PRECISION as i32 - var.exponent())
+        };
+        if !var.round(rscale, RoundingStrategy::HalfUp, inexact) && !inexact {
+            var.dscale = var.dscale.min(var.min_scale().max(scale));
+        }
+    }
+    var.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decs(values: &[&str]) -> Vec<Decimal> {
+        values.iter().map(|s| s.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn variances() {
+        let values = decs(&["2", "4", "4", "4", "5", "5", "7", "9"]);
+        assert_eq!(mean(&values).unwrap().to_string(), "5");
+        assert_eq!(population_variance(&values).unwrap().to_string(), "4");
+        assert_eq!(population_stddev(&values).unwrap().to_string(), "2");
+        assert_eq!(
+            variance(&values).unwrap().to_string(),
+            "4.57142857142857142857142857142857143"
+        );
+        assert_eq!(
+            stddev(&values).unwrap().to_string(),
+            "2.13808993529939507747642784703802817"
+        );
+
+        // Exact results keep the scale of the values, or twice that for variances.
+        let values = decs(&["1.0", "3.0"]);
+        assert_eq!(mean(&values).unwrap().to_string(), "2.0");
+        assert_eq!(variance(&values).unwrap().to_string(), "2.00");
+        assert_eq!(population_variance(&values).unwrap().to_string(), "1.00");
+        assert_eq!(population_stddev(&values).unwrap().to_string(), "1.0");
+        let values = decs(&["1.5", "1.5"]);
+        assert_eq!(variance(&values).unwrap().to_string(), "0.00");
+        assert_eq!(stddev(&values).unwrap().to_string(), "0.0");
+    }
+
+    #[test]
+    fn medians() {
+        assert_eq!(median(&decs(&["3", "1", "2"])).unwrap().to_string(), "2");
+        assert_eq!(median(&decs(&["4", "1", "3", "2"])).unwrap().to_string(), "2.5");
+        assert_eq!(median(&decs(&["1.0", "2.00"])).unwrap().to_string(), "1.50");
+        assert_eq!(median(&decs(&["-1", "5", "-1", "5"])).unwrap().to_string(), "2");
+        assert_eq!(median(&decs(&["2", "3"])).unwrap().to_string(), "2.5");
+        let values = decs(&["3", "1", "2", "0"]);
+        median(&values);
+        assert_eq!(values, decs(&["3", "1", "2", "0"]));
+    }
+
+    #[test]
+    fn empty_and_small() {
+        for f in [
+            mean,
+            median,
+            variance,
+            stddev,
+            population_variance,
+            population_stddev,
+            min,
+            max,
+        ] {
+            assert_eq!(f(&[]), None);
+        }
+        let one = decs(&["1.5"]);
+        assert_eq!(variance(&one), None);
+        assert_eq!(stddev(&one), None);
+        assert_eq!(population_variance(&one).unwrap().to_string(), "0.00");
+        assert_eq!(min(&one), max(&one));
+    }
+
+    #[test]
+    fn nan_propagation() {
+        let values = decs(&["1", "NaN5", "2", "NaN7"]);
+        for f in [
+            mean,
+            median,
+            variance,
+            stddev,
+            population_variance,
+            population_stddev,
+            min,
+            max,
+        ] {
+            assert_eq!(f(&values).unwrap().to_string(), "NaN5");
+        }
+        // A single NaN is enough for the sample statistics.
+        assert!(variance(&decs(&["NaN"])).unwrap().is_nan());
+    }
+
+    #[test]
+    fn overflow() {
+        let values = [Decimal::MAX, Decimal::MIN];
+        assert_eq!(variance(&values), None);
+        assert_eq!(population_variance(&values), None);
+        assert_eq!(stddev(&values), None);
+        // The variance overflows, but its root fits.
+        assert_eq!(population_stddev(&values), Some(Decimal::MAX));
+        // The exact sum is beyond `MAX`, but the mean fits.
+        let values = [Decimal::MAX, Decimal::MAX, Decimal::MAX];
+        assert_eq!(mean(&values), Some(Decimal::MAX));
+        assert_eq!(median(&values), Some(Decimal::MAX));
+        assert_eq!(min(&decs(&["2", "-1", "-1.0"])).unwrap().to_string(), "-1");
+        assert_eq!(max(&decs(&["2", "2.0", "-1"])).unwrap().to_string(), "2.0");
+    }
+}
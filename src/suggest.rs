@@ -0,0 +1,62 @@
+//! Suggestions for fixing invalid decimal literals.
+
+use crate::decimal::Decimal;
+use crate::error::SuggestedParseError;
+
+/// Currency symbols stripped from literals.
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥', '₹', '₩', '₽', '¢'];
+
+impl Decimal {
+    /// Parses a decimal like [`FromStr`](std::str::FromStr), suggesting a fix when the literal
+    /// fails because of a single common defect.
+    ///
+    /// The defects recognized are a trailing `%` (the suggestion is the value divided by 100),
+    /// a currency symbol, a comma as the decimal point, and whitespace inside the literal.
+    pub fn parse_suggesting(s: &str) -> Result<Decimal, SuggestedParseError> {
+        s.parse::<Decimal>().map_err(|kind| SuggestedParseError {
+            kind,
+            suggestion: suggest(s),
+        })
+    }
+}
+
+/// Returns the first single fix of `s` that parses.
+fn suggest(s: &str) -> Option<String> {
+    let fixes: [fn(&str) -> Option<String>; 4] = [fix_percent, fix_currency, fix_comma, fix_whitespace];
+    fixes
+        .iter()
+        .filter_map(|fix| fix(s))
+        .find(|candidate| candidate.parse::<Decimal>().is_ok())
+}
+
+fn fix_percent(s: &str) -> Option<String> {
+    let number = s.strip_suffix('%')?.trim_end();
+    let d = number.parse::<Decimal>().ok()?;
+    d.checked_mul_pow10(-2).map(|d| d.to_string())
+}
+
+fn fix_currency(s: &str) -> Option<String> {
+    let (sign, rest) = match s.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", s.strip_prefix('+').unwrap_or(s)),
+    };
+    let stripped = rest.trim_matches(CURRENCY_SYMBOLS).trim();
+    if stripped.len() == rest.len() {
+        return None;
+    }
+    Some(format!("{}{}", sign, stripped))
+}
+
+fn fix_comma(s: &str) -> Option<String> {
+    if s.contains('.') || s.matches(',').count() != 1 {
+        return None;
+    }
+    Some(s.replace(',', "."))
+}
+
+fn fix_whitespace(s: &str) -> Option<String> {
+    if !s.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(s.chars().filter(|c| !c.is_whitespace()).collect())
+}
@@ -0,0 +1,203 @@
+//! Deterministic test vectors for differential testing.
+//!
+//! Available with the `test_support` feature.
+
+use crate::decimal::{Decimal, MAX_PRECISION, MAX_SCALE};
+use std::cmp::Ordering;
+
+/// A seeded generator of interesting decimals.
+///
+/// The same seed always generates the same sequence, so failures are reproducible. Values cover
+/// zeros of any scale, NaN, limb boundaries such as `10^k` and `10^k - 1`, the maximum precision,
+/// and mixed scales.
+#[derive(Clone, Debug)]
+pub struct DecimalGenerator {
+    state: u64,
+}
+
+/// Two decimals with outcomes computed independently of the decimal arithmetic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TestPair {
+    /// Left operand.
+    pub lhs: Decimal,
+    /// Right operand.
+    pub rhs: Decimal,
+    /// Ordering of `lhs` and `rhs`.
+    pub ordering: Ordering,
+    /// Exact `lhs + rhs`, with the larger scale of the operands.
+    pub sum: Decimal,
+    /// Exact `lhs - rhs`, with the larger scale of the operands.
+    pub difference: Decimal,
+    /// Exact `lhs * rhs`, with the sum of the scales of the operands.
+    pub product: Decimal,
+}
+
+impl DecimalGenerator {
+    /// Creates a generator from a seed.
+    #[inline]
+    pub const fn new(seed: u64) -> DecimalGenerator {
+        DecimalGenerator { state: seed }
+    }
+
+    /// Returns the next pseudo-random number (SplitMix64).
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..n`.
+    #[inline]
+    fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// Returns a random coefficient with exactly `len` digits, `len` must be in `1..=MAX_PRECISION`.
+    fn coefficient(&mut self, len: u32) -> u128 {
+        let low = 10u128.pow(len - 1);
+        let random = (self.next_u64() as u128) << 64 | self.next_u64() as u128;
+        low + random % (low * 9)
+    }
+
+    /// Returns the next decimal.
+    pub fn next_decimal(&mut self) -> Decimal {
+        let negative = self.below(2) == 1;
+        match self.below(8) {
+            0 => Decimal::from_coefficient(false, 0, self.below(MAX_SCALE as u64 + 1) as i32),
+            1 => {
+                let payload = if self.below(2) == 0 {
+                    0
+                } else {
+                    self.below(1_000_000) as u128
+                };
+                Decimal::nan_with_payload(payload, self.below(4) == 0).unwrap()
+            }
+            2 => {
+                let coefficient = self.coefficient(MAX_PRECISION);
+                let scale = self.below(MAX_PRECISION as u64 + 1) as i32;
+                Decimal::from_coefficient(negative, coefficient, scale)
+            }
+            3 => {
+                let k = 1 + self.below(MAX_PRECISION as u64) as u32;
+                let coefficient = 10u128.pow(k) - self.below(2) as u128;
+                let coefficient = if coefficient >= 10u128.pow(MAX_PRECISION) {
+                    coefficient / 10
+                } else {
+                    coefficient
+                };
+                let scale = self.below(MAX_SCALE as u64 + 1) as i32;
+                Decimal::from_coefficient(negative, coefficient, scale)
+            }
+            4 => Decimal::from_coefficient(negative, self.below(1000) as u128, 0),
+            _ => {
+                let len = 1 + self.below(MAX_PRECISION as u64) as u32;
+                let coefficient = self.coefficient(len);
+                let scale = self.below(MAX_SCALE as u64 + 1) as i32;
+                Decimal::from_coefficient(negative, coefficient, scale)
+            }
+        }
+    }
+
+    /// Returns the next pair of numbers with known outcomes.
+    ///
+    /// The outcomes are computed on `i128` coefficients, so they are exact and independent of
+    /// this crate's arithmetic.
+    pub fn next_pair(&mut self) -> TestPair {
+        let (a, a_scale) = self.pair_operand();
+        let (b, b_scale) = self.pair_operand();
+
+        // Coefficients have at most 17 digits and scales at most 18, so the aligned values
+        // have at most 35 digits and nothing overflows.
+        let scale = a_scale.max(b_scale);
+        let a_aligned = a * 10i128.pow((scale - a_scale) as u32);
+        let b_aligned = b * 10i128.pow((scale - b_scale) as u32);
+
+        TestPair {
+            lhs: from_i128(a, a_scale),
+            rhs: from_i128(b, b_scale),
+            ordering: a_aligned.cmp(&b_aligned),
+            sum: from_i128(a_aligned + b_aligned, scale),
+            difference: from_i128(a_aligned - b_aligned, scale),
+            product: from_i128(a * b, a_scale + b_scale),
+        }
+    }
+
+    /// Returns a signed coefficient with at most 17 digits and a scale at most 18.
+    fn pair_operand(&mut self) -> (i128, i32) {
+        let coefficient = match self.below(4) {
+            0 => 0,
+            1 => 10i128.pow(self.below(17) as u32) - self.below(2) as i128,
+            _ => {
+                let len = 1 + self.below(17) as u32;
+                self.coefficient(len) as i128
+            }
+        };
+        let coefficient = if self.below(2) == 1 { -coefficient } else { coefficient };
+        (coefficient, self.below(19) as i32)
+    }
+}
+
+impl Iterator for DecimalGenerator {
+    type Item = Decimal;
+
+    #[inline]
+    fn next(&mut self) -> Option<Decimal> {
+        Some(self.next_decimal())
+    }
+}
+
+#[inline]
+fn from_i128(value: i128, scale: i32) -> Decimal {
+    Decimal::from_coefficient(value < 0, value.unsigned_abs(), scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecimalGenerator;
+    use crate::roundtrip::{assert_format_roundtrip, assert_parse_roundtrip, assert_roundtrip_with};
+    use crate::Decimal;
+
+    #[test]
+    fn pairs() {
+        let mut generator = DecimalGenerator::new(42);
+        for _ in 0..10_000 {
+            let pair = generator.next_pair();
+            let (a, b) = (pair.lhs, pair.rhs);
+            assert_eq!(a.partial_cmp(&b), Some(pair.ordering), "{a} <=> {b}");
+            for (actual, expected) in [(a + b, pair.sum), (a - b, pair.difference), (a * b, pair.product)] {
+                // Same value and scale.
+                assert_eq!(actual.to_string(), expected.to_string(), "{a}, {b}");
+            }
+            assert_eq!(pair.sum - b, a, "{a}, {b}");
+        }
+    }
+
+    #[test]
+    fn decimals() {
+        let mut generator = DecimalGenerator::new(7);
+        let (mut zeros, mut nans) = (0, 0);
+        for d in generator.by_ref().take(10_000) {
+            assert_format_roundtrip(&d);
+            assert_parse_roundtrip(&d.to_string());
+            assert_roundtrip_with(&d, |d| d.to_machine_string(), |s| s.parse().unwrap());
+            if d.is_nan() {
+                nans += 1;
+                continue;
+            }
+            zeros += (d == Decimal::ZERO) as usize;
+            assert_eq!(d - d, Decimal::ZERO, "{d}");
+            assert_eq!((d + Decimal::ZERO).to_string(), d.to_string(), "{d}");
+            assert_eq!(-(-d), d, "{d}");
+        }
+        assert!(zeros > 0 && nans > 0);
+
+        // The same seed gives the same sequence.
+        let a: Vec<_> = DecimalGenerator::new(7).take(100).map(|d| d.to_string()).collect();
+        let b: Vec<_> = DecimalGenerator::new(7).take(100).map(|d| d.to_string()).collect();
+        assert_eq!(a, b);
+        assert!(DecimalGenerator::new(8).take(100).map(|d| d.to_string()).ne(a));
+    }
+}
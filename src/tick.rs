@@ -0,0 +1,66 @@
+//! Tick size utilities for prices.
+
+use crate::decimal::Decimal;
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+
+impl Decimal {
+    /// Rounds to a multiple of `tick` with the given strategy, the result has the scale of `tick`.
+    ///
+    /// The sign of `tick` is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tick` is zero or the result overflows.
+    pub fn snap_to_tick(&self, tick: &Decimal, strategy: RoundingStrategy) -> Decimal {
+        if self.is_nan() {
+            return self.quiet();
+        }
+        if tick.is_nan() {
+            return tick.quiet();
+        }
+        assert!(!tick.is_zero(), "tick is zero");
+
+        let tick = Var::from_decimal(tick).abs();
+        let (ticks, _) = Var::from_decimal(self).div_round(&tick, 0, strategy);
+        let (result, _) = ticks
+            .mul(&tick)
+            .into_decimal(strategy)
+            .expect("overflow when snapping to tick");
+        result
+    }
+
+    /// Returns the number of whole ticks from `a` to `b`, truncated towards zero.
+    ///
+    /// The result is negative if `b` is less than `a`, and the sign of `tick` is ignored.
+    /// Returns `None` if any argument is NaN, `tick` is zero, or the count doesn't fit in `i64`.
+    pub fn ticks_between(a: &Decimal, b: &Decimal, tick: &Decimal) -> Option<i64> {
+        if a.is_nan() || b.is_nan() || tick.is_nan() || tick.is_zero() {
+            return None;
+        }
+
+        let diff = Var::from_decimal(b).sub(&Var::from_decimal(a));
+        let (ticks, _) = diff.div(&Var::from_decimal(tick).abs(), 0);
+        let ticks = ticks.into_i128()?;
+        if ticks >= i64::MIN as i128 && ticks <= i64::MAX as i128 {
+            Some(ticks as i64)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if this decimal is an integral multiple of `step`.
+    ///
+    /// Zero is the only multiple of zero, and NaN is never a multiple.
+    pub fn is_multiple_of(&self, step: &Decimal) -> bool {
+        if self.is_nan() || step.is_nan() {
+            return false;
+        }
+        if step.is_zero() {
+            return self.is_zero();
+        }
+
+        let (_, inexact) = Var::from_decimal(self).div(&Var::from_decimal(step), 0);
+        !inexact
+    }
+}
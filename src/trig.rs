@@ -0,0 +1,389 @@
+//! Trigonometric functions.
+//!
+//! Results are computed with `WORK_DIGITS` significant digits, as the other functions of the
+//! math module, and rounded half up to `MAX_PRECISION` digits with as many digits after the
+//! decimal point as fit.
+
+use crate::decimal::Decimal;
+use crate::math::{div_work, into_full_decimal, mul_work, sqrt_var, WORK_DIGITS};
+use crate::round::RoundingStrategy;
+use crate::var::Var;
+use std::cmp::Ordering;
+
+/// Limbs of `pi` to 153 digits after the decimal point, with weight 0.
+///
+/// Arguments below `10^45` are reduced modulo `pi / 2` with these digits, which
+/// leaves more than `WORK_DIGITS` exact digits in the reduced argument.
+const PI: [u32; 18] = [
+    3,
+    141_592_653,
+    589_793_238,
+    462_643_383,
+    279_502_884,
+    197_169_399,
+    375_105_820,
+    974_944_592,
+    307_816_406,
+    286_208_998,
+    628_034_825,
+    342_117_067,
+    982_148_086,
+    513_282_306,
+    647_093_844,
+    609_550_582,
+    231_725_359,
+    408_128_481,
+];
+
+#[inline]
+fn pi() -> Var {
+    Var::from_limbs(&PI, 0)
+}
+
+#[inline]
+fn half_pi() -> Var {
+    pi().mul(&Var::from_limb(500_000_000, -1))
+}
+
+#[inline]
+fn one() -> Var {
+    Var::from_limb(1, 0)
+}
+
+/// Sums the Taylor series of `sin(r)` if `odd` is `true`, or of `cos(r)` otherwise.
+fn sin_cos_series(r: &Var, odd: bool) -> Var {
+    let (mut sum, first) = if odd { (*r, 1) } else { (one(), 0) };
+    if r.is_zero() {
+        return sum;
+    }
+
+    let r2 = mul_work(r, r);
+    let mut term = sum;
+    for n in (first + 1..).step_by(2) {
+        term = div_work(&mul_work(&term, &r2), &Var::from_i64(n * (n + 1))).neg();
+        if term.is_zero() || term.exponent() < sum.exponent() - WORK_DIGITS - 2 {
+            break;
+        }
+        sum = sum.add(&term);
+    }
+    sum
+}
+
+/// Computes `sin(x)`, or `cos(x)` if `cos` is `true`.
+///
+/// `x` is reduced to `r = x - k * pi / 2` with `|r| <= pi / 4`, unless it is already that small
+/// so tiny arguments keep their relative precision.
+fn sin_cos_var(x: &Var, cos: bool) -> Var {
+    let half_pi = half_pi();
+    let quarter_pi = half_pi.mul(&Var::from_limb(500_000_000, -1));
+    let (r, k) = if x.cmp_abs(&quarter_pi) == Ordering::Greater {
+        let (k, _) = x.div_round(&half_pi, 0, RoundingStrategy::HalfEven);
+        // `NBASE` is a multiple of 4, so the units limb tells the quarter turn of any `k`.
+        let quarter = (k.limb_at(0) % 4) as i128;
+        (
+            x.sub(&k.mul(&half_pi)),
+            if k.is_negative() { -quarter } else { quarter },
+        )
+    } else {
+        (*x, 0)
+    };
+
+    // `cos(x) = sin(x + pi / 2)`, and each quarter turn cycles `sin`, `cos`, `-sin`, `-cos`.
+    match (k + cos as i128).rem_euclid(4) {
+        0 => sin_cos_series(&r, true),
+        1 => sin_cos_series(&r, false),
+        2 => sin_cos_series(&r, true).neg(),
+        _ => sin_cos_series(&r, false).neg(),
+    }
+}
+
+/// Computes `atan(x)`.
+///
+/// Arguments beyond 1 use `atan(x) = pi / 2 - atan(1 / x)`, and the argument is halved twice
+/// with `atan(x) = 2 * atan(x / (1 + sqrt(1 + x^2)))` before summing the Taylor series.
+fn atan_var(x: &Var) -> Var {
+    if x.is_zero() {
+        return Var::ZERO;
+    }
+    let mut a = x.abs();
+    let invert = a.cmp_abs(&one()) == Ordering::Greater;
+    if invert {
+        a = div_work(&one(), &a);
+    }
+    for _ in 0..2 {
+        let s = sqrt_var(&one().add(&mul_work(&a, &a)));
+        a = div_work(&a, &one().add(&s));
+    }
+
+    let a2 = mul_work(&a, &a);
+    let mut power = a;
+    let mut sum = a;
+    for n in (3..).step_by(2) {
+        power = mul_work(&power, &a2).neg();
+        let term = div_work(&power, &Var::from_i64(n));
+        if term.is_zero() || term.exponent() < sum.exponent() - WORK_DIGITS - 2 {
+            break;
+        }
+        sum = sum.add(&term);
+    }
+    sum = sum.mul(&Var::from_limb(4, 0));
+
+    if invert {
+        sum = half_pi().sub(&sum);
+    }
+    if x.is_negative() {
+        sum = sum.neg();
+    }
+    sum
+}
+
+/// Computes `asin(x)` for `|x| <= 1`.
+fn asin_var(x: &Var) -> Var {
+    if x.cmp_abs(&one()) == Ordering::Equal {
+        let half_pi = half_pi();
+        return if x.is_negative() { half_pi.neg() } else { half_pi };
+    }
+    // `1 - x^2` is exact.
+    let c = sqrt_var(&one().sub(&x.mul(x)));
+    atan_var(&div_work(x, &c))
+}
+
+impl Decimal {
+    /// Returns the sine of an angle in radians. NaN gives NaN.
+    pub fn sin(&self) -> Decimal {
+        let x = Var::from_decimal(self);
+        if x.is_nan() || x.is_zero() {
+            return x.into_decimal(RoundingStrategy::HalfUp).unwrap().0;
+        }
+        into_full_decimal(sin_cos_var(&x, false)).unwrap()
+    }
+
+    /// Returns the cosine of an angle in radians. `cos(0)` is exactly 1, and NaN gives NaN.
+    pub fn cos(&self) -> Decimal {
+        let x = Var::from_decimal(self);
+        if x.is_nan() {
+            return Decimal::NAN;
+        }
+        if x.is_zero() {
+            return Decimal::from(1);
+        }
+        into_full_decimal(sin_cos_var(&x, true)).unwrap()
+    }
+
+    /// Returns the tangent of an angle in radians, or `None` instead of panicking if it
+    /// overflows.
+    pub fn checked_tan(&self) -> Option<Decimal> {
+        let x = Var::from_decimal(self);
+        if x.is_nan() || x.is_zero() {
+            return x.into_decimal(RoundingStrategy::HalfUp).map(|(d, _)| d);
+        }
+        // The cosine of a decimal is never exactly zero, as `pi` is irrational.
+        into_full_decimal(div_work(&sin_cos_var(&x, false), &sin_cos_var(&x, true)))
+    }
+
+    /// Returns the tangent of an angle in radians. NaN gives NaN.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the result overflows, for angles within about `10^-36` of an odd multiple of
+    /// `pi / 2`.
+    #[inline]
+    pub fn tan(&self) -> Decimal {
+        self.checked_tan().expect("overflow when computing a tangent")
+    }
+
+    /// Returns the arcsine in radians, in `[-pi / 2, pi / 2]`. Values beyond `[-1, 1]` and NaN
+    /// give NaN.
+    pub fn asin(&self) -> Decimal {
+        let x = Var::from_decimal(self);
+        if x.is_nan() || x.cmp_abs(&one()) == Ordering::Greater {
+            return Decimal::NAN;
+        }
+        if x.is_zero() {
+            return x.into_decimal(RoundingStrategy::HalfUp).unwrap().0;
+        }
+        into_full_decimal(asin_var(&x)).unwrap()
+    }
+
+    /// Returns the arccosine in radians, in `[0, pi]`. `acos(1)` is exactly 0, and values
+    /// beyond `[-1, 1]` and NaN give NaN.
+    pub fn acos(&self) -> Decimal {
+        let x = Var::from_decimal(self);
+        if x.is_nan() || x.cmp_abs(&one()) == Ordering::Greater {
+            return Decimal::NAN;
+        }
+        if *self == Decimal::from(1) {
+            return Decimal::ZERO;
+        }
+        into_full_decimal(half_pi().sub(&asin_var(&x))).unwrap()
+    }
+
+    /// Returns the arctangent in radians, in `(-pi / 2, pi / 2)`. NaN gives NaN.
+    pub fn atan(&self) -> Decimal {
+        let x = Var::from_decimal(self);
+        if x.is_nan() || x.is_zero() {
+            return x.into_decimal(RoundingStrategy::HalfUp).unwrap().0;
+        }
+        into_full_decimal(atan_var(&x)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Decimal;
+
+    fn dec(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    /// Correctly rounded values of `sin`, `cos`, `tan` and `atan`.
+    const KNOWN_ANSWERS: [(&str, [&str; 4]); 6] = [
+        (
+            "1",
+            [
+                "0.841470984807896506652502321630299000",
+                "0.540302305868139717400936607442976604",
+                "1.55740772465490223050697480745836017",
+                "0.785398163397448309615660845819875721",
+            ],
+        ),
+        (
+            "-0.5",
+            [
+                "-0.479425538604203000273287935215571388",
+                "0.877582561890372716116281582603829652",
+                "-0.546302489843790513255179465780285383",
+                "-0.463647609000806116214256231461214402",
+            ],
+        ),
+        (
+            "3",
+            [
+                "0.141120008059867222100744802808110280",
+                "-0.989992496600445457271572794731261302",
+                "-0.142546543074277805295635410533913493",
+                "1.24904577239825442582991707728109012",
+            ],
+        ),
+        (
+            "1e-30",
+            [
+                "0.00000000000000000000000000000100000000000000000000000000000000000",
+                "1.00000000000000000000000000000000000",
+                "0.00000000000000000000000000000100000000000000000000000000000000000",
+                "0.00000000000000000000000000000100000000000000000000000000000000000",
+            ],
+        ),
+        (
+            "1e30",
+            [
+                "-0.0901169019121380580303864289529873303",
+                "-0.995931194405395702394248587997048641",
+                "0.0904850680633021725662231380500412737",
+                "1.57079632679489661923132169163875144",
+            ],
+        ),
+        (
+            // `Decimal::MAX`.
+            "999999999999999999999999999999999999e9",
+            [
+                "-0.803669958845009110936408265408456579",
+                "-0.595075287043632288238760857157034196",
+                "1.35053492615646494115262389890600984",
+                "1.57079632679489661923132169163975144",
+            ],
+        ),
+    ];
+
+    #[test]
+    fn known_answers() {
+        for (x, [sin, cos, tan, atan]) in KNOWN_ANSWERS {
+            let x = dec(x);
+            assert_eq!(x.sin().to_string(), sin, "sin({})", x);
+            assert_eq!(x.cos().to_string(), cos, "cos({})", x);
+            assert_eq!(x.tan().to_string(), tan, "tan({})", x);
+            assert_eq!(x.atan().to_string(), atan, "atan({})", x);
+        }
+        assert_eq!(Decimal::MAX.sin(), dec(KNOWN_ANSWERS[5].1[0]));
+        assert_eq!((-Decimal::MAX).cos(), dec(KNOWN_ANSWERS[5].1[1]));
+    }
+
+    #[test]
+    fn exact_values() {
+        assert_eq!(Decimal::ZERO.sin().to_string(), "0");
+        assert_eq!(Decimal::ZERO.cos().to_string(), "1");
+        assert_eq!(dec("0.000").cos(), Decimal::ONE);
+        assert_eq!(Decimal::ZERO.tan().to_string(), "0");
+        assert_eq!(Decimal::ZERO.atan().to_string(), "0");
+        assert_eq!(Decimal::ONE.acos().to_string(), "0");
+        assert_eq!(Decimal::ZERO.asin().to_string(), "0");
+    }
+
+    #[test]
+    fn inverse_functions() {
+        for (x, asin, acos) in [
+            (
+                "0.5",
+                "0.523598775598298873077107230546583814",
+                "1.04719755119659774615421446109316763",
+            ),
+            (
+                "-0.5",
+                "-0.523598775598298873077107230546583814",
+                "2.09439510239319549230842892218633526",
+            ),
+            (
+                "0.999999",
+                "1.56938211311467236746824989586709579",
+                "0.00141421368022425176307179577265564846",
+            ),
+            (
+                "1e-30",
+                "0.00000000000000000000000000000100000000000000000000000000000000000",
+                "1.57079632679489661923132169163875144",
+            ),
+            (
+                "-1",
+                "-1.57079632679489661923132169163975144",
+                "3.14159265358979323846264338327950288",
+            ),
+        ] {
+            assert_eq!(dec(x).asin().to_string(), asin, "asin({})", x);
+            assert_eq!(dec(x).acos().to_string(), acos, "acos({})", x);
+        }
+    }
+
+    #[test]
+    fn domain_errors() {
+        for x in ["1.0000000001", "-1.5", "1e30", "NaN"] {
+            assert!(dec(x).asin().is_nan(), "asin({})", x);
+            assert!(dec(x).acos().is_nan(), "acos({})", x);
+        }
+        for f in [Decimal::sin, Decimal::cos, Decimal::tan, Decimal::atan] {
+            assert!(f(&Decimal::NAN).is_nan());
+        }
+        assert_eq!(Decimal::NAN.checked_tan().map(|t| t.is_nan()), Some(true));
+    }
+
+    #[test]
+    fn tan_near_half_pi() {
+        assert_eq!(
+            dec("1.5707963267948966").tan().to_string(),
+            "51998506188720270.6601947416612268685"
+        );
+        let x = dec("1.57079632679489661923132169163975144");
+        assert_eq!(x.tan().to_string(), "476511622403844204877207821018623346");
+        assert_eq!((-x).tan().to_string(), "-476511622403844204877207821018623346");
+
+        // The decimal closest to `pi / 2` is about `2 * 10^-36` away, so its tangent fits.
+        let x = dec("1.57079632679489661923132169163975144209858469968755");
+        assert_eq!(x.checked_tan(), Some(x.tan()));
+        assert_eq!(
+            dec("1.57079632679489661923132169163975145")
+                .checked_tan()
+                .unwrap()
+                .to_string(),
+            "-126559605082453623180917025924082415"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Bulk validation of decimal literals.
+
+use crate::decimal::{MAX_INT_DIGITS, MAX_SCALE};
+use crate::error::DecimalParseError;
+use crate::parse::{scan, to_decimal, to_nan, Scanned};
+
+/// Constraints checked by [`validate_slice`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidationOptions {
+    /// Whether `NaN` is accepted.
+    pub allow_nan: bool,
+    /// Maximum number of significant digits, as counted by [`Decimal::precision`].
+    ///
+    /// [`Decimal::precision`]: crate::Decimal::precision
+    pub max_precision: u32,
+    /// Maximum number of digits after the decimal point.
+    pub max_scale: i32,
+}
+
+impl ValidationOptions {
+    /// Accepts every value that parses as a decimal.
+    pub const DEFAULT: ValidationOptions = ValidationOptions {
+        allow_nan: true,
+        // Integers beyond `MAX_PRECISION` digits count their implied trailing zeros.
+        max_precision: MAX_INT_DIGITS as u32,
+        max_scale: MAX_SCALE,
+    };
+}
+
+impl Default for ValidationOptions {
+    #[inline]
+    fn default() -> Self {
+        ValidationOptions::DEFAULT
+    }
+}
+
+/// Kind of a validation error.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// The value doesn't parse as a decimal.
+    Parse(DecimalParseError),
+    /// The value is NaN but NaN isn't allowed.
+    NaN,
+    /// The value has more significant digits than allowed.
+    Precision,
+    /// The value has more digits after the decimal point than allowed.
+    Scale,
+}
+
+/// An invalid value found by [`validate_slice`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ValidationError {
+    /// Index of the value in the slice.
+    pub index: usize,
+    /// Byte offset in the value where the error was detected, 0 if it concerns the whole value.
+    pub position: usize,
+    /// Kind of the error.
+    pub kind: ValidationErrorKind,
+}
+
+/// Result of validating a slice of decimal literals.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    len: usize,
+    errors: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if all values are valid.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns the number of valid values.
+    #[inline]
+    pub fn valid_count(&self) -> usize {
+        self.len - self.errors.len()
+    }
+
+    /// Returns the errors ordered by index.
+    #[inline]
+    pub fn errors(&self) -> &[ValidationError] {
+        &self.errors
+    }
+}
+
+/// Validates all values in one pass, reporting every invalid value instead of stopping at the first.
+pub fn validate_slice(values: &[&str], options: &ValidationOptions) -> ValidationReport {
+    let mut errors = Vec::new();
+    for (index, value) in values.iter().enumerate() {
+        if let Err((position, kind)) = validate(value, options) {
+            errors.push(ValidationError { index, position, kind });
+        }
+    }
+
+    ValidationReport {
+        len: values.len(),
+        errors,
+    }
+}
+
+fn validate(value: &str, options: &ValidationOptions) -> Result<(), (usize, ValidationErrorKind)> {
+    let scanned = scan(value.as_bytes()).map_err(|e| (e.position, ValidationErrorKind::Parse(e.kind)))?;
+    let d = match scanned {
+        Scanned::NaN { signaling, payload } if options.allow_nan => {
+            return to_nan(signaling, payload)
+                .map(|_| ())
+                .map_err(|e| (0, ValidationErrorKind::Parse(e)))
+        }
+        Scanned::NaN { .. } => return Err((0, ValidationErrorKind::NaN)),
+        Scanned::Number {
+            negative,
+            int,
+            frac,
+            exp,
+        } => to_decimal(negative, int, frac, exp).map_err(|e| (0, ValidationErrorKind::Parse(e)))?,
+    };
+
+    if d.scale() > options.max_scale {
+        return Err((0, ValidationErrorKind::Scale));
+    }
+    if d.precision() > options.max_precision {
+        return Err((0, ValidationErrorKind::Precision));
+    }
+    Ok(())
+}
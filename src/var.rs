@@ -0,0 +1,648 @@
+//! Working variables for arithmetic.
+//!
+//! A `Var` has the same layout as a decimal but holds many more limbs, so intermediate results
+//! are computed exactly and rounded once when converted back to a decimal.
+
+use crate::decimal::{
+    Decimal, DEC_DIGITS, MAX_INT_DIGITS, MAX_PRECISION, MAX_SCALE, NBASE, POWERS_10, SIGN_NAN, SIGN_NEG, SIGN_POS,
+};
+use crate::round::RoundingStrategy;
+use std::cmp::Ordering;
+
+/// Maximum number of limbs in a working variable.
+pub(crate) const VAR_NDIGITS: usize = 32;
+
+const HALF_NBASE: u32 = NBASE / 2;
+
+/// Working variable with extended precision.
+///
+/// Unlike a decimal, a zero variable keeps its sign, so a quotient truncated to zero still
+/// rounds in the right direction.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Var {
+    pub(crate) ndigits: usize,
+    pub(crate) weight: i32,
+    pub(crate) sign: u8,
+    pub(crate) dscale: i32,
+    pub(crate) digits: [u32; VAR_NDIGITS],
+}
+
+impl Var {
+    pub(crate) const ZERO: Var = Var {
+        ndigits: 0,
+        weight: 0,
+        sign: SIGN_POS,
+        dscale: 0,
+        digits: [0; VAR_NDIGITS],
+    };
+
+    /// Converts a decimal, a NaN is quieted and keeps its payload in the limbs.
+    #[inline]
+    pub(crate) fn from_decimal(d: &Decimal) -> Var {
+        let mut var = Var {
+            ndigits: d.ndigits as usize,
+            weight: d.weight as i32,
+            sign: d.quiet().sign,
+            dscale: d.dscale as i32,
+            ..Var::ZERO
+        };
+        var.digits[..var.ndigits].copy_from_slice(d.limbs());
+        var
+    }
+
+    #[inline]
+    pub(crate) fn is_nan(&self) -> bool {
+        self.sign == SIGN_NAN
+    }
+
+    #[inline]
+    pub(crate) fn is_zero(&self) -> bool {
+        self.ndigits == 0 && self.sign != SIGN_NAN
+    }
+
+    #[inline]
+    pub(crate) fn is_negative(&self) -> bool {
+        self.sign == SIGN_NEG
+    }
+
+    #[inline]
+    pub(crate) fn limbs(&self) -> &[u32] {
+        &self.digits[..self.ndigits]
+    }
+
+    /// Returns `e` such that `10^(e-1) <= |self| < 10^e`, the value must be non-zero.
+    #[inline]
+    pub(crate) fn exponent(&self) -> i32 {
+        self.weight * DEC_DIGITS + crate::decimal::digits_of(self.digits[0])
+    }
+
+    #[inline]
+    pub(crate) fn neg(mut self) -> Var {
+        self.sign = match self.sign {
+            SIGN_POS => SIGN_NEG,
+            SIGN_NEG => SIGN_POS,
+            sign => sign,
+        };
+        self
+    }
+
+    #[inline]
+    pub(crate) fn abs(mut self) -> Var {
+        if self.sign == SIGN_NEG {
+            self.sign = SIGN_POS;
+        }
+        self
+    }
+
+    /// Sets the limbs, where the first limb has the weight `weight`.
+    ///
+    /// Leading and trailing zero limbs are stripped, and limbs beyond the capacity are
+    /// truncated. The sign and display scale are kept.
+    fn set_limbs(&mut self, limbs: &[u32], weight: i32) {
+        let start = limbs.iter().position(|&l| l != 0).unwrap_or(limbs.len());
+        let limbs = &limbs[start..];
+        let mut end = limbs.len().min(VAR_NDIGITS);
+        while end > 0 && limbs[end - 1] == 0 {
+            end -= 1;
+        }
+
+        self.digits = [0; VAR_NDIGITS];
+        self.digits[..end].copy_from_slice(&limbs[..end]);
+        self.ndigits = end;
+        self.weight = if end == 0 { 0 } else { weight - start as i32 };
+    }
+
+    /// Returns the limb with the weight `weight`.
+    #[inline]
+    pub(crate) fn limb_at(&self, weight: i32) -> u32 {
+        let index = self.weight - weight;
+        if index >= 0 && (index as usize) < self.ndigits {
+            self.digits[index as usize]
+        } else {
+            0
+        }
+    }
+
+    /// Returns the weight of the last limb.
+    #[inline]
+    fn last_weight(&self) -> i32 {
+        self.weight - self.ndigits as i32 + 1
+    }
+
+    /// Returns the minimum scale that displays the value exactly, as [`Decimal::min_scale`].
+    pub(crate) fn min_scale(&self) -> i32 {
+        let last = match self.limbs().last() {
+            Some(&limb) => limb,
+            None => return 0,
+        };
+        let last_weight = self.last_weight();
+        if last_weight >= 0 {
+            return 0;
+        }
+
+        let mut trailing_zeros = 0;
+        while last % POWERS_10[trailing_zeros as usize + 1] == 0 {
+            trailing_zeros += 1;
+        }
+        -last_weight * DEC_DIGITS - trailing_zeros
+    }
+
+    /// Compares absolute values.
+    pub(crate) fn cmp_abs(&self, other: &Var) -> Ordering {
+        match (self.ndigits == 0, other.ndigits == 0) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+
+        self.weight
+            .cmp(&other.weight)
+            .then_with(|| self.limbs().cmp(other.limbs()))
+    }
+
+    /// Adds absolute values.
+    fn add_abs(&self, other: &Var) -> Var {
+        let weight = self.weight.max(other.weight) + 1;
+        let mut buf = [0u32; 2 * VAR_NDIGITS + 1];
+        // Limbs far below the capacity are truncated.
+        let last = self
+            .last_weight()
+            .min(other.last_weight())
+            .max(weight + 1 - buf.len() as i32);
+        let n = (weight - last + 1) as usize;
+        let mut carry = 0;
+        for (i, limb) in buf[..n].iter_mut().enumerate().rev() {
+            let w = weight - i as i32;
+            let sum = self.limb_at(w) + other.limb_at(w) + carry;
+            if sum >= NBASE {
+                *limb = sum - NBASE;
+                carry = 1;
+            } else {
+                *limb = sum;
+                carry = 0;
+            }
+        }
+
+        let mut result = Var {
+            dscale: self.dscale.max(other.dscale),
+            ..Var::ZERO
+        };
+        result.set_limbs(&buf[..n], weight);
+        result
+    }
+
+    /// Subtracts absolute values, `|self|` must be greater than or equal to `|other|`.
+    fn sub_abs(&self, other: &Var) -> Var {
+        let weight = self.weight.max(other.weight);
+        let mut buf = [0u32; 2 * VAR_NDIGITS + 1];
+        // Limbs far below the capacity are truncated.
+        let last = self
+            .last_weight()
+            .min(other.last_weight())
+            .max(weight + 1 - buf.len() as i32);
+        let n = (weight - last + 1) as usize;
+        let mut borrow = 0;
+        for (i, limb) in buf[..n].iter_mut().enumerate().rev() {
+            let w = weight - i as i32;
+            let (a, b) = (self.limb_at(w), other.limb_at(w) + borrow);
+            if a >= b {
+                *limb = a - b;
+                borrow = 0;
+            } else {
+                *limb = a + NBASE - b;
+                borrow = 1;
+            }
+        }
+        debug_assert_eq!(borrow, 0);
+
+        let mut result = Var {
+            dscale: self.dscale.max(other.dscale),
+            ..Var::ZERO
+        };
+        result.set_limbs(&buf[..n], weight);
+        result
+    }
+
+    /// Returns the first NaN operand, which is the result of an operation as in IEEE 754.
+    #[inline]
+    pub(crate) fn nan_operand(&self, other: &Var) -> Option<Var> {
+        if self.is_nan() {
+            Some(*self)
+        } else if other.is_nan() {
+            Some(*other)
+        } else {
+            None
+        }
+    }
+
+    /// Adds exactly.
+    pub(crate) fn add(&self, other: &Var) -> Var {
+        if let Some(nan) = self.nan_operand(other) {
+            return nan;
+        }
+        if self.is_negative() == other.is_negative() {
+            let mut result = self.add_abs(other);
+            result.sign = self.sign;
+            return result;
+        }
+
+        match self.cmp_abs(other) {
+            Ordering::Less => {
+                let mut result = other.sub_abs(self);
+                result.sign = other.sign;
+                result
+            }
+            _ => {
+                let mut result = self.sub_abs(other);
+                result.sign = if result.ndigits == 0 { SIGN_POS } else { self.sign };
+                result
+            }
+        }
+    }
+
+    /// Subtracts exactly.
+    #[inline]
+    pub(crate) fn sub(&self, other: &Var) -> Var {
+        self.add(&other.neg())
+    }
+
+    /// Multiplies exactly.
+    pub(crate) fn mul(&self, other: &Var) -> Var {
+        if let Some(nan) = self.nan_operand(other) {
+            return nan;
+        }
+
+        let mut result = Var {
+            dscale: self.dscale + other.dscale,
+            ..Var::ZERO
+        };
+        if self.ndigits == 0 || other.ndigits == 0 {
+            return result;
+        }
+
+        let (a, b) = (self.limbs(), other.limbs());
+        let mut buf = [0u32; 2 * VAR_NDIGITS];
+        for (i, &x) in a.iter().enumerate().rev() {
+            let mut carry = 0u64;
+            for (j, &y) in b.iter().enumerate().rev() {
+                let t = buf[i + j + 1] as u64 + x as u64 * y as u64 + carry;
+                buf[i + j + 1] = (t % NBASE as u64) as u32;
+                carry = t / NBASE as u64;
+            }
+            buf[i] = carry as u32;
+        }
+
+        result.set_limbs(&buf[..a.len() + b.len()], self.weight + other.weight + 1);
+        result.sign = if self.sign == other.sign { SIGN_POS } else { SIGN_NEG };
+        result
+    }
+
+    /// Multiplies as [`mul`](Var::mul), but adds a unit of the last limb to the magnitude of a
+    /// product that may not fit in the capacity, so the result is never closer to zero than the
+    /// exact product.
+    pub(crate) fn mul_up(&self, other: &Var) -> Var {
+        let product = self.mul(other);
+        if self.ndigits + other.ndigits <= VAR_NDIGITS || product.is_nan() {
+            return product;
+        }
+        let unit = Var {
+            sign: product.sign,
+            ..Var::from_limb(1, product.last_weight())
+        };
+        product.add(&unit)
+    }
+
+    /// Divides, truncating the quotient to `rscale` digits after the decimal point.
+    ///
+    /// Returns the quotient and whether the discarded part is non-zero. The divisor must be
+    /// non-zero, and `rscale` must keep the quotient within the capacity.
+    pub(crate) fn div(&self, other: &Var, rscale: i32) -> (Var, bool) {
+        debug_assert!(!other.is_zero());
+        if let Some(nan) = self.nan_operand(other) {
+            return (nan, false);
+        }
+
+        let sign = if self.sign == other.sign { SIGN_POS } else { SIGN_NEG };
+        let mut result = Var {
+            sign,
+            dscale: rscale.max(0),
+            ..Var::ZERO
+        };
+        if self.ndigits == 0 {
+            return (result, false);
+        }
+
+        // Computes `floor(u * NBASE^s / v)` where the quotient's last limb has weight `last`.
+        let mut last = (-rscale).div_euclid(DEC_DIGITS);
+        let mut r = (-rscale).rem_euclid(DEC_DIGITS) as usize;
+        let top = self.weight - other.weight;
+        if top - last + 1 > VAR_NDIGITS as i32 {
+            // Keeps the quotient within the capacity, a decimal can't hold that many digits anyway.
+            last = top + 1 - VAR_NDIGITS as i32;
+            r = 0;
+        }
+        let s = (self.last_weight() - last) - other.last_weight();
+        let v = other.limbs();
+
+        let mut inexact = false;
+        let mut u = [0u32; 2 * VAR_NDIGITS + 2];
+        let ulen = if s >= 0 {
+            let len = self.ndigits + s as usize;
+            u[1..1 + self.ndigits].copy_from_slice(self.limbs());
+            len
+        } else {
+            let keep = (self.ndigits as i32 + s).max(0) as usize;
+            u[1..1 + keep].copy_from_slice(&self.digits[..keep]);
+            inexact = self.digits[keep..self.ndigits].iter().any(|&l| l != 0);
+            keep
+        };
+
+        debug_assert!(ulen < u.len());
+
+        let mut q = [0u32; 2 * VAR_NDIGITS + 2];
+        let qlen;
+        if ulen < v.len() {
+            qlen = 0;
+            inexact |= u[1..1 + ulen].iter().any(|&l| l != 0);
+        } else if v.len() == 1 {
+            let d = v[0] as u64;
+            let mut rem = 0u64;
+            for i in 0..ulen {
+                let t = rem * NBASE as u64 + u[1 + i] as u64;
+                q[i] = (t / d) as u32;
+                rem = t % d;
+            }
+            qlen = ulen;
+            inexact |= rem != 0;
+        } else {
+            qlen = ulen - v.len() + 1;
+            inexact |= div_knuth(&mut u[..ulen + 1], v, &mut q[..qlen]);
+        }
+
+        // Drops the digits below `10^-rscale` in the last limb.
+        if qlen > 0 && r > 0 {
+            let extra = q[qlen - 1] % POWERS_10[r];
+            inexact |= extra != 0;
+            q[qlen - 1] -= extra;
+        }
+
+        result.set_limbs(&q[..qlen], last + qlen as i32 - 1);
+        (result, inexact)
+    }
+
+    /// Divides, rounding the quotient to `rscale` digits after the decimal point.
+    ///
+    /// Returns the quotient and whether it is inexact.
+    #[inline]
+    pub(crate) fn div_round(&self, other: &Var, rscale: i32, strategy: RoundingStrategy) -> (Var, bool) {
+        // One guard digit and the sticky remainder decide the rounding exactly.
+        let (mut quotient, sticky) = self.div(other, rscale + 1);
+        let inexact = quotient.round(rscale, strategy, sticky);
+        (quotient, inexact)
+    }
+
+    /// Rounds to `rscale` digits after the decimal point, `rscale` may be negative.
+    ///
+    /// `sticky` tells that the variable was truncated from a slightly larger magnitude.
+    /// Returns whether the result is inexact.
+    pub(crate) fn round(&mut self, rscale: i32, strategy: RoundingStrategy, sticky: bool) -> bool {
+        if self.is_nan() {
+            return false;
+        }
+
+        self.dscale = rscale.max(0);
+        let negative = self.is_negative();
+
+        // Number of decimal digits kept from the top of the first limb.
+        let di = (self.weight + 1) * DEC_DIGITS + rscale;
+        if self.ndigits == 0 || di <= 0 {
+            let (half, inexact) = if self.ndigits == 0 || di < 0 {
+                (Ordering::Less, self.ndigits > 0 || sticky)
+            } else {
+                let rest = self.digits[1..self.ndigits].iter().any(|&l| l != 0) || sticky;
+                (
+                    self.digits[0]
+                        .cmp(&HALF_NBASE)
+                        .then(if rest { Ordering::Greater } else { Ordering::Equal }),
+                    true,
+                )
+            };
+            let up = strategy.round_up(negative, false, half, inexact);
+            self.set_limbs(&[], 0);
+            if up {
+                let pos = -rscale;
+                self.set_limbs(
+                    &[POWERS_10[pos.rem_euclid(DEC_DIGITS) as usize]],
+                    pos.div_euclid(DEC_DIGITS),
+                );
+            }
+            return inexact;
+        }
+
+        let nkeep = ((di + DEC_DIGITS - 1) / DEC_DIGITS) as usize;
+        if nkeep > self.ndigits {
+            let up = strategy.round_up(negative, false, Ordering::Less, sticky);
+            if up {
+                let pos = -rscale;
+                let one = Var::from_limb(
+                    POWERS_10[pos.rem_euclid(DEC_DIGITS) as usize],
+                    pos.div_euclid(DEC_DIGITS),
+                );
+                let sign = self.sign;
+                *self = self.abs().add_abs(&one);
+                self.sign = sign;
+                self.dscale = rscale.max(0);
+            }
+            return sticky;
+        }
+
+        let r = di % DEC_DIGITS;
+        let unit = if r == 0 {
+            1
+        } else {
+            POWERS_10[(DEC_DIGITS - r) as usize]
+        };
+        let n = self.ndigits;
+        let (inexact, carried) = strategy.round_limbs(&mut self.digits[..n], nkeep, unit, NBASE, negative, sticky);
+        self.ndigits = nkeep;
+        if carried {
+            // All kept limbs were carried over to zero, the first is now 1 at the next weight.
+            self.weight += 1;
+        }
+
+        let limbs = self.digits;
+        let weight = self.weight;
+        let n = self.ndigits;
+        self.set_limbs(&limbs[..n], weight);
+        inexact
+    }
+
+    #[inline]
+    pub(crate) fn from_limb(limb: u32, weight: i32) -> Var {
+        Var::from_limbs(&[limb], weight)
+    }
+
+    /// Creates a positive variable from limbs where the first limb has the weight `weight`.
+    #[inline]
+    pub(crate) fn from_limbs(limbs: &[u32], weight: i32) -> Var {
+        let mut var = Var::ZERO;
+        var.set_limbs(limbs, weight);
+        var
+    }
+
+    /// Creates an integral variable.
+    pub(crate) fn from_i64(value: i64) -> Var {
+        let n = value.unsigned_abs();
+        let base = NBASE as u64;
+        let limbs = [(n / base / base) as u32, (n / base % base) as u32, (n % base) as u32];
+        let mut var = Var::from_limbs(&limbs, 2);
+        if value < 0 {
+            var.sign = SIGN_NEG;
+        }
+        var
+    }
+
+    /// Converts an integral variable to `i128`, returning `None` if it doesn't fit.
+    pub(crate) fn into_i128(self) -> Option<i128> {
+        debug_assert!(self.ndigits == 0 || self.last_weight() >= 0);
+        let mut value = 0i128;
+        for w in (0..=self.weight).rev() {
+            value = value.checked_mul(NBASE as i128)?.checked_add(self.limb_at(w) as i128)?;
+        }
+        Some(if self.is_negative() { -value } else { value })
+    }
+
+    /// Converts to a decimal, rounding the digits that don't fit.
+    ///
+    /// Returns `None` if the integral part doesn't fit, otherwise the decimal and whether it is
+    /// inexact.
+    pub(crate) fn into_decimal(mut self, strategy: RoundingStrategy) -> Option<(Decimal, bool)> {
+        if self.is_nan() {
+            let mut result = Decimal {
+                ndigits: self.ndigits as u8,
+                sign: SIGN_NAN,
+                weight: self.weight as i8,
+                ..Decimal::ZERO
+            };
+            result.digits[..self.ndigits].copy_from_slice(self.limbs());
+            return Some((result, false));
+        }
+
+        let mut inexact = false;
+        if self.ndigits > 0 {
+            let e = self.exponent();
+            if e > MAX_INT_DIGITS {
+                return None;
+            }
+            // A negative scale rounds an integer beyond `MAX_PRECISION` digits to implied zeros.
+            let scale = self.dscale.min(MAX_SCALE).min(MAX_PRECISION as i32 - e);
+            inexact = self.round(scale, strategy, false);
+            if self.ndigits > 0 && self.exponent() > e {
+                // Carried into a new digit, drops a trailing zero if it no longer fits the
+                // precision. A scale limited by `MAX_SCALE` is kept.
+                if self.exponent() > MAX_INT_DIGITS {
+                    return None;
+                }
+                if self.exponent() + scale > MAX_PRECISION as i32 {
+                    self.dscale = (scale - 1).max(0);
+                }
+            }
+        }
+
+        let mut result = Decimal {
+            dscale: self.dscale.min(MAX_SCALE) as u8,
+            ..Decimal::ZERO
+        };
+        if self.ndigits > 0 {
+            result.ndigits = self.ndigits as u8;
+            result.sign = self.sign;
+            result.weight = self.weight as i8;
+            result.digits[..self.ndigits].copy_from_slice(self.limbs());
+        }
+        debug_assert!(result.is_internally_consistent(), "inconsistent decimal {:?}", self);
+        Some((result, inexact))
+    }
+}
+
+/// Divides `u` by `v` with Knuth's algorithm D, storing the quotient in `q`.
+///
+/// `u[0]` is a spare limb for normalization. Returns whether the remainder is non-zero.
+fn div_knuth(u: &mut [u32], v: &[u32], q: &mut [u32]) -> bool {
+    let base = NBASE as u64;
+    let n = v.len();
+
+    // Normalizes so that the leading limb of the divisor is at least `NBASE / 2`.
+    let d = base / (v[0] as u64 + 1);
+    let mut vn = [0u32; VAR_NDIGITS];
+    let vn = &mut vn[..n];
+    if d > 1 {
+        let mut carry = 0u64;
+        for i in (0..u.len()).rev() {
+            let t = u[i] as u64 * d + carry;
+            u[i] = (t % base) as u32;
+            carry = t / base;
+        }
+        let mut carry = 0u64;
+        for i in (0..n).rev() {
+            let t = v[i] as u64 * d + carry;
+            vn[i] = (t % base) as u32;
+            carry = t / base;
+        }
+    } else {
+        vn.copy_from_slice(v);
+    }
+
+    for j in 0..q.len() {
+        let num = u[j] as u64 * base + u[j + 1] as u64;
+        let mut qhat = num / vn[0] as u64;
+        let mut rhat = num % vn[0] as u64;
+        while qhat >= base || qhat * vn[1] as u64 > rhat * base + u[j + 2] as u64 {
+            qhat -= 1;
+            rhat += vn[0] as u64;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        // Multiplies and subtracts.
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in (0..n).rev() {
+            let p = qhat * vn[i] as u64 + carry;
+            carry = p / base;
+            let t = u[j + i + 1] as i64 - (p % base) as i64 + borrow;
+            if t < 0 {
+                u[j + i + 1] = (t + base as i64) as u32;
+                borrow = -1;
+            } else {
+                u[j + i + 1] = t as u32;
+                borrow = 0;
+            }
+        }
+        let t = u[j] as i64 - carry as i64 + borrow;
+
+        if t < 0 {
+            // Adds back.
+            qhat -= 1;
+            let mut carry = 0u32;
+            for i in (0..n).rev() {
+                let s = u[j + i + 1] + vn[i] + carry;
+                if s >= NBASE {
+                    u[j + i + 1] = s - NBASE;
+                    carry = 1;
+                } else {
+                    u[j + i + 1] = s;
+                    carry = 0;
+                }
+            }
+            u[j] = 0;
+        } else {
+            u[j] = t as u32;
+        }
+
+        q[j] = qhat as u32;
+    }
+
+    u.iter().any(|&l| l != 0)
+}